@@ -0,0 +1,424 @@
+// src-tauri/src/checksums.rs
+// File hashing with progress and verification against checksum files
+
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use md5::Md5;
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Emitter};
+
+use crate::path_policy::{check_read, FsError};
+
+/// Event emitted while hashing when the caller passes a `job_id`.
+const PROGRESS_EVENT: &str = "hash-progress";
+
+/// Minimum time between progress events.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(150);
+
+const BUFFER_SIZE: usize = 1024 * 1024;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgorithm {
+    Sha256,
+    Sha1,
+    Md5,
+    Blake3,
+}
+
+impl HashAlgorithm {
+    /// Guess from a checksum file name such as `SHA256SUMS` or `x.iso.md5`.
+    fn from_file_name(name: &str) -> Option<Self> {
+        let name = name.to_lowercase();
+        if name.contains("sha256") {
+            Some(HashAlgorithm::Sha256)
+        } else if name.contains("sha1") {
+            Some(HashAlgorithm::Sha1)
+        } else if name.contains("md5") {
+            Some(HashAlgorithm::Md5)
+        } else if name.contains("b3") || name.contains("blake3") {
+            Some(HashAlgorithm::Blake3)
+        } else {
+            None
+        }
+    }
+
+    /// Tag used by BSD-style lines: `SHA256 (file) = ...`
+    fn from_tag(tag: &str) -> Option<Self> {
+        match tag.to_uppercase().as_str() {
+            "SHA256" | "SHA2-256" => Some(HashAlgorithm::Sha256),
+            "SHA1" => Some(HashAlgorithm::Sha1),
+            "MD5" => Some(HashAlgorithm::Md5),
+            "BLAKE3" => Some(HashAlgorithm::Blake3),
+            _ => None,
+        }
+    }
+
+    /// Fallback by digest length. 64 hex digits is read as SHA-256, which is
+    /// far more common than BLAKE3 for unlabelled files.
+    fn from_digest_len(len: usize) -> Option<Self> {
+        match len {
+            64 => Some(HashAlgorithm::Sha256),
+            40 => Some(HashAlgorithm::Sha1),
+            32 => Some(HashAlgorithm::Md5),
+            _ => None,
+        }
+    }
+}
+
+enum Hasher {
+    Sha256(Sha256),
+    Sha1(Sha1),
+    Md5(Md5),
+    Blake3(Box<blake3::Hasher>),
+}
+
+impl Hasher {
+    fn new(algorithm: HashAlgorithm) -> Self {
+        match algorithm {
+            HashAlgorithm::Sha256 => Hasher::Sha256(Sha256::new()),
+            HashAlgorithm::Sha1 => Hasher::Sha1(Sha1::new()),
+            HashAlgorithm::Md5 => Hasher::Md5(Md5::new()),
+            HashAlgorithm::Blake3 => Hasher::Blake3(Box::new(blake3::Hasher::new())),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Hasher::Sha256(h) => h.update(data),
+            Hasher::Sha1(h) => h.update(data),
+            Hasher::Md5(h) => h.update(data),
+            Hasher::Blake3(h) => {
+                h.update(data);
+            }
+        }
+    }
+
+    fn finalize_hex(self) -> String {
+        let bytes: Vec<u8> = match self {
+            Hasher::Sha256(h) => h.finalize().to_vec(),
+            Hasher::Sha1(h) => h.finalize().to_vec(),
+            Hasher::Md5(h) => h.finalize().to_vec(),
+            Hasher::Blake3(h) => h.finalize().as_bytes().to_vec(),
+        };
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct FileHash {
+    path: String,
+    algorithm: HashAlgorithm,
+    /// Lowercase hex digest
+    hash: String,
+    size: u64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct HashProgress {
+    job_id: String,
+    /// File currently being hashed
+    path: String,
+    bytes_done: u64,
+    bytes_total: u64,
+    files_done: usize,
+    files_total: usize,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChecksumStatus {
+    Ok,
+    Mismatch,
+    Missing,
+    Error,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ChecksumEntry {
+    /// File name as written in the checksum file
+    name: String,
+    path: String,
+    algorithm: HashAlgorithm,
+    expected: String,
+    actual: Option<String>,
+    status: ChecksumStatus,
+    error: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ChecksumReport {
+    checksum_file: String,
+    entries: Vec<ChecksumEntry>,
+    ok: usize,
+    mismatched: usize,
+    missing: usize,
+    errors: usize,
+    /// Lines that could not be parsed as checksum entries
+    skipped_lines: usize,
+}
+
+/// Reports progress across one or more files for a single job.
+struct Progress<'a> {
+    app: &'a AppHandle,
+    job_id: Option<String>,
+    bytes_before: u64,
+    bytes_total: u64,
+    files_done: usize,
+    files_total: usize,
+    last_emit: Option<Instant>,
+}
+
+impl Progress<'_> {
+    fn report(&mut self, path: &Path, bytes_in_file: u64, force: bool) {
+        let Some(job_id) = &self.job_id else { return };
+        if !force && self.last_emit.map(|t| t.elapsed() < PROGRESS_INTERVAL).unwrap_or(false) {
+            return;
+        }
+        self.last_emit = Some(Instant::now());
+        let _ = self.app.emit(
+            PROGRESS_EVENT,
+            HashProgress {
+                job_id: job_id.clone(),
+                path: path.to_string_lossy().to_string(),
+                bytes_done: self.bytes_before + bytes_in_file,
+                bytes_total: self.bytes_total,
+                files_done: self.files_done,
+                files_total: self.files_total,
+            },
+        );
+    }
+}
+
+// ─── Hashing ──────────────────────────────────────────────────────────────────
+
+fn hash_path(path: &Path, algorithm: HashAlgorithm, progress: &mut Progress) -> Result<String, String> {
+    let mut file = fs::File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut hasher = Hasher::new(algorithm);
+    let mut buffer = vec![0u8; BUFFER_SIZE];
+    let mut done = 0u64;
+
+    loop {
+        let read = file.read(&mut buffer).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        done += read as u64;
+        progress.report(path, done, false);
+    }
+
+    progress.bytes_before += done;
+    progress.files_done += 1;
+    progress.report(path, 0, true);
+    Ok(hasher.finalize_hex())
+}
+
+// ─── Checksum files ───────────────────────────────────────────────────────────
+
+struct ParsedLine {
+    name: String,
+    digest: String,
+    tag: Option<HashAlgorithm>,
+}
+
+fn is_hex_digest(s: &str) -> bool {
+    !s.is_empty() && s.len() % 2 == 0 && s.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// Undo GNU coreutils escaping, used when a line starts with `\`.
+fn unescape_name(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    let mut chars = name.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n') => out.push('\n'),
+                Some('r') => out.push('\r'),
+                Some(other) => out.push(other),
+                None => out.push('\\'),
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Parse one line in GNU (`<hash>  <name>`, `<hash> *<name>`) or BSD
+/// (`SHA256 (<name>) = <hash>`) format.
+fn parse_line(line: &str) -> Option<ParsedLine> {
+    let line = line.trim_end_matches(['\r', '\n']);
+    let (escaped, line) = match line.strip_prefix('\\') {
+        Some(rest) => (true, rest),
+        None => (false, line),
+    };
+    let fix_name = |n: &str| if escaped { unescape_name(n) } else { n.to_string() };
+
+    // BSD tag format
+    if let Some(open) = line.find(" (") {
+        if let Some(close) = line.rfind(") = ") {
+            if close > open {
+                let digest = line[close + 4..].trim();
+                if is_hex_digest(digest) {
+                    return Some(ParsedLine {
+                        name: fix_name(&line[open + 2..close]),
+                        digest: digest.to_lowercase(),
+                        tag: HashAlgorithm::from_tag(&line[..open]),
+                    });
+                }
+            }
+        }
+    }
+
+    // GNU format: digest, one space, then a mode character (' ' text, '*' binary)
+    let (digest, rest) = line.split_once(' ')?;
+    if !is_hex_digest(digest) {
+        return None;
+    }
+    let name = rest.strip_prefix(['*', ' ']).unwrap_or(rest);
+    if name.is_empty() {
+        return None;
+    }
+    Some(ParsedLine { name: fix_name(name), digest: digest.to_lowercase(), tag: None })
+}
+
+fn resolve_entry(base: &Path, name: &str) -> PathBuf {
+    let candidate = Path::new(name);
+    if candidate.is_absolute() {
+        candidate.to_path_buf()
+    } else {
+        base.join(candidate)
+    }
+}
+
+// ─── Commands ─────────────────────────────────────────────────────────────────
+
+/// Hash a file with `algorithm` (SHA-256 by default). When `job_id` is given,
+/// `hash-progress` events report bytes hashed so far.
+#[tauri::command]
+pub async fn hash_file(
+    app: AppHandle,
+    path: String,
+    algorithm: Option<HashAlgorithm>,
+    job_id: Option<String>,
+) -> Result<FileHash, FsError> {
+    tokio::task::spawn_blocking(move || {
+        let resolved = check_read(&path)?;
+        let meta = fs::metadata(&resolved).map_err(|e| format!("Failed to get metadata: {}", e))?;
+        if meta.is_dir() {
+            return Err(format!("Cannot hash a folder: {}", path).into());
+        }
+
+        let algorithm = algorithm.unwrap_or(HashAlgorithm::Sha256);
+        let mut progress = Progress {
+            app: &app,
+            job_id,
+            bytes_before: 0,
+            bytes_total: meta.len(),
+            files_done: 0,
+            files_total: 1,
+            last_emit: None,
+        };
+        let hash = hash_path(&resolved, algorithm, &mut progress)?;
+        Ok(FileHash { path, algorithm, hash, size: meta.len() })
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Check every entry of a `sha256sum`/`md5sum`/`b3sum`-style file. Names are
+/// resolved relative to the checksum file's folder. The algorithm comes from
+/// `algorithm`, else BSD tags, the file name, or the digest length.
+#[tauri::command]
+pub async fn verify_checksums(
+    app: AppHandle,
+    checksum_file: String,
+    algorithm: Option<HashAlgorithm>,
+    job_id: Option<String>,
+) -> Result<ChecksumReport, FsError> {
+    tokio::task::spawn_blocking(move || {
+        let resolved = check_read(&checksum_file)?;
+        let content = fs::read_to_string(&resolved)
+            .map_err(|e| format!("Failed to read checksum file: {}", e))?;
+        let base = resolved.parent().map(Path::to_path_buf).unwrap_or_default();
+        let name_hint = resolved
+            .file_name()
+            .and_then(|n| HashAlgorithm::from_file_name(&n.to_string_lossy()));
+
+        let mut parsed = Vec::new();
+        let mut skipped_lines = 0;
+        for line in content.lines().filter(|l| !l.trim().is_empty() && !l.starts_with('#')) {
+            match parse_line(line) {
+                Some(p) => parsed.push(p),
+                None => skipped_lines += 1,
+            }
+        }
+        if parsed.is_empty() {
+            return Err("No checksum entries found".into());
+        }
+
+        let targets: Vec<PathBuf> = parsed.iter().map(|p| resolve_entry(&base, &p.name)).collect();
+        let mut progress = Progress {
+            app: &app,
+            job_id,
+            bytes_before: 0,
+            bytes_total: targets.iter().filter_map(|t| fs::metadata(t).ok()).map(|m| m.len()).sum(),
+            files_done: 0,
+            files_total: parsed.len(),
+            last_emit: None,
+        };
+
+        let mut entries = Vec::with_capacity(parsed.len());
+        for (line, target) in parsed.into_iter().zip(targets) {
+            let algorithm = algorithm
+                .or(line.tag)
+                .or(name_hint)
+                .or_else(|| HashAlgorithm::from_digest_len(line.digest.len()))
+                .unwrap_or(HashAlgorithm::Sha256);
+            let path = target.to_string_lossy().to_string();
+
+            let (actual, status, error) = if !target.exists() {
+                progress.files_done += 1;
+                (None, ChecksumStatus::Missing, None)
+            } else {
+                match check_read(&path)
+                    .map_err(String::from)
+                    .and_then(|p| hash_path(&p, algorithm, &mut progress))
+                {
+                    Ok(hash) if hash == line.digest => (Some(hash), ChecksumStatus::Ok, None),
+                    Ok(hash) => (Some(hash), ChecksumStatus::Mismatch, None),
+                    Err(e) => (None, ChecksumStatus::Error, Some(e)),
+                }
+            };
+
+            entries.push(ChecksumEntry {
+                name: line.name,
+                path,
+                algorithm,
+                expected: line.digest,
+                actual,
+                status,
+                error,
+            });
+        }
+
+        let count = |status| entries.iter().filter(|e| e.status == status).count();
+        Ok(ChecksumReport {
+            checksum_file,
+            ok: count(ChecksumStatus::Ok),
+            mismatched: count(ChecksumStatus::Mismatch),
+            missing: count(ChecksumStatus::Missing),
+            errors: count(ChecksumStatus::Error),
+            entries,
+            skipped_lines,
+        })
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
@@ -0,0 +1,429 @@
+// src-tauri/src/diff.rs
+// Line diffs between files and structural comparison of directory trees
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::time::Duration;
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use similar::{Algorithm, ChangeTag, TextDiff};
+
+use crate::path_policy::{check_read, FsError};
+
+/// Larger files are compared for equality but not line-diffed.
+const MAX_DIFF_BYTES: u64 = 20 * 1024 * 1024;
+const DEFAULT_CONTEXT: usize = 3;
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LineChange {
+    Unchanged,
+    Added,
+    Removed,
+}
+
+/// A run of text within a line; `changed` marks the words that differ from
+/// the paired line on the other side.
+#[derive(Serialize, Debug, Clone)]
+pub struct InlineSegment {
+    text: String,
+    changed: bool,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct DiffLine {
+    change: LineChange,
+    /// 1-based line numbers; `None` on the side the line doesn't exist
+    old_line: Option<usize>,
+    new_line: Option<usize>,
+    segments: Vec<InlineSegment>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct DiffHunk {
+    old_start: usize,
+    old_lines: usize,
+    new_start: usize,
+    new_lines: usize,
+    lines: Vec<DiffLine>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct TextDiffResult {
+    pub(crate) hunks: Vec<DiffHunk>,
+    pub(crate) added: usize,
+    pub(crate) removed: usize,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct FileDiff {
+    left: String,
+    right: String,
+    identical: bool,
+    /// Binary or oversized files are only compared for equality
+    binary: bool,
+    hunks: Vec<DiffHunk>,
+    added: usize,
+    removed: usize,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryStatus {
+    OnlyLeft,
+    OnlyRight,
+    Changed,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryKind {
+    File,
+    Dir,
+    /// Compared by target, never followed
+    Symlink,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct DirEntryDiff {
+    /// Path relative to the compared roots, using `/` separators
+    path: String,
+    status: EntryStatus,
+    is_dir: bool,
+    left_kind: Option<EntryKind>,
+    right_kind: Option<EntryKind>,
+    left_size: Option<u64>,
+    right_size: Option<u64>,
+}
+
+/// An entry that couldn't be compared, e.g. an unreadable file or folder.
+#[derive(Serialize, Debug, Clone)]
+pub struct DirEntryError {
+    path: String,
+    message: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct DirectoryDiff {
+    left: String,
+    right: String,
+    only_left: Vec<DirEntryDiff>,
+    only_right: Vec<DirEntryDiff>,
+    changed: Vec<DirEntryDiff>,
+    identical_count: usize,
+    errors: Vec<DirEntryError>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CompareMode {
+    Name,
+    Size,
+    Hash,
+}
+
+// ─── Text diffs ───────────────────────────────────────────────────────────────
+
+fn strip_line_ending(text: &str) -> &str {
+    text.strip_suffix('\n').map(|t| t.strip_suffix('\r').unwrap_or(t)).unwrap_or(text)
+}
+
+/// Diff two texts line by line, grouping changes into hunks with `context`
+/// unchanged lines around them. Changed line pairs carry word-level
+/// emphasis.
+pub(crate) fn diff_text(old: &str, new: &str, context: usize) -> TextDiffResult {
+    let diff = TextDiff::configure()
+        .algorithm(Algorithm::Patience)
+        .timeout(Duration::from_secs(2))
+        .diff_lines(old, new);
+
+    let mut hunks = Vec::new();
+    let (mut added, mut removed) = (0, 0);
+
+    for group in diff.grouped_ops(context) {
+        let (Some(first), Some(last)) = (group.first(), group.last()) else {
+            continue;
+        };
+        let mut lines = Vec::new();
+
+        for op in &group {
+            for change in diff.iter_inline_changes(op) {
+                let kind = match change.tag() {
+                    ChangeTag::Equal => LineChange::Unchanged,
+                    ChangeTag::Insert => {
+                        added += 1;
+                        LineChange::Added
+                    }
+                    ChangeTag::Delete => {
+                        removed += 1;
+                        LineChange::Removed
+                    }
+                };
+
+                let mut segments: Vec<InlineSegment> = change
+                    .iter_strings_lossy()
+                    .map(|(changed, text)| InlineSegment { text: text.to_string(), changed })
+                    .collect();
+                if let Some(last_segment) = segments.last_mut() {
+                    last_segment.text = strip_line_ending(&last_segment.text).to_string();
+                }
+                segments.retain(|s| !s.text.is_empty());
+
+                lines.push(DiffLine {
+                    change: kind,
+                    old_line: change.old_index().map(|i| i + 1),
+                    new_line: change.new_index().map(|i| i + 1),
+                    segments,
+                });
+            }
+        }
+
+        let (old_start, old_end) = (first.old_range().start, last.old_range().end);
+        let (new_start, new_end) = (first.new_range().start, last.new_range().end);
+        hunks.push(DiffHunk {
+            old_start: old_start + 1,
+            old_lines: old_end - old_start,
+            new_start: new_start + 1,
+            new_lines: new_end - new_start,
+            lines,
+        });
+    }
+
+    TextDiffResult { hunks, added, removed }
+}
+
+fn looks_binary(bytes: &[u8]) -> bool {
+    bytes.iter().take(8192).any(|&b| b == 0)
+}
+
+// ─── Directory comparison ─────────────────────────────────────────────────────
+
+fn hash_file(path: &Path) -> Result<[u8; 32], String> {
+    let mut file = fs::File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher.finalize().into())
+}
+
+struct EntryMeta {
+    kind: EntryKind,
+    size: u64,
+}
+
+impl EntryMeta {
+    fn is_dir(&self) -> bool {
+        self.kind == EntryKind::Dir
+    }
+
+    fn file_size(&self) -> Option<u64> {
+        (self.kind == EntryKind::File).then_some(self.size)
+    }
+}
+
+fn list_dir(dir: &Path) -> Result<BTreeMap<String, EntryMeta>, String> {
+    let mut entries = BTreeMap::new();
+    for entry in fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))? {
+        let entry = entry.map_err(|e| e.to_string())?;
+        // Symlinks are compared as entries, not followed, so loops can't recurse forever
+        let meta = match fs::symlink_metadata(entry.path()) {
+            Ok(m) => m,
+            Err(_) => continue,
+        };
+        let kind = if meta.file_type().is_symlink() {
+            EntryKind::Symlink
+        } else if meta.is_dir() {
+            EntryKind::Dir
+        } else {
+            EntryKind::File
+        };
+        entries.insert(
+            entry.file_name().to_string_lossy().to_string(),
+            EntryMeta { kind, size: if kind == EntryKind::File { meta.len() } else { 0 } },
+        );
+    }
+    Ok(entries)
+}
+
+/// Whether two same-named entries differ; entries of different kinds always do.
+fn entries_differ(left: &Path, right: &Path, l: &EntryMeta, r: &EntryMeta, mode: CompareMode) -> Result<bool, String> {
+    if l.kind != r.kind {
+        return Ok(true);
+    }
+    match (l.kind, mode) {
+        (_, CompareMode::Name) => Ok(false),
+        (EntryKind::Symlink, _) => {
+            let target = |p: &Path| fs::read_link(p).map_err(|e| format!("Failed to read link {}: {}", p.display(), e));
+            Ok(target(left)? != target(right)?)
+        }
+        (_, CompareMode::Size) => Ok(l.size != r.size),
+        (_, CompareMode::Hash) => {
+            if l.size != r.size {
+                return Ok(true);
+            }
+            Ok(hash_file(left)? != hash_file(right)?)
+        }
+    }
+}
+
+fn compare_dirs(
+    left: &Path,
+    right: &Path,
+    relative: &str,
+    mode: CompareMode,
+    result: &mut DirectoryDiff,
+) -> Result<(), String> {
+    let (left_entries, right_entries) = match (list_dir(left), list_dir(right)) {
+        (Ok(l), Ok(r)) => (l, r),
+        // An unreadable subfolder is reported; only the roots are fatal
+        (Err(message), _) | (_, Err(message)) if !relative.is_empty() => {
+            result.errors.push(DirEntryError { path: relative.to_string(), message });
+            return Ok(());
+        }
+        (Err(e), _) | (_, Err(e)) => return Err(e),
+    };
+    let join = |name: &str| if relative.is_empty() { name.to_string() } else { format!("{}/{}", relative, name) };
+
+    for (name, l) in &left_entries {
+        let path = join(name);
+        match right_entries.get(name) {
+            None => result.only_left.push(DirEntryDiff {
+                path,
+                status: EntryStatus::OnlyLeft,
+                is_dir: l.is_dir(),
+                left_kind: Some(l.kind),
+                right_kind: None,
+                left_size: l.file_size(),
+                right_size: None,
+            }),
+            Some(r) if l.is_dir() && r.is_dir() => {
+                compare_dirs(&left.join(name), &right.join(name), &path, mode, result)?;
+            }
+            Some(r) => match entries_differ(&left.join(name), &right.join(name), l, r, mode) {
+                Ok(true) => result.changed.push(DirEntryDiff {
+                    path,
+                    status: EntryStatus::Changed,
+                    is_dir: l.is_dir(),
+                    left_kind: Some(l.kind),
+                    right_kind: Some(r.kind),
+                    left_size: l.file_size(),
+                    right_size: r.file_size(),
+                }),
+                Ok(false) => result.identical_count += 1,
+                Err(message) => result.errors.push(DirEntryError { path, message }),
+            },
+        }
+    }
+
+    for (name, r) in &right_entries {
+        if !left_entries.contains_key(name) {
+            result.only_right.push(DirEntryDiff {
+                path: join(name),
+                status: EntryStatus::OnlyRight,
+                is_dir: r.is_dir(),
+                left_kind: None,
+                right_kind: Some(r.kind),
+                left_size: None,
+                right_size: r.file_size(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+// ─── Commands ─────────────────────────────────────────────────────────────────
+
+/// Structured line diff of two files. `context` is the number of unchanged
+/// lines kept around each change (default 3).
+#[tauri::command]
+pub async fn diff_files(left: String, right: String, context: Option<usize>) -> Result<FileDiff, FsError> {
+    tokio::task::spawn_blocking(move || {
+        let left_path = check_read(&left)?;
+        let right_path = check_read(&right)?;
+
+        let too_large = |p: &Path| fs::metadata(p).map(|m| m.len() > MAX_DIFF_BYTES).unwrap_or(false);
+        let oversized = too_large(&left_path) || too_large(&right_path);
+
+        let (left_bytes, right_bytes) = if oversized {
+            (Vec::new(), Vec::new())
+        } else {
+            (
+                fs::read(&left_path).map_err(|e| format!("Failed to read {}: {}", left, e))?,
+                fs::read(&right_path).map_err(|e| format!("Failed to read {}: {}", right, e))?,
+            )
+        };
+
+        let binary = oversized || looks_binary(&left_bytes) || looks_binary(&right_bytes);
+        if binary {
+            let identical = if oversized {
+                hash_file(&left_path)? == hash_file(&right_path)?
+            } else {
+                left_bytes == right_bytes
+            };
+            return Ok(FileDiff { left, right, identical, binary, hunks: Vec::new(), added: 0, removed: 0 });
+        }
+
+        let old = String::from_utf8_lossy(&left_bytes);
+        let new = String::from_utf8_lossy(&right_bytes);
+        let result = diff_text(&old, &new, context.unwrap_or(DEFAULT_CONTEXT));
+
+        Ok(FileDiff {
+            left,
+            right,
+            identical: left_bytes == right_bytes,
+            binary: false,
+            hunks: result.hunks,
+            added: result.added,
+            removed: result.removed,
+        })
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Compare two directory trees. `compare` is `"name"` (presence only),
+/// `"size"` or `"hash"` (size, then SHA-256 when sizes match; the default).
+/// Only differing entries are listed; folders present on one side only are
+/// reported once rather than file by file. Symlinks are compared by target
+/// without being followed; entries that can't be read are listed in `errors`
+/// instead of failing the comparison.
+#[tauri::command]
+pub async fn diff_directories(left: String, right: String, compare: Option<String>) -> Result<DirectoryDiff, FsError> {
+    tokio::task::spawn_blocking(move || {
+        let mode = match compare.as_deref() {
+            Some("name") => CompareMode::Name,
+            Some("size") => CompareMode::Size,
+            None | Some("hash") => CompareMode::Hash,
+            Some(other) => return Err(format!("Unknown comparison mode: {}", other).into()),
+        };
+
+        let left_path = check_read(&left)?;
+        let right_path = check_read(&right)?;
+        if !left_path.is_dir() || !right_path.is_dir() {
+            return Err("Both paths must be directories".into());
+        }
+
+        let mut result = DirectoryDiff {
+            left,
+            right,
+            only_left: Vec::new(),
+            only_right: Vec::new(),
+            changed: Vec::new(),
+            identical_count: 0,
+            errors: Vec::new(),
+        };
+        compare_dirs(&left_path, &right_path, "", mode, &mut result)?;
+        Ok(result)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
@@ -0,0 +1,169 @@
+// src-tauri/src/disk_space.rs
+// Capacity of the filesystem holding a path, and pre-flight space checks for copies
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct DiskSpace {
+    path: String,
+    total: u64,
+    free: u64,
+    /// Free bytes usable by the current user; lower than `free` when the
+    /// filesystem reserves blocks for root.
+    pub(crate) available: u64,
+    filesystem: Option<String>,
+}
+
+/// Nearest existing ancestor of `path`, so space can be queried for a
+/// destination that hasn't been created yet.
+fn existing_ancestor(path: &Path) -> Option<PathBuf> {
+    path.ancestors().find(|p| p.exists()).map(Path::to_path_buf)
+}
+
+/// Returns `(total, free, available)` bytes.
+#[cfg(unix)]
+pub(crate) fn query_space(path: &Path) -> Result<(u64, u64, u64), String> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = CString::new(path.as_os_str().as_bytes())
+        .map_err(|_| "Path contains a NUL byte".to_string())?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return Err(format!("Failed to query disk space: {}", std::io::Error::last_os_error()));
+    }
+    let fragment = stat.f_frsize as u64;
+    Ok((
+        stat.f_blocks as u64 * fragment,
+        stat.f_bfree as u64 * fragment,
+        stat.f_bavail as u64 * fragment,
+    ))
+}
+
+#[cfg(target_os = "windows")]
+fn to_wide(path: &Path) -> Vec<u16> {
+    use std::os::windows::ffi::OsStrExt;
+    path.as_os_str().encode_wide().chain(std::iter::once(0)).collect()
+}
+
+#[cfg(target_os = "windows")]
+pub(crate) fn query_space(path: &Path) -> Result<(u64, u64, u64), String> {
+    use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let wide = to_wide(path);
+    let (mut available, mut total, mut free) = (0u64, 0u64, 0u64);
+    let ok = unsafe { GetDiskFreeSpaceExW(wide.as_ptr(), &mut available, &mut total, &mut free) };
+    if ok == 0 {
+        return Err(format!("Failed to query disk space: {}", std::io::Error::last_os_error()));
+    }
+    Ok((total, free, available))
+}
+
+#[cfg(target_os = "linux")]
+fn filesystem_type(path: &Path) -> Option<String> {
+    crate::volumes::filesystem_type_for(path)
+}
+
+#[cfg(target_os = "windows")]
+fn filesystem_type(path: &Path) -> Option<String> {
+    use windows_sys::Win32::Storage::FileSystem::{GetVolumeInformationW, GetVolumePathNameW};
+
+    let wide = to_wide(path);
+    let mut root = [0u16; 261];
+    if unsafe { GetVolumePathNameW(wide.as_ptr(), root.as_mut_ptr(), root.len() as u32) } == 0 {
+        return None;
+    }
+    let mut fs_name = [0u16; 64];
+    let ok = unsafe {
+        GetVolumeInformationW(
+            root.as_ptr(),
+            std::ptr::null_mut(),
+            0,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            fs_name.as_mut_ptr(),
+            fs_name.len() as u32,
+        )
+    };
+    if ok == 0 {
+        return None;
+    }
+    let len = fs_name.iter().position(|&c| c == 0).unwrap_or(fs_name.len());
+    Some(String::from_utf16_lossy(&fs_name[..len]))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+fn filesystem_type(_path: &Path) -> Option<String> {
+    None
+}
+
+pub(crate) fn disk_space(path: &Path) -> Result<DiskSpace, String> {
+    let target = existing_ancestor(path).ok_or_else(|| format!("Path not found: {}", path.display()))?;
+    let (total, free, available) = query_space(&target)?;
+    Ok(DiskSpace {
+        path: target.to_string_lossy().to_string(),
+        total,
+        free,
+        available,
+        filesystem: filesystem_type(&target),
+    })
+}
+
+/// Total bytes of the files under `path` (or of `path` itself for a file).
+/// Symlinks are not followed, matching how copies treat them.
+pub(crate) fn required_bytes(path: &Path) -> Result<u64, String> {
+    let meta = fs::symlink_metadata(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    if !meta.is_dir() {
+        return Ok(meta.len());
+    }
+    let mut total = 0;
+    for entry in fs::read_dir(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))? {
+        let entry = entry.map_err(|e| e.to_string())?;
+        total += required_bytes(&entry.path())?;
+    }
+    Ok(total)
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+/// Fail early if copying `source` into `dest_folder` cannot fit.
+pub(crate) fn ensure_space_for_copy(source: &Path, dest_folder: &Path) -> Result<(), String> {
+    ensure_space(required_bytes(source)?, dest_folder)
+}
+
+/// Fail early if `required` bytes cannot fit in `dest_folder`.
+pub(crate) fn ensure_space(required: u64, dest_folder: &Path) -> Result<(), String> {
+    let space = disk_space(dest_folder)?;
+    if required > space.available {
+        return Err(format!(
+            "Not enough space on destination: {} needed, {} available",
+            format_bytes(required),
+            format_bytes(space.available)
+        ));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_disk_space(path: String) -> Result<DiskSpace, String> {
+    tokio::task::spawn_blocking(move || disk_space(Path::new(&path)))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
@@ -0,0 +1,411 @@
+// src-tauri/src/editorconfig.rs
+// Resolving `.editorconfig` rules for a file and applying them when saving
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+use serde::Serialize;
+
+use crate::path_policy::{check_read, check_write, FsError};
+
+const CONFIG_FILE_NAME: &str = ".editorconfig";
+
+/// Effective settings for one file. `None` means no rule applies and the
+/// editor's own setting is used.
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct EditorConfigSettings {
+    /// `"tab"` or `"space"`
+    indent_style: Option<String>,
+    indent_size: Option<u32>,
+    tab_width: Option<u32>,
+    /// `"lf"`, `"crlf"` or `"cr"`
+    end_of_line: Option<String>,
+    /// `"utf-8"`, `"utf-8-bom"`, `"utf-16le"`, `"utf-16be"` or `"latin1"`
+    charset: Option<String>,
+    trim_trailing_whitespace: Option<bool>,
+    insert_final_newline: Option<bool>,
+    max_line_length: Option<u32>,
+    /// `.editorconfig` files with a matching section, outermost first
+    sources: Vec<String>,
+}
+
+struct Section {
+    pattern: String,
+    properties: Vec<(String, String)>,
+}
+
+struct ConfigFile {
+    path: PathBuf,
+    root: bool,
+    sections: Vec<Section>,
+}
+
+fn parse(path: PathBuf, text: &str) -> ConfigFile {
+    let mut file = ConfigFile { path, root: false, sections: Vec::new() };
+
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if line.starts_with('[') && line.ends_with(']') {
+            file.sections.push(Section {
+                pattern: line[1..line.len() - 1].to_string(),
+                properties: Vec::new(),
+            });
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else { continue };
+        let key = key.trim().to_lowercase();
+        // Values of the known properties are case-insensitive
+        let value = value.trim().to_lowercase();
+        match file.sections.last_mut() {
+            Some(section) => section.properties.push((key, value)),
+            None if key == "root" => file.root = value == "true",
+            None => {}
+        }
+    }
+    file
+}
+
+/// Index of the `}` closing the brace group that starts before `from`.
+fn find_closing_brace(chars: &[char], from: usize) -> Option<usize> {
+    let mut depth = 0;
+    let mut i = from;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 1,
+            '{' => depth += 1,
+            '}' if depth == 0 => return Some(i),
+            '}' => depth -= 1,
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Split `{a,b{c,d}}` contents at top-level commas.
+fn split_alternatives(chars: &[char]) -> Vec<&[char]> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 1,
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(&chars[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    parts.push(&chars[start..]);
+    parts
+}
+
+/// Translate an EditorConfig glob to a regex. Numeric ranges (`{1..3}`)
+/// become capture groups, checked against `ranges` after matching.
+fn glob_to_regex(chars: &[char], ranges: &mut Vec<(i64, i64)>) -> String {
+    let mut out = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' if i + 1 < chars.len() => {
+                out.push_str(&regex::escape(&chars[i + 1].to_string()));
+                i += 1;
+            }
+            '*' if chars.get(i + 1) == Some(&'*') => {
+                if chars.get(i + 2) == Some(&'/') {
+                    // `a/**/b` also matches `a/b`
+                    out.push_str("(?:.*/)?");
+                    i += 2;
+                } else {
+                    out.push_str(".*");
+                    i += 1;
+                }
+            }
+            '*' => out.push_str("[^/]*"),
+            '?' => out.push_str("[^/]"),
+            '[' => match chars[i + 1..].iter().position(|&c| c == ']').map(|p| i + 1 + p) {
+                Some(end) if !chars[i + 1..end].contains(&'/') => {
+                    let mut class: &[char] = &chars[i + 1..end];
+                    out.push('[');
+                    if class.first() == Some(&'!') {
+                        out.push('^');
+                        class = &class[1..];
+                    }
+                    for &c in class {
+                        if matches!(c, '\\' | '[' | ']' | '&' | '~' | '^') {
+                            out.push('\\');
+                        }
+                        out.push(c);
+                    }
+                    out.push(']');
+                    i = end;
+                }
+                _ => out.push_str("\\["),
+            },
+            '{' => match find_closing_brace(chars, i + 1) {
+                Some(end) => {
+                    let inner = &chars[i + 1..end];
+                    let text: String = inner.iter().collect();
+                    let range = text
+                        .split_once("..")
+                        .and_then(|(a, b)| Some((a.parse::<i64>().ok()?, b.parse::<i64>().ok()?)));
+                    let alternatives = split_alternatives(inner);
+                    if let Some((a, b)) = range {
+                        ranges.push((a.min(b), a.max(b)));
+                        out.push_str(r"([+-]?\d+)");
+                    } else if alternatives.len() > 1 {
+                        let parts: Vec<String> =
+                            alternatives.iter().map(|alt| glob_to_regex(alt, ranges)).collect();
+                        out.push_str(&format!("(?:{})", parts.join("|")));
+                    } else {
+                        // `{single}` is literal
+                        out.push_str(&regex::escape(&format!("{{{}}}", text)));
+                    }
+                    i = end;
+                }
+                None => out.push_str("\\{"),
+            },
+            c => out.push_str(&regex::escape(&c.to_string())),
+        }
+        i += 1;
+    }
+    out
+}
+
+/// Whether the section `pattern` of the config in `dir` applies to `file`.
+/// Both paths use forward slashes.
+fn section_matches(pattern: &str, dir: &str, file: &str) -> bool {
+    let dir = dir.trim_end_matches('/');
+    // Patterns without a slash match the file name at any depth
+    let (prefix, glob) = if pattern.contains('/') {
+        (format!("{}/", regex::escape(dir)), pattern.trim_start_matches('/'))
+    } else {
+        (format!("{}/(?:.*/)?", regex::escape(dir)), pattern)
+    };
+    let chars: Vec<char> = glob.chars().collect();
+    let mut ranges = Vec::new();
+    let body = glob_to_regex(&chars, &mut ranges);
+    let Ok(re) = Regex::new(&format!("^{}{}$", prefix, body)) else {
+        return false;
+    };
+    let Some(caps) = re.captures(file) else { return false };
+    ranges.iter().enumerate().all(|(n, (low, high))| match caps.get(n + 1) {
+        Some(m) => m.as_str().parse::<i64>().is_ok_and(|v| v >= *low && v <= *high),
+        // The range was in an alternative that didn't match
+        None => true,
+    })
+}
+
+fn slashes(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// Config files that apply to `file`, nearest first, up to the first
+/// `root = true`.
+fn config_files(file: &Path) -> Vec<ConfigFile> {
+    let mut files = Vec::new();
+    for dir in file.ancestors().skip(1) {
+        let path = dir.join(CONFIG_FILE_NAME);
+        if !path.is_file() {
+            continue;
+        }
+        match fs::read_to_string(&path) {
+            Ok(text) => {
+                let config = parse(path, &text);
+                let root = config.root;
+                files.push(config);
+                if root {
+                    break;
+                }
+            }
+            Err(e) => log::warn!("editorconfig: failed to read {}: {}", path.display(), e),
+        }
+    }
+    files
+}
+
+fn parse_size(value: Option<&String>) -> Option<u32> {
+    value.and_then(|v| v.parse().ok()).filter(|&v| v > 0)
+}
+
+fn parse_bool(value: Option<&String>) -> Option<bool> {
+    match value.map(String::as_str) {
+        Some("true") => Some(true),
+        Some("false") => Some(false),
+        _ => None,
+    }
+}
+
+fn one_of(value: Option<&String>, allowed: &[&str]) -> Option<String> {
+    value.filter(|v| allowed.contains(&v.as_str())).cloned()
+}
+
+/// Effective settings for `file` (which need not exist). Nearer config files
+/// and later sections win; `unset` clears a property.
+pub(crate) fn resolve(file: &Path) -> EditorConfigSettings {
+    let file_path = slashes(file);
+    let mut properties: HashMap<String, String> = HashMap::new();
+    let mut sources = Vec::new();
+
+    for config in config_files(file).iter().rev() {
+        let dir = config.path.parent().map(slashes).unwrap_or_default();
+        let mut matched = false;
+        for section in &config.sections {
+            if !section_matches(&section.pattern, &dir, &file_path) {
+                continue;
+            }
+            matched = true;
+            for (key, value) in &section.properties {
+                if value == "unset" {
+                    properties.remove(key);
+                } else {
+                    properties.insert(key.clone(), value.clone());
+                }
+            }
+        }
+        if matched {
+            sources.push(config.path.to_string_lossy().to_string());
+        }
+    }
+
+    let indent_style = one_of(properties.get("indent_style"), &["tab", "space"]);
+    let explicit_tab_width = parse_size(properties.get("tab_width"));
+    let indent_size = match properties.get("indent_size").map(String::as_str) {
+        Some("tab") => explicit_tab_width,
+        Some(_) => parse_size(properties.get("indent_size")),
+        None if indent_style.as_deref() == Some("tab") => explicit_tab_width,
+        None => None,
+    };
+    let tab_width = explicit_tab_width.or(indent_size);
+
+    EditorConfigSettings {
+        indent_style,
+        indent_size,
+        tab_width,
+        end_of_line: one_of(properties.get("end_of_line"), &["lf", "crlf", "cr"]),
+        charset: one_of(
+            properties.get("charset"),
+            &["utf-8", "utf-8-bom", "utf-16le", "utf-16be", "latin1"],
+        ),
+        trim_trailing_whitespace: parse_bool(properties.get("trim_trailing_whitespace")),
+        insert_final_newline: parse_bool(properties.get("insert_final_newline")),
+        max_line_length: parse_size(properties.get("max_line_length")),
+        sources,
+    }
+}
+
+fn encode(text: &str, charset: Option<&str>) -> Result<Vec<u8>, String> {
+    match charset {
+        Some("utf-8-bom") => Ok([&[0xEF, 0xBB, 0xBF][..], text.as_bytes()].concat()),
+        Some("utf-16le") => Ok([0xFF, 0xFE]
+            .into_iter()
+            .chain(text.encode_utf16().flat_map(u16::to_le_bytes))
+            .collect()),
+        Some("utf-16be") => Ok([0xFE, 0xFF]
+            .into_iter()
+            .chain(text.encode_utf16().flat_map(u16::to_be_bytes))
+            .collect()),
+        Some("latin1") => text
+            .chars()
+            .map(|c| {
+                u8::try_from(u32::from(c))
+                    .map_err(|_| format!("Character '{}' cannot be saved as latin1", c))
+            })
+            .collect(),
+        _ => Ok(text.as_bytes().to_vec()),
+    }
+}
+
+/// Decode file bytes: a BOM decides the encoding, then UTF-8, then the
+/// `charset` setting for files that aren't UTF-8.
+pub(crate) fn decode(bytes: &[u8], charset: Option<&str>) -> Result<String, String> {
+    let utf16 = |data: &[u8], from: fn([u8; 2]) -> u16| {
+        let units: Vec<u16> = data.chunks_exact(2).map(|c| from([c[0], c[1]])).collect();
+        String::from_utf16(&units).map_err(|e| format!("Invalid UTF-16: {}", e))
+    };
+    match bytes {
+        [0xEF, 0xBB, 0xBF, rest @ ..] => String::from_utf8(rest.to_vec()).map_err(|e| e.to_string()),
+        [0xFF, 0xFE, rest @ ..] => utf16(rest, u16::from_le_bytes),
+        [0xFE, 0xFF, rest @ ..] => utf16(rest, u16::from_be_bytes),
+        _ => match std::str::from_utf8(bytes) {
+            Ok(text) => Ok(text.to_string()),
+            Err(_) if charset == Some("latin1") => Ok(bytes.iter().map(|&b| char::from(b)).collect()),
+            Err(e) => Err(format!("File is not valid UTF-8: {}", e)),
+        },
+    }
+}
+
+/// Apply line ending, whitespace, final newline and charset rules to text
+/// being saved and return the bytes to write.
+pub(crate) fn apply(settings: &EditorConfigSettings, content: &str) -> Result<Vec<u8>, String> {
+    let text = content.strip_prefix('\u{feff}').unwrap_or(content);
+
+    // Lines with their original terminator ("" for the last)
+    let mut lines: Vec<(&str, &str)> = Vec::new();
+    let bytes = text.as_bytes();
+    let (mut start, mut i) = (0, 0);
+    while i < bytes.len() {
+        let end = match bytes[i] {
+            b'\r' if bytes.get(i + 1) == Some(&b'\n') => i + 2,
+            b'\r' | b'\n' => i + 1,
+            _ => {
+                i += 1;
+                continue;
+            }
+        };
+        lines.push((&text[start..i], &text[i..end]));
+        start = end;
+        i = end;
+    }
+    lines.push((&text[start..], ""));
+
+    let configured = match settings.end_of_line.as_deref() {
+        Some("lf") => Some("\n"),
+        Some("crlf") => Some("\r\n"),
+        Some("cr") => Some("\r"),
+        _ => None,
+    };
+    let detected = lines.iter().map(|(_, sep)| *sep).find(|sep| !sep.is_empty()).unwrap_or("\n");
+    let trim = settings.trim_trailing_whitespace == Some(true);
+
+    let mut out = String::with_capacity(text.len() + 1);
+    for (line, sep) in &lines {
+        out.push_str(if trim { line.trim_end_matches([' ', '\t']) } else { line });
+        if !sep.is_empty() {
+            out.push_str(configured.unwrap_or(sep));
+        }
+    }
+
+    match settings.insert_final_newline {
+        Some(true) if !out.is_empty() && !out.ends_with(['\n', '\r']) => {
+            out.push_str(configured.unwrap_or(detected));
+        }
+        Some(false) => out.truncate(out.trim_end_matches(['\n', '\r']).len()),
+        _ => {}
+    }
+
+    encode(&out, settings.charset.as_deref())
+}
+
+// ─── Commands ─────────────────────────────────────────────────────────────────
+
+/// EditorConfig settings for a file, which need not exist yet.
+#[tauri::command]
+pub async fn resolve_editorconfig(path: String) -> Result<EditorConfigSettings, FsError> {
+    tokio::task::spawn_blocking(move || {
+        let file = check_read(&path).or_else(|_| check_write(&path))?;
+        Ok(resolve(&file))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
@@ -0,0 +1,64 @@
+// src-tauri/src/ffmpeg.rs
+// Locating and launching the FFmpeg binaries shared by the media commands
+
+use std::process::Command;
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+/// Find an FFmpeg suite binary (`ffmpeg`, `ffprobe`) on PATH or in the
+/// usual Windows install locations.
+fn find_ff_binary(name: &str) -> Option<String> {
+    let mut check_cmd = Command::new(name);
+    check_cmd.arg("-version")
+        .stderr(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null());
+
+    #[cfg(target_os = "windows")]
+    {
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        check_cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    if check_cmd.status().is_ok() {
+        return Some(name.to_string());
+    }
+
+    let common_dirs = vec![
+        "C:\\ffmpeg",
+        "C:\\ffmpeg\\bin",
+        "C:\\Program Files\\ffmpeg\\bin",
+        "C:\\Program Files (x86)\\ffmpeg\\bin",
+    ];
+
+    for dir in common_dirs {
+        let path = std::path::Path::new(dir).join(format!("{}.exe", name));
+        if path.exists() {
+            return Some(path.to_string_lossy().to_string());
+        }
+    }
+
+    None
+}
+
+pub(crate) fn find_ffmpeg() -> Result<String, String> {
+    find_ff_binary("ffmpeg")
+        .ok_or_else(|| "FFmpeg not found. Please ensure FFmpeg is installed and in your PATH.".to_string())
+}
+
+pub(crate) fn find_ffprobe() -> Result<String, String> {
+    find_ff_binary("ffprobe")
+        .ok_or_else(|| "FFprobe not found. Please ensure FFmpeg is installed and in your PATH.".to_string())
+}
+
+pub(crate) fn create_ffmpeg_command(ffmpeg_path: &str) -> Command {
+    let mut cmd = Command::new(ffmpeg_path);
+
+    #[cfg(target_os = "windows")]
+    {
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    cmd
+}
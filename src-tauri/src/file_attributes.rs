@@ -0,0 +1,509 @@
+// src-tauri/src/file_attributes.rs
+// Changing permissions, ownership and timestamps of files and folders
+
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+use crate::path_policy::{check_modify, check_write, FsError};
+
+#[derive(Serialize, Debug, Clone)]
+pub struct AttributeFailure {
+    path: String,
+    message: String,
+}
+
+/// Outcome of a metadata change. Recursive changes keep going past entries
+/// that fail and list them in `failed`.
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct AttributeChange {
+    changed: usize,
+    failed: Vec<AttributeFailure>,
+}
+
+impl AttributeChange {
+    fn record(&mut self, path: &Path, result: Result<(), String>) {
+        match result {
+            Ok(()) => self.changed += 1,
+            Err(message) => self.failed.push(AttributeFailure {
+                path: path.to_string_lossy().to_string(),
+                message,
+            }),
+        }
+    }
+}
+
+/// Visit `path` and, when `recursive`, everything below it. Symlinks are not
+/// followed: changing a link's mode or owner would change its target.
+fn for_each_entry(
+    path: &Path,
+    recursive: bool,
+    change: &mut AttributeChange,
+    apply: &mut dyn FnMut(&Path, &fs::Metadata) -> Result<(), String>,
+) {
+    let meta = match fs::symlink_metadata(path) {
+        Ok(m) => m,
+        Err(e) => return change.record(path, Err(format!("Failed to get metadata: {}", e))),
+    };
+    if meta.file_type().is_symlink() {
+        return;
+    }
+    change.record(path, apply(path, &meta));
+
+    if recursive && meta.is_dir() {
+        match fs::read_dir(path) {
+            Ok(entries) => {
+                for entry in entries.flatten() {
+                    for_each_entry(&entry.path(), true, change, apply);
+                }
+            }
+            Err(e) => change.record(path, Err(format!("Failed to read directory: {}", e))),
+        }
+    }
+}
+
+/// Resolve `path` for a metadata change; it must already exist. Only the
+/// parent is resolved, so a symlink stays the link rather than its target
+/// and `for_each_entry` skips it like a nested one.
+fn existing_target(path: &str) -> Result<std::path::PathBuf, FsError> {
+    check_modify(path)
+}
+
+/// Single-entry changes (executable bit, times) refuse a symlink outright,
+/// since applying them would change its target.
+fn reject_symlink(path: &Path) -> Result<(), FsError> {
+    match fs::symlink_metadata(path) {
+        Ok(meta) if meta.file_type().is_symlink() => {
+            Err(format!("{} is a symbolic link; links are not followed", path.display()).into())
+        }
+        _ => Ok(()),
+    }
+}
+
+// ─── Mode bits ────────────────────────────────────────────────────────────────
+
+/// A mode given as octal (`755`, `0644`) or symbolic (`u+x,go-w`, `a=rX`).
+#[cfg(unix)]
+enum ModeSpec {
+    Octal(u32),
+    Symbolic(String),
+}
+
+#[cfg(unix)]
+impl ModeSpec {
+    fn parse(spec: &str) -> Result<Self, String> {
+        let spec = spec.trim();
+        if spec.is_empty() {
+            return Err("Mode is empty".to_string());
+        }
+        if spec.bytes().all(|b| (b'0'..=b'7').contains(&b)) {
+            if spec.len() > 4 {
+                return Err(format!("Invalid octal mode: {}", spec));
+            }
+            return u32::from_str_radix(spec, 8)
+                .map(ModeSpec::Octal)
+                .map_err(|e| format!("Invalid octal mode {}: {}", spec, e));
+        }
+        let parsed = ModeSpec::Symbolic(spec.to_string());
+        // Validate up front so a bad spec fails before anything is changed
+        parsed.apply(0, false)?;
+        Ok(parsed)
+    }
+
+    /// New mode for an entry whose current mode is `mode`.
+    fn apply(&self, mode: u32, is_dir: bool) -> Result<u32, String> {
+        match self {
+            ModeSpec::Octal(bits) => Ok((mode & !0o7777) | bits),
+            ModeSpec::Symbolic(spec) => apply_symbolic(mode, spec, is_dir),
+        }
+    }
+}
+
+/// Apply a `chmod`-style symbolic spec. A clause without `u`/`g`/`o`/`a`
+/// applies to everyone (the umask is not consulted).
+#[cfg(unix)]
+fn apply_symbolic(mut mode: u32, spec: &str, is_dir: bool) -> Result<u32, String> {
+    for clause in spec.split(',') {
+        let mut chars = clause.chars().peekable();
+
+        let mut who = 0u32;
+        while let Some(&c) = chars.peek() {
+            who |= match c {
+                'u' => 0o4700,
+                'g' => 0o2070,
+                'o' => 0o1007,
+                'a' => 0o7777,
+                _ => break,
+            };
+            chars.next();
+        }
+        if who == 0 {
+            who = 0o7777;
+        }
+
+        let mut applied = false;
+        while let Some(op) = chars.next() {
+            if !matches!(op, '+' | '-' | '=') {
+                return Err(format!("Invalid mode clause '{}': expected +, - or =", clause));
+            }
+            let mut perms = 0u32;
+            while let Some(&c) = chars.peek() {
+                perms |= match c {
+                    'r' => 0o444,
+                    'w' => 0o222,
+                    'x' => 0o111,
+                    'X' if is_dir || mode & 0o111 != 0 => 0o111,
+                    'X' => 0,
+                    's' => 0o6000,
+                    't' => 0o1000,
+                    '+' | '-' | '=' => break,
+                    other => return Err(format!("Invalid permission '{}' in '{}'", other, clause)),
+                };
+                chars.next();
+            }
+            let bits = perms & who;
+            mode = match op {
+                '+' => mode | bits,
+                '-' => mode & !bits,
+                _ => (mode & !who) | bits,
+            };
+            applied = true;
+        }
+        if !applied {
+            return Err(format!("Invalid mode clause '{}'", clause));
+        }
+    }
+    Ok(mode)
+}
+
+/// `rwxr-xr-x` style rendering of permission bits, including setuid,
+/// setgid and sticky.
+#[cfg(unix)]
+pub(crate) fn mode_string(mode: u32) -> String {
+    let triplet = |shift: u32, special: u32, special_char: char| {
+        let bits = (mode >> shift) & 0o7;
+        let exec = bits & 0o1 != 0;
+        [
+            if bits & 0o4 != 0 { 'r' } else { '-' },
+            if bits & 0o2 != 0 { 'w' } else { '-' },
+            match (mode & special != 0, exec) {
+                (true, true) => special_char,
+                (true, false) => special_char.to_ascii_uppercase(),
+                (false, true) => 'x',
+                (false, false) => '-',
+            },
+        ]
+    };
+    triplet(6, 0o4000, 's')
+        .into_iter()
+        .chain(triplet(3, 0o2000, 's'))
+        .chain(triplet(0, 0o1000, 't'))
+        .collect()
+}
+
+#[cfg(unix)]
+fn set_mode(path: &Path, mode: u32) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(mode & 0o7777))
+        .map_err(|e| format!("Failed to set permissions: {}", e))
+}
+
+// ─── Owners ───────────────────────────────────────────────────────────────────
+
+#[cfg(unix)]
+const LOOKUP_BUFFER: usize = 16 * 1024;
+
+#[cfg(unix)]
+fn c_string(value: &str) -> Result<std::ffi::CString, String> {
+    std::ffi::CString::new(value).map_err(|_| format!("Invalid name: {}", value))
+}
+
+#[cfg(unix)]
+fn lookup_uid(user: &str) -> Result<u32, String> {
+    if let Ok(uid) = user.parse() {
+        return Ok(uid);
+    }
+    let name = c_string(user)?;
+    let mut pwd: libc::passwd = unsafe { std::mem::zeroed() };
+    let mut buf = vec![0 as libc::c_char; LOOKUP_BUFFER];
+    let mut result = std::ptr::null_mut();
+    let rc = unsafe { libc::getpwnam_r(name.as_ptr(), &mut pwd, buf.as_mut_ptr(), buf.len(), &mut result) };
+    if rc != 0 || result.is_null() {
+        return Err(format!("Unknown user: {}", user));
+    }
+    Ok(pwd.pw_uid)
+}
+
+#[cfg(unix)]
+fn lookup_gid(group: &str) -> Result<u32, String> {
+    if let Ok(gid) = group.parse() {
+        return Ok(gid);
+    }
+    let name = c_string(group)?;
+    let mut grp: libc::group = unsafe { std::mem::zeroed() };
+    let mut buf = vec![0 as libc::c_char; LOOKUP_BUFFER];
+    let mut result = std::ptr::null_mut();
+    let rc = unsafe { libc::getgrnam_r(name.as_ptr(), &mut grp, buf.as_mut_ptr(), buf.len(), &mut result) };
+    if rc != 0 || result.is_null() {
+        return Err(format!("Unknown group: {}", group));
+    }
+    Ok(grp.gr_gid)
+}
+
+/// User name for `uid`, or the number when it has no passwd entry.
+#[cfg(unix)]
+pub(crate) fn user_name(uid: u32) -> String {
+    let mut pwd: libc::passwd = unsafe { std::mem::zeroed() };
+    let mut buf = vec![0 as libc::c_char; LOOKUP_BUFFER];
+    let mut result = std::ptr::null_mut();
+    let rc = unsafe { libc::getpwuid_r(uid, &mut pwd, buf.as_mut_ptr(), buf.len(), &mut result) };
+    if rc != 0 || result.is_null() {
+        return uid.to_string();
+    }
+    unsafe { std::ffi::CStr::from_ptr(pwd.pw_name) }.to_string_lossy().to_string()
+}
+
+/// Group name for `gid`, or the number when it has no group entry.
+#[cfg(unix)]
+pub(crate) fn group_name(gid: u32) -> String {
+    let mut grp: libc::group = unsafe { std::mem::zeroed() };
+    let mut buf = vec![0 as libc::c_char; LOOKUP_BUFFER];
+    let mut result = std::ptr::null_mut();
+    let rc = unsafe { libc::getgrgid_r(gid, &mut grp, buf.as_mut_ptr(), buf.len(), &mut result) };
+    if rc != 0 || result.is_null() {
+        return gid.to_string();
+    }
+    unsafe { std::ffi::CStr::from_ptr(grp.gr_name) }.to_string_lossy().to_string()
+}
+
+// ─── Timestamps ───────────────────────────────────────────────────────────────
+
+fn to_system_time(secs: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(secs)
+}
+
+#[cfg(unix)]
+fn set_times(path: &Path, modified: Option<SystemTime>, accessed: Option<SystemTime>) -> Result<(), String> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let to_timespec = |time: Option<SystemTime>| match time.and_then(|t| t.duration_since(UNIX_EPOCH).ok()) {
+        Some(d) => libc::timespec { tv_sec: d.as_secs() as libc::time_t, tv_nsec: d.subsec_nanos() as _ },
+        None => libc::timespec { tv_sec: 0, tv_nsec: libc::UTIME_OMIT },
+    };
+    // utimensat works on folders and on files we can't open for reading
+    let times = [to_timespec(accessed), to_timespec(modified)];
+    let c_path = std::ffi::CString::new(path.as_os_str().as_bytes())
+        .map_err(|_| "Path contains a NUL byte".to_string())?;
+    if unsafe { libc::utimensat(libc::AT_FDCWD, c_path.as_ptr(), times.as_ptr(), 0) } != 0 {
+        return Err(format!("Failed to set times: {}", std::io::Error::last_os_error()));
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn set_times(path: &Path, modified: Option<SystemTime>, accessed: Option<SystemTime>) -> Result<(), String> {
+    use std::os::windows::fs::OpenOptionsExt;
+
+    const FILE_WRITE_ATTRIBUTES: u32 = 0x0100;
+    // Needed to open a handle to a directory
+    const FILE_FLAG_BACKUP_SEMANTICS: u32 = 0x0200_0000;
+
+    let file = fs::OpenOptions::new()
+        .access_mode(FILE_WRITE_ATTRIBUTES)
+        .custom_flags(FILE_FLAG_BACKUP_SEMANTICS)
+        .open(path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut times = fs::FileTimes::new();
+    if let Some(m) = modified {
+        times = times.set_modified(m);
+    }
+    if let Some(a) = accessed {
+        times = times.set_accessed(a);
+    }
+    file.set_times(times).map_err(|e| format!("Failed to set times: {}", e))
+}
+
+// ─── Commands ─────────────────────────────────────────────────────────────────
+
+/// Change permission bits, `chmod`-style. `mode` is octal (`755`) or
+/// symbolic (`u+x,go-w`, `a+rX`).
+#[tauri::command]
+pub async fn set_permissions(path: String, mode: String, recursive: Option<bool>) -> Result<AttributeChange, FsError> {
+    tokio::task::spawn_blocking(move || {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            let target = existing_target(&path)?;
+            let spec = ModeSpec::parse(&mode)?;
+            let mut change = AttributeChange::default();
+            for_each_entry(&target, recursive.unwrap_or(false), &mut change, &mut |p, meta| {
+                let current = meta.permissions().mode();
+                let next = spec.apply(current, meta.is_dir())?;
+                if next & 0o7777 == current & 0o7777 {
+                    return Ok(());
+                }
+                set_mode(p, next)
+            });
+            Ok(change)
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = (path, mode, recursive);
+            Err("Permission bits are not supported on this platform; use set_readonly".into())
+        }
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Add or remove execute permission. Adding grants it to each class that can
+/// already read the file, like `chmod +X` for readers.
+#[tauri::command]
+pub async fn set_executable(path: String, executable: bool) -> Result<AttributeChange, FsError> {
+    tokio::task::spawn_blocking(move || {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            let target = existing_target(&path)?;
+            reject_symlink(&target)?;
+            let current = fs::metadata(&target)
+                .map_err(|e| format!("Failed to get metadata: {}", e))?
+                .permissions()
+                .mode();
+            let next = if executable {
+                current | ((current & 0o444) >> 2)
+            } else {
+                current & !0o111
+            };
+            let mut change = AttributeChange::default();
+            change.record(&target, set_mode(&target, next));
+            Ok(change)
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = (path, executable);
+            Err("The executable bit is not supported on this platform".into())
+        }
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Toggle read-only. On Unix this removes every write bit, or restores
+/// owner write.
+#[tauri::command]
+pub async fn set_readonly(path: String, readonly: bool, recursive: Option<bool>) -> Result<AttributeChange, FsError> {
+    tokio::task::spawn_blocking(move || {
+        let target = existing_target(&path)?;
+        let mut change = AttributeChange::default();
+        for_each_entry(&target, recursive.unwrap_or(false), &mut change, &mut |p, meta| {
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                let current = meta.permissions().mode();
+                let next = if readonly { current & !0o222 } else { current | 0o200 };
+                set_mode(p, next)
+            }
+            #[cfg(not(unix))]
+            {
+                let mut permissions = meta.permissions();
+                permissions.set_readonly(readonly);
+                fs::set_permissions(p, permissions).map_err(|e| format!("Failed to set permissions: {}", e))
+            }
+        });
+        Ok(change)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Change owner and/or group (names or numeric ids). Changing the owner
+/// normally requires root; a user may change the group to one they belong to.
+#[tauri::command]
+pub async fn set_owner(
+    path: String,
+    owner: Option<String>,
+    group: Option<String>,
+    recursive: Option<bool>,
+) -> Result<AttributeChange, FsError> {
+    tokio::task::spawn_blocking(move || {
+        #[cfg(unix)]
+        {
+            let owner = owner.filter(|o| !o.trim().is_empty());
+            let group = group.filter(|g| !g.trim().is_empty());
+            if owner.is_none() && group.is_none() {
+                return Err("Specify an owner or a group".into());
+            }
+            let uid = owner.as_deref().map(|o| lookup_uid(o.trim())).transpose()?;
+            let gid = group.as_deref().map(|g| lookup_gid(g.trim())).transpose()?;
+
+            let target = existing_target(&path)?;
+            let mut change = AttributeChange::default();
+            for_each_entry(&target, recursive.unwrap_or(false), &mut change, &mut |p, _| {
+                std::os::unix::fs::lchown(p, uid, gid).map_err(|e| {
+                    if e.kind() == std::io::ErrorKind::PermissionDenied {
+                        "Permission denied: changing ownership requires administrator rights".to_string()
+                    } else {
+                        format!("Failed to change owner: {}", e)
+                    }
+                })
+            });
+            Ok(change)
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = (path, owner, group, recursive);
+            Err("Changing ownership is not supported on this platform".into())
+        }
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Set modified and/or accessed times (seconds since the Unix epoch).
+/// Omitted times are left unchanged.
+#[tauri::command]
+pub async fn set_file_times(
+    path: String,
+    modified: Option<u64>,
+    accessed: Option<u64>,
+) -> Result<(), FsError> {
+    tokio::task::spawn_blocking(move || {
+        let target = existing_target(&path)?;
+        reject_symlink(&target)?;
+        set_times(&target, modified.map(to_system_time), accessed.map(to_system_time))?;
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Set both times to now, creating an empty file when `path` doesn't exist.
+#[tauri::command]
+pub async fn touch_file(path: String) -> Result<(), FsError> {
+    tokio::task::spawn_blocking(move || {
+        // An existing entry is resolved like any other change, keeping links unfollowed
+        let target = match fs::symlink_metadata(&path) {
+            Ok(_) => existing_target(&path)?,
+            Err(_) => check_write(&path)?,
+        };
+        reject_symlink(&target)?;
+        if !target.exists() {
+            fs::OpenOptions::new()
+                .create(true)
+                .truncate(false)
+                .write(true)
+                .open(&target)
+                .map_err(|e| format!("Failed to create file: {}", e))?;
+        }
+        let now = SystemTime::now();
+        set_times(&target, Some(now), Some(now))?;
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
@@ -0,0 +1,316 @@
+// src-tauri/src/file_index.rs
+// In-memory index of the files under a workspace's roots, with fuzzy lookup
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::{Match, WalkBuilder};
+use serde::Serialize;
+
+/// Indexing stops here; lookups then only cover part of the workspace.
+pub(crate) const MAX_INDEXED_FILES: usize = 200_000;
+
+struct IndexedFile {
+    /// Index into `FileIndex::roots`
+    root: usize,
+    /// `/`-separated path relative to its root
+    rel_path: String,
+    /// `rel_path` lowercased char by char, so char positions line up
+    lower: String,
+}
+
+struct IndexRoot {
+    path: PathBuf,
+    /// `.git/info/exclude`, which ranks below every `.gitignore`
+    exclude: Gitignore,
+}
+
+/// Watcher events are filtered with the same rules the walk applies: each
+/// folder's `.gitignore` and `.ignore`, the repository's exclude file and the
+/// global excludes file.
+pub(crate) struct FileIndex {
+    roots: Vec<IndexRoot>,
+    files: BTreeMap<PathBuf, IndexedFile>,
+    pub(crate) truncated: bool,
+    /// Per-folder ignore files, read as events under each folder arrive
+    dir_ignores: HashMap<PathBuf, Gitignore>,
+    global_ignore: Gitignore,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct FileMatch {
+    path: String,
+    rel_path: String,
+    root: String,
+    score: i64,
+    /// Char positions in `rel_path` matched by the query, for highlighting
+    positions: Vec<usize>,
+}
+
+fn lowercase_aligned(s: &str) -> String {
+    s.chars().map(|c| c.to_lowercase().next().unwrap_or(c)).collect()
+}
+
+fn repo_exclude(root: &Path) -> Gitignore {
+    let mut builder = GitignoreBuilder::new(root);
+    builder.add(root.join(".git").join("info").join("exclude"));
+    builder.build().unwrap_or_else(|_| Gitignore::empty())
+}
+
+/// A folder's own rules; `.ignore` is added last so it overrides `.gitignore`.
+fn dir_ignore(dir: &Path) -> Gitignore {
+    let mut builder = GitignoreBuilder::new(dir);
+    builder.add(dir.join(".gitignore"));
+    builder.add(dir.join(".ignore"));
+    builder.build().unwrap_or_else(|_| Gitignore::empty())
+}
+
+fn is_ignore_file(path: &Path) -> bool {
+    path.file_name().is_some_and(|name| name == ".gitignore" || name == ".ignore")
+}
+
+impl FileIndex {
+    /// Walk `roots`, skipping hidden and git-ignored files.
+    pub(crate) fn build(roots: &[PathBuf]) -> Self {
+        let mut index = FileIndex {
+            roots: roots
+                .iter()
+                .map(|path| IndexRoot {
+                    path: path.clone(),
+                    exclude: repo_exclude(path),
+                })
+                .collect(),
+            files: BTreeMap::new(),
+            truncated: false,
+            dir_ignores: HashMap::new(),
+            global_ignore: Gitignore::global().0,
+        };
+        for root in roots {
+            index.add_tree(root);
+        }
+        index
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    fn root_of(&self, path: &Path) -> Option<(usize, String)> {
+        // Nested roots: the innermost one wins
+        self.roots
+            .iter()
+            .enumerate()
+            .filter_map(|(i, root)| path.strip_prefix(&root.path).ok().map(|rel| (i, rel)))
+            .min_by_key(|(_, rel)| rel.components().count())
+            .map(|(i, rel)| {
+                let rel_path = rel
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                (i, rel_path)
+            })
+    }
+
+    fn insert(&mut self, path: &Path) {
+        if self.files.len() >= MAX_INDEXED_FILES {
+            self.truncated = true;
+            return;
+        }
+        if let Some((root, rel_path)) = self.root_of(path) {
+            let lower = lowercase_aligned(&rel_path);
+            self.files.insert(path.to_path_buf(), IndexedFile { root, rel_path, lower });
+        }
+    }
+
+    fn add_tree(&mut self, dir: &Path) {
+        for entry in WalkBuilder::new(dir).build().flatten() {
+            if entry.file_type().map(|t| t.is_file()).unwrap_or(false) {
+                self.insert(entry.path());
+                if self.truncated {
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Whether a watcher event for `path` concerns indexed files at all.
+    fn is_indexable(&mut self, path: &Path, is_dir: bool) -> bool {
+        let Some((root, rel_path)) = self.root_of(path) else { return false };
+        if rel_path.split('/').any(|part| part.starts_with('.')) {
+            return false;
+        }
+
+        // The deepest folder with a matching rule decides, as in the walk
+        let root_path = self.roots[root].path.clone();
+        for dir in path.ancestors().skip(1).take_while(|dir| dir.starts_with(&root_path)) {
+            let ignore = self.dir_ignores.entry(dir.to_path_buf()).or_insert_with(|| dir_ignore(dir));
+            match ignore.matched_path_or_any_parents(path, is_dir) {
+                Match::Ignore(_) => return false,
+                Match::Whitelist(_) => return true,
+                Match::None => {}
+            }
+        }
+        if self.roots[root].exclude.matched_path_or_any_parents(path, is_dir).is_ignore() {
+            return false;
+        }
+
+        // Global patterns are relative; check the path and each parent folder
+        let parts: Vec<&str> = rel_path.split('/').collect();
+        !(1..=parts.len()).any(|n| {
+            self.global_ignore
+                .matched(parts[..n].join("/"), n < parts.len() || is_dir)
+                .is_ignore()
+        })
+    }
+
+    /// Bring the entries for `path` (a file or folder) in line with the disk
+    /// after a filesystem event. Returns whether the index changed.
+    pub(crate) fn refresh_path(&mut self, path: &Path) -> bool {
+        if is_ignore_file(path) {
+            // Re-read on the next event under that folder
+            if let Some(dir) = path.parent() {
+                self.dir_ignores.remove(dir);
+            }
+            return false;
+        }
+        let before = self.files.len();
+        let meta = std::fs::symlink_metadata(path).ok();
+        match meta {
+            Some(meta) if meta.is_file() => {
+                if self.files.contains_key(path) || !self.is_indexable(path, false) {
+                    return false;
+                }
+                self.insert(path);
+                true
+            }
+            Some(meta) if meta.is_dir() => {
+                if !self.is_indexable(path, true) {
+                    return false;
+                }
+                self.add_tree(path);
+                self.files.len() != before
+            }
+            _ => {
+                // Gone: drop the file, or everything below a removed folder
+                let removed: Vec<PathBuf> = self
+                    .files
+                    .range(path.to_path_buf()..)
+                    .take_while(|(p, _)| p.starts_with(path))
+                    .map(|(p, _)| p.clone())
+                    .collect();
+                for p in &removed {
+                    self.files.remove(p);
+                }
+                !removed.is_empty()
+            }
+        }
+    }
+
+    /// Best matches for `query`, highest score first. An empty query lists
+    /// the shallowest files.
+    pub(crate) fn search(&self, query: &str, limit: usize) -> Vec<FileMatch> {
+        let query: Vec<char> = lowercase_aligned(query).chars().filter(|c| !c.is_whitespace()).collect();
+        let mut scored: Vec<(i64, Vec<usize>, &PathBuf, &IndexedFile)> = Vec::new();
+
+        for (path, file) in &self.files {
+            if query.is_empty() {
+                let depth = file.rel_path.matches('/').count() as i64;
+                scored.push((-(depth * 1000 + file.rel_path.len() as i64), Vec::new(), path, file));
+            } else if let Some((score, positions)) = fuzzy_score(&query, file) {
+                scored.push((score, positions, path, file));
+            }
+        }
+
+        scored.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.3.rel_path.cmp(&b.3.rel_path)));
+        scored
+            .into_iter()
+            .take(limit)
+            .map(|(score, positions, path, file)| FileMatch {
+                path: path.to_string_lossy().to_string(),
+                rel_path: file.rel_path.clone(),
+                root: self.roots[file.root].path.to_string_lossy().to_string(),
+                score,
+                positions,
+            })
+            .collect()
+    }
+}
+
+// ─── Fuzzy matching ───────────────────────────────────────────────────────────
+
+const SCORE_MATCH: i64 = 16;
+const BONUS_CONSECUTIVE: i64 = 24;
+const BONUS_SEGMENT_START: i64 = 32;
+const BONUS_CAMEL: i64 = 24;
+const BONUS_IN_FILE_NAME: i64 = 12;
+const PENALTY_GAP: i64 = 3;
+
+/// Score `query` (lowercased) as a subsequence of the file's path. The
+/// leftmost-ending match is found first and then tightened from its end, so
+/// the matched chars are as close together as possible; matches at word
+/// starts and inside the file name score higher.
+fn fuzzy_score(query: &[char], file: &IndexedFile) -> Option<(i64, Vec<usize>)> {
+    let lower: Vec<char> = file.lower.chars().collect();
+    if query.len() > lower.len() {
+        return None;
+    }
+
+    let mut qi = 0;
+    let mut end = 0;
+    for (i, &c) in lower.iter().enumerate() {
+        if c == query[qi] {
+            qi += 1;
+            if qi == query.len() {
+                end = i;
+                break;
+            }
+        }
+    }
+    if qi < query.len() {
+        return None;
+    }
+
+    // Walk back from the end to find the tightest window
+    let mut positions = Vec::with_capacity(query.len());
+    let mut qi = query.len();
+    for i in (0..=end).rev() {
+        if lower[i] == query[qi - 1] {
+            positions.push(i);
+            qi -= 1;
+            if qi == 0 {
+                break;
+            }
+        }
+    }
+    positions.reverse();
+
+    let original: Vec<char> = file.rel_path.chars().collect();
+    let name_start = file.rel_path.rfind('/').map(|i| file.rel_path[..=i].chars().count()).unwrap_or(0);
+    let mut score = 0;
+    for (n, &pos) in positions.iter().enumerate() {
+        score += SCORE_MATCH;
+        let prev = if pos > 0 { Some(original[pos - 1]) } else { None };
+        match prev {
+            None => score += BONUS_SEGMENT_START,
+            Some(p) if matches!(p, '/' | '\\' | '_' | '-' | '.' | ' ') => score += BONUS_SEGMENT_START,
+            Some(p) if p.is_lowercase() && original[pos].is_uppercase() => score += BONUS_CAMEL,
+            _ => {}
+        }
+        if n > 0 {
+            let gap = (pos - positions[n - 1] - 1) as i64;
+            if gap == 0 {
+                score += BONUS_CONSECUTIVE;
+            } else {
+                score -= PENALTY_GAP * gap.min(10);
+            }
+        }
+        if pos >= name_start {
+            score += BONUS_IN_FILE_NAME;
+        }
+    }
+    // Prefer shorter paths among otherwise equal matches
+    score -= (lower.len() as i64) / 8;
+    Some((score, positions))
+}
@@ -1,11 +1,19 @@
 // src-tauri/src/file_settings.rs
 // Commands for file manager settings functionality
 
+use std::collections::hash_map::DefaultHasher;
 use std::fs;
-use std::path::PathBuf;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Cache budget in MB applied automatically whenever the media caches grow.
+/// Kept in sync with the `fileThumbnailCacheSize` setting by the frontend.
+static CACHE_LIMIT_MB: AtomicU64 = AtomicU64::new(500);
 
 /// Get the thumbnail cache directory path using app data directory
-fn get_cache_dir() -> Result<PathBuf, String> {
+pub(crate) fn get_cache_dir() -> Result<PathBuf, String> {
     // Use platform-specific cache directory
     let cache_dir = dirs::cache_dir()
         .ok_or_else(|| "Failed to get cache directory".to_string())?;
@@ -22,7 +30,7 @@ fn get_cache_dir() -> Result<PathBuf, String> {
 }
 
 /// Get the video preview cache directory path
-fn get_preview_cache_dir() -> Result<PathBuf, String> {
+pub(crate) fn get_preview_cache_dir() -> Result<PathBuf, String> {
     let cache_dir = dirs::cache_dir()
         .ok_or_else(|| "Failed to get cache directory".to_string())?;
     
@@ -36,16 +44,66 @@ fn get_preview_cache_dir() -> Result<PathBuf, String> {
     Ok(preview_cache)
 }
 
+/// Get the cache directory for videos transcoded into a web-playable format
+pub(crate) fn get_transcode_cache_dir() -> Result<PathBuf, String> {
+    let cache_dir = dirs::cache_dir()
+        .ok_or_else(|| "Failed to get cache directory".to_string())?;
+    
+    let transcode_cache = cache_dir.join("com.devtoolkit.app").join("video_transcodes");
+    
+    if !transcode_cache.exists() {
+        fs::create_dir_all(&transcode_cache)
+            .map_err(|e| format!("Failed to create transcode cache dir: {}", e))?;
+    }
+    
+    Ok(transcode_cache)
+}
+
+/// Every cache directory counted, cleared and trimmed by the commands below
+fn managed_cache_dirs() -> Result<Vec<PathBuf>, String> {
+    Ok(vec![get_cache_dir()?, get_preview_cache_dir()?, get_transcode_cache_dir()?])
+}
+
+/// Content address for a cache entry derived from a source file.
+///
+/// Combines the source path, its size and modification time with any
+/// generation parameters, so a changed source or different settings never
+/// hit a stale entry and identical requests always map to the same file.
+pub(crate) fn cache_key<P: Hash>(source: &Path, params: P) -> Result<String, String> {
+    let meta = fs::metadata(source).map_err(|e| format!("Failed to get metadata: {}", e))?;
+    let modified = meta
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+
+    let mut hasher = DefaultHasher::new();
+    source.hash(&mut hasher);
+    meta.len().hash(&mut hasher);
+    modified.hash(&mut hasher);
+    params.hash(&mut hasher);
+
+    Ok(format!("{:016x}", hasher.finish()))
+}
+
+/// Mark a cache entry as recently used so size enforcement evicts it last
+pub(crate) fn touch_cache_entry(path: &Path) {
+    if let Ok(file) = fs::File::options().write(true).open(path) {
+        let _ = file.set_modified(SystemTime::now());
+    }
+}
+
 /// Calculate total size of thumbnail cache in bytes
 #[tauri::command]
 pub async fn get_thumbnail_cache_size() -> Result<u64, String> {
-    let thumbnail_dir = get_cache_dir()?;
-    let preview_dir = get_preview_cache_dir()?;
+    let mut total_size = 0;
     
-    let thumbnail_size = calculate_dir_size(&thumbnail_dir)?;
-    let preview_size = calculate_dir_size(&preview_dir)?;
+    for dir in managed_cache_dirs()? {
+        total_size += calculate_dir_size(&dir)?;
+    }
     
-    Ok(thumbnail_size + preview_size)
+    Ok(total_size)
 }
 
 /// Calculate directory size recursively
@@ -74,20 +132,13 @@ fn calculate_dir_size(path: &PathBuf) -> Result<u64, String> {
     Ok(total_size)
 }
 
-/// Clear all thumbnail and preview cache files
+/// Clear all thumbnail, preview and transcode cache files
 #[tauri::command]
 pub async fn clear_thumbnail_cache() -> Result<(), String> {
-    let thumbnail_dir = get_cache_dir()?;
-    let preview_dir = get_preview_cache_dir()?;
-    
-    // Clear thumbnail cache
-    if thumbnail_dir.exists() {
-        clear_directory(&thumbnail_dir)?;
-    }
-    
-    // Clear video preview cache
-    if preview_dir.exists() {
-        clear_directory(&preview_dir)?;
+    for dir in managed_cache_dirs()? {
+        if dir.exists() {
+            clear_directory(&dir)?;
+        }
     }
     
     Ok(())
@@ -149,12 +200,36 @@ pub async fn count_files_in_directory(path: String) -> Result<u64, String> {
 /// Enforce cache size limit by removing oldest files
 #[tauri::command]
 pub async fn enforce_cache_limit(max_size_mb: u64) -> Result<(), String> {
-    let max_size_bytes = max_size_mb * 1024 * 1024;
-    let thumbnail_dir = get_cache_dir()?;
-    let preview_dir = get_preview_cache_dir()?;
+    tokio::task::spawn_blocking(move || enforce_cache_limit_bytes(max_size_mb * 1024 * 1024, None))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Set the cache budget applied automatically as thumbnails, previews and
+/// transcodes are written
+#[tauri::command]
+pub fn set_thumbnail_cache_limit(max_size_mb: u64) {
+    CACHE_LIMIT_MB.store(max_size_mb, Ordering::Relaxed);
+}
+
+/// Trim the managed caches to the configured budget, never evicting `keep`
+/// (typically the entry that was just written and is about to be used).
+pub(crate) fn enforce_configured_cache_limit(keep: Option<&Path>) {
+    let max_size_bytes = CACHE_LIMIT_MB.load(Ordering::Relaxed) * 1024 * 1024;
+    if let Err(e) = enforce_cache_limit_bytes(max_size_bytes, keep) {
+        log::warn!("Failed to enforce cache limit: {}", e);
+    }
+}
+
+/// Blocking implementation of `enforce_cache_limit`
+fn enforce_cache_limit_bytes(max_size_bytes: u64, keep: Option<&Path>) -> Result<(), String> {
+    let dirs = managed_cache_dirs()?;
     
     // Get current size
-    let current_size = calculate_dir_size(&thumbnail_dir)? + calculate_dir_size(&preview_dir)?;
+    let mut current_size = 0;
+    for dir in &dirs {
+        current_size += calculate_dir_size(dir)?;
+    }
     
     if current_size <= max_size_bytes {
         return Ok(());
@@ -163,8 +238,15 @@ pub async fn enforce_cache_limit(max_size_mb: u64) -> Result<(), String> {
     // Collect all cache files with their metadata
     let mut files: Vec<(PathBuf, u64, std::time::SystemTime)> = Vec::new();
     
-    collect_files_with_metadata(&thumbnail_dir, &mut files)?;
-    collect_files_with_metadata(&preview_dir, &mut files)?;
+    for dir in &dirs {
+        collect_files_with_metadata(dir, &mut files)?;
+    }
+    // In-progress `*.part.*` outputs count toward the total but are never
+    // evicted; removing one would make its writer's final rename fail.
+    files.retain(|(path, _, _)| {
+        let in_progress = path.file_name().is_some_and(|n| n.to_string_lossy().contains(".part."));
+        !in_progress && Some(path.as_path()) != keep
+    });
     
     // Sort by modified time (oldest first)
     files.sort_by(|a, b| a.2.cmp(&b.2));
@@ -1,13 +1,29 @@
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::process::Command;
 use std::time::UNIX_EPOCH;
 use serde::Serialize;
-use tauri::command;
+use tauri::{command, AppHandle, Manager};
 use base64::{Engine as _, engine::general_purpose};
 
+#[cfg(target_os = "windows")]
+use crate::disk_space::query_space;
+use crate::disk_space::ensure_space_for_copy;
+use crate::editorconfig;
+use crate::ffmpeg::{create_ffmpeg_command, find_ffmpeg};
+use crate::file_settings::{
+    cache_key, enforce_configured_cache_limit, get_preview_cache_dir, touch_cache_entry,
+};
+#[cfg(unix)]
+use crate::file_attributes;
+use crate::history_commands;
+use crate::history_db::HistoryDb;
+use crate::path_policy::{check_modify, check_read, check_write, validate_name, FsError};
+use crate::thumbnails;
+
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
+#[cfg(target_os = "windows")]
+use std::process::Command;
 
 #[derive(Serialize, Debug, Clone)]
 pub struct FileEntry {
@@ -20,10 +36,14 @@ pub struct FileEntry {
 
 #[derive(Serialize, Debug, Clone)]
 pub struct DriveInfo {
-    letter: String,
-    path: String,
-    label: Option<String>,
-    drive_type: String,
+    pub(crate) letter: String,
+    pub(crate) path: String,
+    pub(crate) label: Option<String>,
+    pub(crate) drive_type: String,
+    pub(crate) filesystem: Option<String>,
+    pub(crate) device: Option<String>,
+    pub(crate) total_space: Option<u64>,
+    pub(crate) free_space: Option<u64>,
 }
 
 const MAX_FILES_FOR_SIZE_CALC: usize = 1000;
@@ -59,7 +79,7 @@ fn calculate_dir_size(path: &Path) -> Option<u64> {
     }
 }
 
-fn get_unique_path(path: PathBuf) -> PathBuf {
+pub(crate) fn get_unique_path(path: PathBuf) -> PathBuf {
     if !path.exists() {
         return path;
     }
@@ -79,50 +99,6 @@ fn get_unique_path(path: PathBuf) -> PathBuf {
     }
 }
 
-fn find_ffmpeg() -> Result<String, String> {
-    let mut check_cmd = Command::new("ffmpeg");
-    check_cmd.arg("-version")
-        .stderr(std::process::Stdio::null())
-        .stdout(std::process::Stdio::null());
-    
-    #[cfg(target_os = "windows")]
-    {
-        const CREATE_NO_WINDOW: u32 = 0x08000000;
-        check_cmd.creation_flags(CREATE_NO_WINDOW);
-    }
-    
-    if check_cmd.status().is_ok() {
-        return Ok("ffmpeg".to_string());
-    }
-    
-    let common_paths = vec![
-        "C:\\ffmpeg\\ffmpeg.exe",
-        "C:\\ffmpeg\\bin\\ffmpeg.exe",
-        "C:\\Program Files\\ffmpeg\\bin\\ffmpeg.exe",
-        "C:\\Program Files (x86)\\ffmpeg\\bin\\ffmpeg.exe",
-    ];
-    
-    for path in common_paths {
-        if std::path::Path::new(path).exists() {
-            return Ok(path.to_string());
-        }
-    }
-    
-    Err("FFmpeg not found. Please ensure FFmpeg is installed and in your PATH.".to_string())
-}
-
-fn create_ffmpeg_command(ffmpeg_path: &str) -> Command {
-    let mut cmd = Command::new(ffmpeg_path);
-    
-    #[cfg(target_os = "windows")]
-    {
-        const CREATE_NO_WINDOW: u32 = 0x08000000;
-        cmd.creation_flags(CREATE_NO_WINDOW);
-    }
-    
-    cmd
-}
-
 #[command]
 pub async fn get_available_drives() -> Result<Vec<DriveInfo>, String> {
     tokio::task::spawn_blocking(|| {
@@ -156,24 +132,38 @@ pub async fn get_available_drives() -> Result<Vec<DriveInfo>, String> {
                     };
                     
                     let drive_type = get_drive_type(&drive_path);
+                    let space = query_space(Path::new(&drive_path)).ok();
                     
                     drives.push(DriveInfo {
                         letter: drive_letter,
                         path: drive_path,
                         label,
                         drive_type,
+                        filesystem: None,
+                        device: None,
+                        total_space: space.map(|(total, _, _)| total),
+                        free_space: space.map(|(_, _, available)| available),
                     });
                 }
             }
         }
         
-        #[cfg(not(target_os = "windows"))]
+        #[cfg(target_os = "linux")]
+        {
+            drives.extend(crate::volumes::list_volumes()?);
+        }
+
+        #[cfg(not(any(target_os = "windows", target_os = "linux")))]
         {
             drives.push(DriveInfo {
                 letter: "/".to_string(),
                 path: "/".to_string(),
                 label: Some("Root".to_string()),
                 drive_type: "fixed".to_string(),
+                filesystem: None,
+                device: None,
+                total_space: None,
+                free_space: None,
             });
         }
         
@@ -205,10 +195,11 @@ fn get_drive_type(path: &str) -> String {
 }
 
 #[command]
-pub async fn read_directory(path: String) -> Result<Vec<FileEntry>, String> {
+pub async fn read_directory(path: String) -> Result<Vec<FileEntry>, FsError> {
     tokio::task::spawn_blocking(move || {
+        let dir = check_read(&path)?;
         let mut entries = Vec::new();
-        let paths = fs::read_dir(&path).map_err(|e| e.to_string())?;
+        let paths = fs::read_dir(&dir).map_err(|e| e.to_string())?;
 
         for path_result in paths {
             if let Ok(entry) = path_result {
@@ -253,29 +244,28 @@ pub async fn read_directory(path: String) -> Result<Vec<FileEntry>, String> {
 }
 
 #[command]
-pub async fn get_directory_size(path: String) -> Result<Option<u64>, String> {
+pub async fn get_directory_size(path: String) -> Result<Option<u64>, FsError> {
     tokio::task::spawn_blocking(move || {
-        let p = Path::new(&path);
+        let p = check_read(&path)?;
         if !p.is_dir() {
-            return Err("Path is not a directory".to_string());
+            return Err("Path is not a directory".into());
         }
-        Ok(calculate_dir_size(p))
+        Ok(calculate_dir_size(&p))
     })
     .await
     .map_err(|e| format!("Task join error: {}", e))?
 }
 
 #[command]
-pub async fn get_directory_sizes(paths: Vec<String>) -> Result<Vec<(String, Option<u64>)>, String> {
+pub async fn get_directory_sizes(paths: Vec<String>) -> Result<Vec<(String, Option<u64>)>, FsError> {
     tokio::task::spawn_blocking(move || {
         let results: Vec<(String, Option<u64>)> = paths
             .into_iter()
             .map(|path| {
-                let p = Path::new(&path);
-                let size = if p.is_dir() {
-                    calculate_dir_size(p)
-                } else {
-                    None
+                // Paths outside policy just report no size, like non-directories
+                let size = match check_read(&path) {
+                    Ok(p) if p.is_dir() => calculate_dir_size(&p),
+                    _ => None,
                 };
                 (path, size)
             })
@@ -287,92 +277,128 @@ pub async fn get_directory_sizes(paths: Vec<String>) -> Result<Vec<(String, Opti
 }
 
 #[command]
-pub async fn delete_item(path: String) -> Result<(), String> {
+pub async fn delete_item(path: String) -> Result<(), FsError> {
     tokio::task::spawn_blocking(move || {
-        let p = Path::new(&path);
-        if p.is_dir() {
-            fs::remove_dir_all(&path).map_err(|e| e.to_string())
+        let p = check_modify(&path)?;
+        let is_dir = fs::symlink_metadata(&p).map(|m| m.is_dir()).unwrap_or(false);
+        if is_dir {
+            fs::remove_dir_all(&p).map_err(|e| e.to_string())?;
         } else {
-            fs::remove_file(&path).map_err(|e| e.to_string())
+            fs::remove_file(&p).map_err(|e| e.to_string())?;
         }
+        Ok(())
     })
     .await
     .map_err(|e| format!("Task join error: {}", e))?
 }
 
 #[command]
-pub async fn rename_item(path: String, new_name: String) -> Result<(), String> {
+pub async fn rename_item(path: String, new_name: String) -> Result<(), FsError> {
     tokio::task::spawn_blocking(move || {
-        let p = Path::new(&path);
+        validate_name(&new_name)?;
+        let p = check_modify(&path)?;
         let parent = p.parent().ok_or("Cannot rename root directory or invalid path")?;
-        let new_path = parent.join(&new_name);
-        fs::rename(&path, new_path).map_err(|e| e.to_string())
+        let new_path = check_write(&parent.join(&new_name).to_string_lossy())?;
+        fs::rename(&p, new_path).map_err(|e| e.to_string())?;
+        Ok(())
     })
     .await
     .map_err(|e| format!("Task join error: {}", e))?
 }
 
 #[command]
-pub async fn create_directory(path: String, name: String) -> Result<(), String> {
+pub async fn create_directory(path: String, name: String) -> Result<(), FsError> {
     tokio::task::spawn_blocking(move || {
-        let full_path = Path::new(&path).join(&name);
-        fs::create_dir_all(full_path).map_err(|e| e.to_string())
+        validate_name(&name)?;
+        let full_path = check_write(&Path::new(&path).join(&name).to_string_lossy())?;
+        fs::create_dir_all(full_path).map_err(|e| e.to_string())?;
+        Ok(())
     })
     .await
     .map_err(|e| format!("Task join error: {}", e))?
 }
 
 #[command]
-pub async fn create_file(path: String, name: String) -> Result<(), String> {
+pub async fn create_file(path: String, name: String) -> Result<(), FsError> {
     tokio::task::spawn_blocking(move || {
-        let full_path = Path::new(&path).join(&name);
+        validate_name(&name)?;
+        let full_path = check_write(&Path::new(&path).join(&name).to_string_lossy())?;
         if full_path.exists() {
-            return Err("File already exists".to_string());
+            return Err("File already exists".into());
         }
-        fs::write(full_path, "").map_err(|e| e.to_string())
+        fs::write(full_path, "").map_err(|e| e.to_string())?;
+        Ok(())
     })
     .await
     .map_err(|e| format!("Task join error: {}", e))?
 }
 
 #[command]
-pub async fn move_item(source: String, destination: String) -> Result<(), String> {
+pub async fn move_item(source: String, destination: String) -> Result<(), FsError> {
     tokio::task::spawn_blocking(move || {
-        let src_path = Path::new(&source);
-        let dest_folder = Path::new(&destination);
-        
-        if !src_path.exists() || !dest_folder.is_dir() {
-            return Err("Invalid source or destination".to_string());
+        let src = check_modify(&source)?;
+        let dest = check_write(&destination)?;
+        let (src_path, dest_folder) = (src.as_path(), dest.as_path());
+
+        if !dest_folder.is_dir() {
+            return Err("Invalid source or destination".into());
+        }
+        if dest_folder.starts_with(src_path) {
+            return Err("Cannot move a folder into itself".into());
         }
 
         let file_name = src_path.file_name().ok_or("Invalid source filename")?;
         let dest_path = dest_folder.join(file_name);
         let final_dest = get_unique_path(dest_path);
 
-        fs::rename(&source, final_dest).map_err(|e| e.to_string())
+        match fs::rename(src_path, &final_dest) {
+            Ok(()) => Ok(()),
+            // Renames can't cross filesystems; fall back to copy + delete
+            Err(e) if is_cross_device(&e) => {
+                ensure_space_for_copy(src_path, dest_folder)?;
+                if src_path.is_dir() {
+                    copy_dir_recursive(src_path, &final_dest)?;
+                    fs::remove_dir_all(src_path).map_err(|e| e.to_string())?;
+                } else {
+                    fs::copy(src_path, &final_dest).map_err(|e| e.to_string())?;
+                    fs::remove_file(src_path).map_err(|e| e.to_string())?;
+                }
+                Ok(())
+            }
+            Err(e) => Err(e.to_string().into()),
+        }
     })
     .await
     .map_err(|e| format!("Task join error: {}", e))?
 }
 
 #[command]
-pub async fn copy_item(source: String, destination: String, new_name: Option<String>) -> Result<(), String> {
+pub async fn copy_item(source: String, destination: String, new_name: Option<String>) -> Result<(), FsError> {
     tokio::task::spawn_blocking(move || {
-        let src_path = Path::new(&source);
-        let dest_folder = Path::new(&destination);
-        
-        if !src_path.exists() || !dest_folder.is_dir() {
-            return Err("Invalid source or destination".to_string());
+        let src = check_read(&source)?;
+        let dest = check_write(&destination)?;
+        let (src_path, dest_folder) = (src.as_path(), dest.as_path());
+
+        if !dest_folder.is_dir() {
+            return Err("Invalid source or destination".into());
+        }
+        if src_path.is_dir() && dest_folder.starts_with(src_path) {
+            return Err("Cannot copy a folder into itself".into());
         }
 
         let file_name = match &new_name {
-            Some(name) => name.as_str(),
+            Some(name) => {
+                validate_name(name)?;
+                name.as_str()
+            }
             None => src_path.file_name().and_then(|n| n.to_str()).ok_or("Invalid source filename")?
         };
         
         let dest_path = dest_folder.join(file_name);
         let final_dest = if new_name.is_some() { dest_path } else { get_unique_path(dest_path) };
 
+        ensure_space_for_copy(src_path, dest_folder)?;
+
         if src_path.is_dir() {
             copy_dir_recursive(src_path, &final_dest)?;
         } else {
@@ -385,6 +411,15 @@ pub async fn copy_item(source: String, destination: String, new_name: Option<Str
     .map_err(|e| format!("Task join error: {}", e))?
 }
 
+fn is_cross_device(e: &std::io::Error) -> bool {
+    #[cfg(target_os = "windows")]
+    const CROSS_DEVICE: i32 = 17; // ERROR_NOT_SAME_DEVICE
+    #[cfg(not(target_os = "windows"))]
+    const CROSS_DEVICE: i32 = 18; // EXDEV
+
+    e.raw_os_error() == Some(CROSS_DEVICE)
+}
+
 fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<(), String> {
     fs::create_dir_all(dst).map_err(|e| e.to_string())?;
     
@@ -404,27 +439,57 @@ fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<(), String> {
 }
 
 #[command]
-pub async fn read_file(path: String) -> Result<String, String> {
+pub async fn read_file(path: String) -> Result<String, FsError> {
     tokio::task::spawn_blocking(move || {
-        fs::read_to_string(&path).map_err(|e| e.to_string())
+        let p = check_read(&path)?;
+        let bytes = fs::read(&p).map_err(|e| e.to_string())?;
+        // Only files that aren't UTF-8 pay for resolving their charset
+        let charset = match std::str::from_utf8(&bytes) {
+            Ok(_) => None,
+            Err(_) => editorconfig::resolve(&p).charset,
+        };
+        Ok(editorconfig::decode(&bytes, charset.as_deref())?)
     })
     .await
     .map_err(|e| format!("Task join error: {}", e))?
 }
 
 #[command]
-pub async fn write_file(path: String, content: String) -> Result<(), String> {
+pub async fn write_file(
+    app: AppHandle,
+    path: String,
+    content: String,
+    apply_editorconfig: Option<bool>,
+    record_history: Option<bool>,
+) -> Result<(), FsError> {
     tokio::task::spawn_blocking(move || {
-        fs::write(&path, content).map_err(|e| e.to_string())
+        let p = check_write(&path)?;
+        // The editor saves through `.editorconfig` rules; exports are written as is
+        let bytes = if apply_editorconfig.unwrap_or(false) {
+            editorconfig::apply(&editorconfig::resolve(&p), &content)?
+        } else {
+            content.into_bytes()
+        };
+        let history = app.try_state::<HistoryDb>();
+        if let Some(history) = &history {
+            history_commands::before_write(history, &p);
+        }
+        fs::write(&p, &bytes).map_err(|e| e.to_string())?;
+        // Autosaves opt out so they don't crowd explicit saves out of the history
+        if let Some(history) = history.as_ref().filter(|_| record_history.unwrap_or(true)) {
+            history_commands::after_write(history, &p, &bytes);
+        }
+        Ok(())
     })
     .await
     .map_err(|e| format!("Task join error: {}", e))?
 }
 
 #[command]
-pub async fn read_file_base64(path: String) -> Result<String, String> {
+pub async fn read_file_base64(path: String) -> Result<String, FsError> {
     tokio::task::spawn_blocking(move || {
-        let bytes = fs::read(&path).map_err(|e| e.to_string())?;
+        let p = check_read(&path)?;
+        let bytes = fs::read(&p).map_err(|e| e.to_string())?;
         Ok(general_purpose::STANDARD.encode(&bytes))
     })
     .await
@@ -432,35 +497,11 @@ pub async fn read_file_base64(path: String) -> Result<String, String> {
 }
 
 #[command]
-pub async fn extract_video_thumbnail(path: String) -> Result<String, String> {
+pub async fn extract_video_thumbnail(path: String) -> Result<String, FsError> {
     tokio::task::spawn_blocking(move || {
-        let ffmpeg_path = find_ffmpeg()?;
-        
-        let temp_dir = std::env::temp_dir().join("dev-toolkit-thumbs");
-        fs::create_dir_all(&temp_dir).map_err(|e| format!("Failed to create temp dir: {}", e))?;
-        
-        let temp_output = temp_dir.join(format!("thumb_{}.png", uuid::Uuid::new_v4()));
-        
-        let output = create_ffmpeg_command(&ffmpeg_path)
-            .args([
-                "-i", &path,
-                "-vframes", "1",
-                "-vf", "scale=128:128:force_original_aspect_ratio=decrease,pad=128:128:(ow-iw)/2:(oh-ih)/2",
-                "-y",
-                temp_output.to_str().unwrap()
-            ])
-            .stderr(std::process::Stdio::null())
-            .stdout(std::process::Stdio::null())
-            .output()
-            .map_err(|e| format!("FFmpeg execution failed: {}", e))?;
-
-        if !output.status.success() {
-            return Err("FFmpeg failed to extract thumbnail".to_string());
-        }
-
-        let bytes = fs::read(&temp_output).map_err(|e| e.to_string())?;
-        let _ = fs::remove_file(&temp_output);
-        
+        let p = check_read(&path)?;
+        let thumbnail = thumbnails::ensure_thumbnail(&p, 128)?;
+        let bytes = fs::read(&thumbnail).map_err(|e| e.to_string())?;
         Ok(general_purpose::STANDARD.encode(&bytes))
     })
     .await
@@ -474,24 +515,31 @@ pub async fn generate_video_preview(
     resolution: u32, 
     fps: u32,
     use_hardware_accel: bool
-) -> Result<String, String> {
+) -> Result<String, FsError> {
     tokio::task::spawn_blocking(move || {
-        let ffmpeg_path = find_ffmpeg()?;
+        let source = check_read(&path)?;
+        // Hardware decoding doesn't change the output, so it isn't part of the key
+        let key = cache_key(&source, ("preview", max_duration, resolution, fps))?;
+        let output_path = get_preview_cache_dir()?.join(format!("{}.webm", key));
         
-        let temp_dir = std::env::temp_dir().join("dev-toolkit-previews");
-        fs::create_dir_all(&temp_dir).map_err(|e| format!("Failed to create temp dir: {}", e))?;
+        // Repeat hovers reuse the preview generated the first time
+        if output_path.exists() {
+            touch_cache_entry(&output_path);
+            return Ok(output_path.to_string_lossy().to_string());
+        }
         
-        let output_path = temp_dir.join(format!("preview_{}.webm", uuid::Uuid::new_v4()));
+        let ffmpeg_path = find_ffmpeg()?;
+        let partial_path = output_path.with_extension("part.webm");
         
         let mut args = vec![
-            "-i".to_string(), path,
+            "-i".to_string(), source.to_string_lossy().to_string(),
             "-t".to_string(), max_duration.to_string(),
             "-vf".to_string(), format!("scale={}:-1,fps={}", resolution, fps),
             "-an".to_string(),
             "-c:v".to_string(), "libvpx-vp9".to_string(),
             "-b:v".to_string(), "200k".to_string(),
             "-y".to_string(),
-            output_path.to_string_lossy().to_string()
+            partial_path.to_string_lossy().to_string()
         ];
         
         if use_hardware_accel {
@@ -507,9 +555,14 @@ pub async fn generate_video_preview(
             .map_err(|e| format!("FFmpeg execution failed: {}", e))?;
 
         if !output.status.success() {
-            return Err("FFmpeg failed to generate preview".to_string());
+            let _ = fs::remove_file(&partial_path);
+            return Err("FFmpeg failed to generate preview".into());
         }
 
+        fs::rename(&partial_path, &output_path)
+            .map_err(|e| format!("Failed to store preview: {}", e))?;
+        enforce_configured_cache_limit(Some(&output_path));
+
         Ok(output_path.to_string_lossy().to_string())
     })
     .await
@@ -517,7 +570,7 @@ pub async fn generate_video_preview(
 }
 
 #[command]
-pub async fn save_screenshot(path: String, data: String) -> Result<(), String> {
+pub async fn save_screenshot(path: String, data: String) -> Result<(), FsError> {
     tokio::task::spawn_blocking(move || {
         let bytes = general_purpose::STANDARD
             .decode(&data)
@@ -525,11 +578,13 @@ pub async fn save_screenshot(path: String, data: String) -> Result<(), String> {
         
         // Ensure parent directory exists
         if let Some(parent) = Path::new(&path).parent() {
+            let parent = check_write(&parent.to_string_lossy())?;
             fs::create_dir_all(parent)
                 .map_err(|e| format!("Failed to create directory: {}", e))?;
         }
+        let target = check_write(&path)?;
         
-        fs::write(&path, bytes)
+        fs::write(&target, bytes)
             .map_err(|e| format!("Failed to write file: {}", e))?;
         
         Ok(())
@@ -538,86 +593,6 @@ pub async fn save_screenshot(path: String, data: String) -> Result<(), String> {
     .map_err(|e| format!("Task join error: {}", e))?
 }
 
-#[command]
-pub async fn get_playable_video(path: String) -> Result<String, String> {
-    tokio::task::spawn_blocking(move || {
-        let path_lower = path.to_lowercase();
-        
-        // Common web-compatible formats - return as-is
-        if path_lower.ends_with(".mp4") 
-            || path_lower.ends_with(".webm") 
-            || path_lower.ends_with(".ogg") {
-            return Ok(path);
-        }
-        
-        let ffmpeg_path = find_ffmpeg()?;
-        
-        // Create temp directory for transcoded videos
-        let temp_dir = std::env::temp_dir().join("dev-toolkit-video-transcode");
-        fs::create_dir_all(&temp_dir)
-            .map_err(|e| format!("Failed to create temp dir: {}", e))?;
-        
-        // Create a hash of the original path + modified time for cache
-        use std::collections::hash_map::DefaultHasher;
-        use std::hash::{Hash, Hasher};
-        
-        let source_modified = fs::metadata(&path)
-            .and_then(|m| m.modified())
-            .ok()
-            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
-            .map(|d| d.as_secs())
-            .unwrap_or(0);
-        
-        let mut hasher = DefaultHasher::new();
-        path.hash(&mut hasher);
-        source_modified.hash(&mut hasher);
-        let hash = hasher.finish();
-        
-        let output_path = temp_dir.join(format!("{}.mp4", hash));
-        
-        // If already transcoded with same hash, return cached version
-        if output_path.exists() {
-            return Ok(output_path.to_string_lossy().to_string());
-        }
-        
-        // Transcode to H.264 MP4 (web compatible)
-        // Key fixes for ProRes:
-        // - pix_fmt yuv420p: Convert from ProRes 4:2:2/4:4:4 to 4:2:0 (browser compatible)
-        // - vf scale: Ensure dimensions are even (required for H.264)
-        // - colorspace filters: Handle ProRes color space properly
-        let output = create_ffmpeg_command(&ffmpeg_path)
-            .args([
-                "-i", &path,
-                "-c:v", "libx264",
-                "-preset", "fast",
-                "-crf", "18",
-                "-vf", "scale=trunc(iw/2)*2:trunc(ih/2)*2,format=yuv420p",  // Ensure even dimensions + convert pixel format
-                "-color_primaries", "bt709",
-                "-color_trc", "bt709", 
-                "-colorspace", "bt709",
-                "-c:a", "aac",
-                "-b:a", "192k",
-                "-movflags", "+faststart",
-                "-y",
-                output_path.to_str().unwrap()
-            ])
-            .stderr(std::process::Stdio::piped())
-            .stdout(std::process::Stdio::piped())
-            .output()
-            .map_err(|e| format!("FFmpeg execution failed: {}", e))?;
-        
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(format!("FFmpeg transcoding failed: {}", stderr));
-        }
-        
-        Ok(output_path.to_string_lossy().to_string())
-    })
-    .await
-    .map_err(|e| format!("Task join error: {}", e))?
-}
-
-
 #[derive(Serialize, Debug, Clone)]
 pub struct FileInfoResult {
     name: String,
@@ -630,12 +605,23 @@ pub struct FileInfoResult {
     readonly: bool,
     hidden: bool,
     item_count: Option<u64>,
+    /// Permission bits (`0o7777` mask); `None` on Windows
+    mode: Option<u32>,
+    /// `rwxr-xr-x` rendering of `mode`
+    permissions: Option<String>,
+    owner: Option<String>,
+    group: Option<String>,
+    /// Capacity of the volume holding a directory; `None` for files
+    disk_total: Option<u64>,
+    disk_free: Option<u64>,
+    disk_available: Option<u64>,
 }
 
 #[command]
-pub async fn get_file_info(path: String) -> Result<FileInfoResult, String> {
+pub async fn get_file_info(path: String) -> Result<FileInfoResult, FsError> {
     tokio::task::spawn_blocking(move || {
-        let p = Path::new(&path);
+        let p = check_read(&path)?;
+        let p = p.as_path();
         let meta = fs::metadata(&p).map_err(|e| format!("Failed to get metadata: {}", e))?;
 
         let name = p.file_name()
@@ -682,6 +668,28 @@ pub async fn get_file_info(path: String) -> Result<FileInfoResult, String> {
             None
         };
 
+        let space = if is_dir {
+            crate::disk_space::query_space(p)
+                .map_err(|e| log::debug!("get_file_info: {}", e))
+                .ok()
+        } else {
+            None
+        };
+
+        #[cfg(unix)]
+        let (mode, permissions, owner, group) = {
+            use std::os::unix::fs::MetadataExt;
+            let mode = meta.mode() & 0o7777;
+            (
+                Some(mode),
+                Some(file_attributes::mode_string(mode)),
+                Some(file_attributes::user_name(meta.uid())),
+                Some(file_attributes::group_name(meta.gid())),
+            )
+        };
+        #[cfg(not(unix))]
+        let (mode, permissions, owner, group) = (None, None, None, None);
+
         Ok(FileInfoResult {
             name,
             path: p.to_string_lossy().to_string(),
@@ -693,6 +701,13 @@ pub async fn get_file_info(path: String) -> Result<FileInfoResult, String> {
             readonly,
             hidden,
             item_count,
+            mode,
+            permissions,
+            owner,
+            group,
+            disk_total: space.map(|(total, _, _)| total),
+            disk_free: space.map(|(_, free, _)| free),
+            disk_available: space.map(|(_, _, available)| available),
         })
     })
     .await
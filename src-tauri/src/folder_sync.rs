@@ -0,0 +1,465 @@
+// src-tauri/src/folder_sync.rs
+// Planning and executing one-way mirror and two-way sync between two folders
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::UNIX_EPOCH;
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::{Deserialize, Serialize};
+
+/// FAT32 (common on USB drives) stores mtimes with 2 second resolution, so
+/// closer timestamps count as equal.
+const MTIME_TOLERANCE_MS: i64 = 2000;
+
+/// A plan deleting more than this share of a side's files needs confirmation...
+const MASS_DELETE_RATIO: f64 = 0.5;
+/// ...once it deletes at least this many.
+const MASS_DELETE_MIN: usize = 20;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncMode {
+    /// Make the right folder an exact copy of the left one
+    Mirror,
+    /// Propagate changes made on either side to the other
+    TwoWay,
+}
+
+impl SyncMode {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            SyncMode::Mirror => "mirror",
+            SyncMode::TwoWay => "two_way",
+        }
+    }
+
+    pub(crate) fn parse(value: &str) -> SyncMode {
+        if value == "two_way" { SyncMode::TwoWay } else { SyncMode::Mirror }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncActionKind {
+    CopyToRight,
+    CopyToLeft,
+    DeleteLeft,
+    DeleteRight,
+    /// Both sides changed; left untouched for the user to resolve
+    Conflict,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct SyncAction {
+    pub(crate) kind: SyncActionKind,
+    /// Path relative to both roots, using `/` separators
+    pub(crate) path: String,
+    reason: String,
+    /// Bytes that will be copied (0 for deletions and conflicts)
+    pub(crate) size: u64,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct SyncPlan {
+    pub(crate) actions: Vec<SyncAction>,
+    pub(crate) bytes_to_left: u64,
+    pub(crate) bytes_to_right: u64,
+    pub(crate) conflicts: usize,
+    pub(crate) unchanged: usize,
+    /// Set when the deletions look like an emptied or unmounted folder rather
+    /// than real edits; such a plan only runs when confirmed.
+    pub(crate) deletion_warning: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct FileMeta {
+    pub size: u64,
+    /// Milliseconds since the Unix epoch
+    pub mtime: i64,
+}
+
+pub(crate) type Tree = BTreeMap<String, FileMeta>;
+
+/// Per-file state recorded after the previous successful run.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SyncedFile {
+    pub size: u64,
+    pub left_mtime: i64,
+    pub right_mtime: i64,
+}
+
+pub(crate) struct SyncOptions<'a> {
+    pub mode: SyncMode,
+    pub propagate_deletions: bool,
+    pub compare_hash: bool,
+    pub left_root: &'a Path,
+    pub right_root: &'a Path,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct SyncFailure {
+    pub(crate) path: String,
+    message: String,
+}
+
+// ─── Filters ──────────────────────────────────────────────────────────────────
+
+pub(crate) struct Filters {
+    include: Option<GlobSet>,
+    exclude: GlobSet,
+}
+
+fn build_globset(patterns: &[String]) -> Result<GlobSet, String> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns.iter().map(|p| p.trim()).filter(|p| !p.is_empty()) {
+        let glob = Glob::new(pattern).map_err(|e| format!("Invalid glob '{}': {}", pattern, e))?;
+        builder.add(glob);
+    }
+    builder.build().map_err(|e| format!("Invalid glob set: {}", e))
+}
+
+impl Filters {
+    pub(crate) fn new(include: &[String], exclude: &[String]) -> Result<Self, String> {
+        let has_include = include.iter().any(|p| !p.trim().is_empty());
+        Ok(Filters {
+            include: if has_include { Some(build_globset(include)?) } else { None },
+            exclude: build_globset(exclude)?,
+        })
+    }
+
+    /// Patterns match the relative path or, like `.gitignore`, the bare name.
+    fn matches(set: &GlobSet, rel: &str) -> bool {
+        let name = rel.rsplit('/').next().unwrap_or(rel);
+        set.is_match(rel) || set.is_match(name)
+    }
+
+    fn excludes(&self, rel: &str) -> bool {
+        Self::matches(&self.exclude, rel)
+    }
+
+    fn includes_file(&self, rel: &str) -> bool {
+        self.include.as_ref().map(|set| Self::matches(set, rel)).unwrap_or(true)
+    }
+}
+
+// ─── Scanning ─────────────────────────────────────────────────────────────────
+
+fn mtime_ms(meta: &fs::Metadata) -> i64 {
+    meta.modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+fn scan_dir(root: &Path, dir: &Path, filters: &Filters, tree: &mut Tree) -> Result<(), String> {
+    let entries = fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let rel = match path.strip_prefix(root) {
+            Ok(r) => r.to_string_lossy().replace('\\', "/"),
+            Err(_) => continue,
+        };
+        if filters.excludes(&rel) {
+            continue;
+        }
+        // Symlinks are skipped rather than followed so a link can't pull
+        // in files from outside the synced folders
+        let meta = match fs::symlink_metadata(&path) {
+            Ok(m) => m,
+            Err(_) => continue,
+        };
+        if meta.is_dir() {
+            scan_dir(root, &path, filters, tree)?;
+        } else if meta.is_file() && filters.includes_file(&rel) {
+            tree.insert(rel, FileMeta { size: meta.len(), mtime: mtime_ms(&meta) });
+        }
+    }
+    Ok(())
+}
+
+/// Files under `root` that pass the filters, keyed by relative path.
+pub(crate) fn scan(root: &Path, filters: &Filters) -> Result<Tree, String> {
+    let mut tree = Tree::new();
+    if root.is_dir() {
+        scan_dir(root, root, filters, &mut tree)?;
+    }
+    Ok(tree)
+}
+
+// ─── Planning ─────────────────────────────────────────────────────────────────
+
+fn same_mtime(a: i64, b: i64) -> bool {
+    (a - b).abs() <= MTIME_TOLERANCE_MS
+}
+
+fn hash_file(path: &Path) -> Option<[u8; 32]> {
+    use sha2::{Digest, Sha256};
+    use std::io::Read;
+
+    let mut file = fs::File::open(path).ok()?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer).ok()?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Some(hasher.finalize().into())
+}
+
+fn same_content(rel: &str, left: &FileMeta, right: &FileMeta, options: &SyncOptions) -> bool {
+    if left.size != right.size {
+        return false;
+    }
+    if same_mtime(left.mtime, right.mtime) {
+        return true;
+    }
+    options.compare_hash && {
+        let l = hash_file(&options.left_root.join(rel));
+        l.is_some() && l == hash_file(&options.right_root.join(rel))
+    }
+}
+
+fn changed_since(meta: &FileMeta, size: u64, mtime: i64) -> bool {
+    meta.size != size || !same_mtime(meta.mtime, mtime)
+}
+
+fn action(kind: SyncActionKind, path: &str, reason: &str, size: u64) -> SyncAction {
+    SyncAction { kind, path: path.to_string(), reason: reason.to_string(), size }
+}
+
+fn plan_mirror(left: &Tree, right: &Tree, options: &SyncOptions, plan: &mut SyncPlan) {
+    for (rel, l) in left {
+        match right.get(rel) {
+            None => plan.actions.push(action(SyncActionKind::CopyToRight, rel, "new", l.size)),
+            Some(r) if !same_content(rel, l, r, options) => {
+                plan.actions.push(action(SyncActionKind::CopyToRight, rel, "changed", l.size))
+            }
+            Some(_) => plan.unchanged += 1,
+        }
+    }
+    if options.propagate_deletions {
+        for rel in right.keys().filter(|rel| !left.contains_key(*rel)) {
+            plan.actions.push(action(SyncActionKind::DeleteRight, rel, "not in source", 0));
+        }
+    }
+}
+
+fn plan_two_way(
+    left: &Tree,
+    right: &Tree,
+    previous: &HashMap<String, SyncedFile>,
+    options: &SyncOptions,
+    plan: &mut SyncPlan,
+) {
+    let paths: BTreeSet<&String> = left.keys().chain(right.keys()).collect();
+
+    for rel in paths {
+        let prev = previous.get(rel.as_str());
+        let next = match (left.get(rel), right.get(rel)) {
+            (Some(l), Some(r)) => {
+                if same_content(rel, l, r, options) {
+                    plan.unchanged += 1;
+                    continue;
+                }
+                match prev {
+                    Some(p) => match (
+                        changed_since(l, p.size, p.left_mtime),
+                        changed_since(r, p.size, p.right_mtime),
+                    ) {
+                        (true, false) => action(SyncActionKind::CopyToRight, rel, "changed on left", l.size),
+                        (false, true) => action(SyncActionKind::CopyToLeft, rel, "changed on right", r.size),
+                        _ => action(SyncActionKind::Conflict, rel, "changed on both sides", 0),
+                    },
+                    None => action(SyncActionKind::Conflict, rel, "differs and was never synced", 0),
+                }
+            }
+            (Some(l), None) => match prev {
+                Some(p) if options.propagate_deletions => {
+                    if changed_since(l, p.size, p.left_mtime) {
+                        action(SyncActionKind::Conflict, rel, "deleted on right, changed on left", 0)
+                    } else {
+                        action(SyncActionKind::DeleteLeft, rel, "deleted on right", 0)
+                    }
+                }
+                Some(_) => action(SyncActionKind::CopyToRight, rel, "missing on right", l.size),
+                None => action(SyncActionKind::CopyToRight, rel, "new on left", l.size),
+            },
+            (None, Some(r)) => match prev {
+                Some(p) if options.propagate_deletions => {
+                    if changed_since(r, p.size, p.right_mtime) {
+                        action(SyncActionKind::Conflict, rel, "deleted on left, changed on right", 0)
+                    } else {
+                        action(SyncActionKind::DeleteRight, rel, "deleted on left", 0)
+                    }
+                }
+                Some(_) => action(SyncActionKind::CopyToLeft, rel, "missing on left", r.size),
+                None => action(SyncActionKind::CopyToLeft, rel, "new on right", r.size),
+            },
+            (None, None) => continue,
+        };
+        plan.actions.push(next);
+    }
+}
+
+/// Flag deletions that wipe out a side because the other one is empty (or
+/// missing, which scans as empty), or that remove most of a side's files.
+fn deletion_warning(left: &Tree, right: &Tree, plan: &SyncPlan) -> Option<String> {
+    let sides = [
+        (SyncActionKind::DeleteRight, left, right, "left", "right"),
+        (SyncActionKind::DeleteLeft, right, left, "right", "left"),
+    ];
+    for (kind, source, target, source_name, target_name) in sides {
+        let count = plan.actions.iter().filter(|a| a.kind == kind).count();
+        if count == 0 {
+            continue;
+        }
+        if source.is_empty() {
+            return Some(format!(
+                "The {} folder is empty or missing; syncing would delete {} files on the {}",
+                source_name, count, target_name
+            ));
+        }
+        if count >= MASS_DELETE_MIN && count as f64 > target.len() as f64 * MASS_DELETE_RATIO {
+            return Some(format!("Syncing would delete {} of {} files on the {}", count, target.len(), target_name));
+        }
+    }
+    None
+}
+
+/// Work out what a run would do, without touching either folder.
+pub(crate) fn build_plan(
+    left: &Tree,
+    right: &Tree,
+    previous: &HashMap<String, SyncedFile>,
+    options: &SyncOptions,
+) -> SyncPlan {
+    let mut plan = SyncPlan::default();
+    match options.mode {
+        SyncMode::Mirror => plan_mirror(left, right, options, &mut plan),
+        SyncMode::TwoWay => plan_two_way(left, right, previous, options, &mut plan),
+    }
+    for a in &plan.actions {
+        match a.kind {
+            SyncActionKind::CopyToLeft => plan.bytes_to_left += a.size,
+            SyncActionKind::CopyToRight => plan.bytes_to_right += a.size,
+            SyncActionKind::Conflict => plan.conflicts += 1,
+            _ => {}
+        }
+    }
+    plan.deletion_warning = deletion_warning(left, right, &plan);
+    plan
+}
+
+// ─── Execution ────────────────────────────────────────────────────────────────
+
+/// Copy via a temporary sibling and rename, carrying the source mtime over so
+/// the next comparison sees the files as equal.
+fn copy_file(src: &Path, dest: &Path) -> Result<(), String> {
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+    let mut partial = dest.as_os_str().to_owned();
+    partial.push(".sync-part");
+    let partial = PathBuf::from(partial);
+
+    let result = (|| {
+        fs::copy(src, &partial).map_err(|e| format!("Failed to copy: {}", e))?;
+        let modified = fs::metadata(src)
+            .and_then(|m| m.modified())
+            .map_err(|e| format!("Failed to read source time: {}", e))?;
+        fs::File::options()
+            .write(true)
+            .open(&partial)
+            .and_then(|f| f.set_modified(modified))
+            .map_err(|e| format!("Failed to set modification time: {}", e))?;
+        fs::rename(&partial, dest).map_err(|e| format!("Failed to replace file: {}", e))
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&partial);
+    }
+    result
+}
+
+/// Remove now-empty parent folders up to (not including) `root`.
+fn prune_empty_dirs(root: &Path, mut dir: Option<&Path>) {
+    while let Some(d) = dir {
+        if d == root || !d.starts_with(root) || fs::remove_dir(d).is_err() {
+            break;
+        }
+        dir = d.parent();
+    }
+}
+
+/// Apply `plan`, calling `on_progress(index, action)` before each step.
+/// Conflicts are skipped. Stops early when `cancelled` is set.
+pub(crate) fn execute_plan(
+    plan: &SyncPlan,
+    left_root: &Path,
+    right_root: &Path,
+    cancelled: &AtomicBool,
+    mut on_progress: impl FnMut(usize, &SyncAction),
+) -> Vec<SyncFailure> {
+    let mut failures = Vec::new();
+
+    for (index, a) in plan.actions.iter().enumerate() {
+        if cancelled.load(Ordering::Relaxed) {
+            break;
+        }
+        on_progress(index, a);
+
+        let left = left_root.join(&a.path);
+        let right = right_root.join(&a.path);
+        let result = match a.kind {
+            SyncActionKind::CopyToRight => copy_file(&left, &right),
+            SyncActionKind::CopyToLeft => copy_file(&right, &left),
+            SyncActionKind::DeleteLeft => fs::remove_file(&left)
+                .map(|_| prune_empty_dirs(left_root, left.parent()))
+                .map_err(|e| format!("Failed to delete: {}", e)),
+            SyncActionKind::DeleteRight => fs::remove_file(&right)
+                .map(|_| prune_empty_dirs(right_root, right.parent()))
+                .map_err(|e| format!("Failed to delete: {}", e)),
+            SyncActionKind::Conflict => Ok(()),
+        };
+
+        if let Err(message) = result {
+            failures.push(SyncFailure { path: a.path.clone(), message });
+        }
+    }
+
+    failures
+}
+
+/// State to remember after a run: every file present on both sides with the
+/// same content. Copies carry the source mtime over, so equal mtimes mean a
+/// copy was just made; otherwise the contents are compared by hash, since an
+/// equal size alone could be two different edits.
+pub(crate) fn snapshot_state(
+    left: &Tree,
+    right: &Tree,
+    left_root: &Path,
+    right_root: &Path,
+) -> Vec<(String, SyncedFile)> {
+    left.iter()
+        .filter_map(|(rel, l)| {
+            let r = right.get(rel)?;
+            if l.size != r.size {
+                return None;
+            }
+            let identical = same_mtime(l.mtime, r.mtime) || {
+                let hash = hash_file(&left_root.join(rel));
+                hash.is_some() && hash == hash_file(&right_root.join(rel))
+            };
+            identical.then(|| {
+                (rel.clone(), SyncedFile { size: l.size, left_mtime: l.mtime, right_mtime: r.mtime })
+            })
+        })
+        .collect()
+}
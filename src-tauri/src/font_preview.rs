@@ -0,0 +1,151 @@
+// src-tauri/src/font_preview.rs
+// Rendering sample text in an installed font to a PNG for the font picker
+
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::sync::{Arc, Mutex};
+
+use ab_glyph::{point, Font, FontRef, Glyph, PxScale, ScaleFont};
+use base64::{engine::general_purpose, Engine as _};
+use image::{ImageFormat, Rgba, RgbaImage};
+
+use crate::fonts::resolve_face;
+
+const DEFAULT_SIZE: f32 = 24.0;
+const MIN_SIZE: f32 = 6.0;
+const MAX_SIZE: f32 = 200.0;
+const MAX_TEXT_CHARS: usize = 500;
+const MAX_DIMENSION: u32 = 4096;
+/// Font files kept in memory; the picker renders many samples per family.
+const MAX_CACHED_FILES: usize = 16;
+
+static FONT_DATA: Mutex<Option<HashMap<String, Arc<Vec<u8>>>>> = Mutex::new(None);
+
+fn font_data(path: &str) -> Result<Arc<Vec<u8>>, String> {
+    let mut cache = FONT_DATA.lock().map_err(|e| e.to_string())?;
+    let files = cache.get_or_insert_with(HashMap::new);
+    if let Some(data) = files.get(path) {
+        return Ok(data.clone());
+    }
+    let data = Arc::new(std::fs::read(path).map_err(|e| format!("Failed to read font {}: {}", path, e))?);
+    if files.len() >= MAX_CACHED_FILES {
+        files.clear();
+    }
+    files.insert(path.to_string(), data.clone());
+    Ok(data)
+}
+
+/// `#rgb` or `#rrggbb`; anything else is an error.
+fn parse_color(color: &str) -> Result<[u8; 3], String> {
+    let hex = color.trim().trim_start_matches('#');
+    let channel = |s: &str| u8::from_str_radix(s, 16).map_err(|_| format!("Invalid color: {}", color));
+    match hex.len() {
+        3 => {
+            let mut rgb = [0; 3];
+            for (i, c) in hex.chars().enumerate() {
+                rgb[i] = channel(&c.to_string().repeat(2))?;
+            }
+            Ok(rgb)
+        }
+        6 if hex.is_ascii() => Ok([channel(&hex[0..2])?, channel(&hex[2..4])?, channel(&hex[4..6])?]),
+        _ => Err(format!("Invalid color: {}", color)),
+    }
+}
+
+/// Lay out `text` line by line with kerning. Returns the positioned glyphs
+/// and the size of the text block.
+fn layout<F: Font>(font: &F, scale: PxScale, text: &str) -> (Vec<Glyph>, f32, f32) {
+    let scaled = font.as_scaled(scale);
+    let line_height = scaled.height() + scaled.line_gap();
+    let mut glyphs = Vec::new();
+    let mut width: f32 = 0.0;
+    let mut lines = 0;
+
+    for (line, line_text) in text.lines().enumerate() {
+        let baseline = scaled.ascent() + line as f32 * line_height;
+        let mut x = 0.0;
+        let mut previous = None;
+        for c in line_text.chars() {
+            let id = scaled.glyph_id(c);
+            if let Some(previous) = previous {
+                x += scaled.kern(previous, id);
+            }
+            glyphs.push(id.with_scale_and_position(scale, point(x, baseline)));
+            x += scaled.h_advance(id);
+            previous = Some(id);
+        }
+        width = width.max(x);
+        lines = line + 1;
+    }
+    (glyphs, width, lines as f32 * line_height)
+}
+
+fn render(
+    data: &[u8],
+    index: u32,
+    text: &str,
+    size: f32,
+    color: [u8; 3],
+) -> Result<Vec<u8>, String> {
+    let font = FontRef::try_from_slice_and_index(data, index).map_err(|e| format!("Failed to load font: {}", e))?;
+    let scale = PxScale::from(size);
+    let (glyphs, width, height) = layout(&font, scale, text);
+
+    // Padding keeps antialiased edges and overhanging glyphs in view
+    let padding = (size / 8.0).ceil() as u32;
+    let image_width = (width.ceil() as u32 + padding * 2).clamp(1, MAX_DIMENSION);
+    let image_height = (height.ceil() as u32 + padding * 2).clamp(1, MAX_DIMENSION);
+    let mut image = RgbaImage::new(image_width, image_height);
+
+    for glyph in glyphs {
+        let Some(outline) = font.outline_glyph(glyph) else { continue };
+        let bounds = outline.px_bounds();
+        outline.draw(|gx, gy, coverage| {
+            let x = bounds.min.x as i64 + gx as i64 + padding as i64;
+            let y = bounds.min.y as i64 + gy as i64 + padding as i64;
+            if x < 0 || y < 0 || x >= image_width as i64 || y >= image_height as i64 {
+                return;
+            }
+            let alpha = (coverage.clamp(0.0, 1.0) * 255.0).round() as u8;
+            let pixel = image.get_pixel_mut(x as u32, y as u32);
+            // Overlapping glyphs keep the stronger coverage
+            if alpha > pixel[3] {
+                *pixel = Rgba([color[0], color[1], color[2], alpha]);
+            }
+        });
+    }
+
+    let mut png = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode preview: {}", e))?;
+    Ok(png)
+}
+
+/// Render `text` in the installed `family` to a PNG on a transparent
+/// background and return it base64-encoded. Lets the font picker show fonts
+/// the webview can't load by name. `size` is in pixels (default 24) and
+/// `color` a `#rrggbb` text color (default black).
+#[tauri::command]
+pub async fn render_font_preview(
+    family: String,
+    text: String,
+    size: Option<f32>,
+    weight: Option<u16>,
+    italic: Option<bool>,
+    color: Option<String>,
+) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || {
+        let face = resolve_face(&family, weight.unwrap_or(400), italic.unwrap_or(false))?;
+        let size = size.unwrap_or(DEFAULT_SIZE).clamp(MIN_SIZE, MAX_SIZE);
+        let color = parse_color(color.as_deref().unwrap_or("#000000"))?;
+        let text: String = text.chars().take(MAX_TEXT_CHARS).collect();
+        let text = if text.trim().is_empty() { face.family.clone() } else { text };
+
+        let data = font_data(&face.path)?;
+        let png = render(&data, face.index, &text, size, color)?;
+        Ok(general_purpose::STANDARD.encode(&png))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
@@ -1,107 +1,388 @@
-use std::collections::BTreeSet;
-use std::path::Path;
+// src-tauri/src/fonts.rs
+// Installed fonts, read from the name, OS/2 and post tables of each font file
 
-/// Scan a directory for font files and extract family names from filenames
-fn collect_font_names_from_dir(dir: &Path, names: &mut BTreeSet<String>) {
-    let Ok(entries) = std::fs::read_dir(dir) else { return };
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::UNIX_EPOCH;
 
-    for entry in entries.flatten() {
-        let path = entry.path();
+use serde::{Deserialize, Serialize};
+use ttf_parser::{name_id, Face, Language};
 
-        if path.is_dir() {
-            collect_font_names_from_dir(&path, names);
-            continue;
-        }
+/// Bumped when the cached metadata changes shape or meaning.
+const CACHE_VERSION: u32 = 1;
 
-        let ext = path
-            .extension()
-            .and_then(|e| e.to_str())
-            .unwrap_or("")
-            .to_lowercase();
+/// Faces found by the last scan, reused until a refresh is asked for.
+static FONTS: Mutex<Option<Vec<FontInfo>>> = Mutex::new(None);
 
-        if !matches!(ext.as_str(), "ttf" | "otf" | "ttc" | "woff" | "woff2") {
-            continue;
-        }
+/// One face of an installed font. Collections (`.ttc`) have a face per index.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FontInfo {
+    pub(crate) family: String,
+    pub(crate) style: String,
+    /// 100 (thin) to 900 (black); 400 is regular
+    pub(crate) weight: u16,
+    pub(crate) italic: bool,
+    pub(crate) monospace: bool,
+    pub(crate) path: String,
+    /// Face index within the file
+    pub(crate) index: u32,
+}
 
-        if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
-            let clean = stem
-                .replace('-', " ")
-                .replace('_', " ");
+/// Parsed faces of each font file, keyed by path, so unchanged files are not
+/// read again on the next start.
+#[derive(Serialize, Deserialize, Default)]
+struct FontCache {
+    version: u32,
+    files: HashMap<String, CachedFile>,
+}
 
-            // Strip common style suffixes to get the family name
-            let family = clean
-                .trim()
-                .to_string();
+#[derive(Serialize, Deserialize)]
+struct CachedFile {
+    modified: u64,
+    size: u64,
+    faces: Vec<FontInfo>,
+}
 
-            let family = strip_style_suffix(&family);
+fn cache_path() -> Option<PathBuf> {
+    Some(dirs::cache_dir()?.join("com.devtoolkit.app").join("fonts.json"))
+}
 
-            if !family.is_empty() {
-                names.insert(family);
-            }
-        }
-    }
+fn load_cache() -> FontCache {
+    cache_path()
+        .and_then(|path| fs::read(path).ok())
+        .and_then(|bytes| serde_json::from_slice::<FontCache>(&bytes).ok())
+        .filter(|cache| cache.version == CACHE_VERSION)
+        .unwrap_or_default()
 }
 
-fn strip_style_suffix(name: &str) -> String {
-    let suffixes = [
-        "Bold Italic", "Bold Oblique", "Semi Bold Italic", "SemiBold Italic",
-        "Extra Bold Italic", "ExtraBold Italic", "Ultra Bold Italic",
-        "Light Italic", "Light Oblique", "Thin Italic", "Thin Oblique",
-        "Medium Italic", "Medium Oblique", "Black Italic", "Black Oblique",
-        "Heavy Italic", "Heavy Oblique",
-        "Bold", "Italic", "Oblique", "Regular", "Medium", "Light",
-        "Thin", "Black", "Heavy", "ExtraBold", "Extra Bold",
-        "SemiBold", "Semi Bold", "DemiBold", "Demi Bold",
-        "ExtraLight", "Extra Light", "UltraLight", "Ultra Light",
-        "Book", "Condensed", "Narrow", "Wide", "Extended",
-    ];
-
-    let mut result = name.to_string();
-    for suffix in &suffixes {
-        if let Some(stripped) = result.strip_suffix(suffix) {
-            result = stripped.trim().to_string();
-        }
+fn store_cache(cache: &FontCache) {
+    let Some(path) = cache_path() else { return };
+    let result = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| fs::write(&path, serde_json::to_vec(cache).unwrap_or_default()));
+    if let Err(e) = result {
+        log::warn!("fonts: failed to write cache {}: {}", path.display(), e);
     }
-    result
 }
 
-#[tauri::command]
-pub async fn get_system_fonts() -> Result<Vec<String>, String> {
-    let mut names = BTreeSet::new();
+/// Folders fonts are installed in on this platform.
+fn font_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
 
     #[cfg(target_os = "windows")]
     {
         if let Some(windir) = std::env::var_os("WINDIR") {
-            let sys_fonts = Path::new(&windir).join("Fonts");
-            collect_font_names_from_dir(&sys_fonts, &mut names);
+            dirs.push(Path::new(&windir).join("Fonts"));
         }
         if let Some(local) = std::env::var_os("LOCALAPPDATA") {
-            let user_fonts = Path::new(&local).join("Microsoft").join("Windows").join("Fonts");
-            collect_font_names_from_dir(&user_fonts, &mut names);
+            dirs.push(Path::new(&local).join("Microsoft").join("Windows").join("Fonts"));
         }
     }
 
     #[cfg(target_os = "macos")]
     {
         for dir in &["/System/Library/Fonts", "/Library/Fonts"] {
-            collect_font_names_from_dir(Path::new(dir), &mut names);
+            dirs.push(PathBuf::from(dir));
         }
         if let Some(home) = std::env::var_os("HOME") {
-            let user_fonts = Path::new(&home).join("Library/Fonts");
-            collect_font_names_from_dir(&user_fonts, &mut names);
+            dirs.push(Path::new(&home).join("Library/Fonts"));
         }
     }
 
     #[cfg(target_os = "linux")]
     {
+        dirs.extend(fontconfig::font_dirs());
         for dir in &["/usr/share/fonts", "/usr/local/share/fonts"] {
-            collect_font_names_from_dir(Path::new(dir), &mut names);
+            dirs.push(PathBuf::from(dir));
         }
         if let Some(home) = std::env::var_os("HOME") {
-            let user_fonts = Path::new(&home).join(".local/share/fonts");
-            collect_font_names_from_dir(&user_fonts, &mut names);
+            dirs.push(Path::new(&home).join(".local/share/fonts"));
+            dirs.push(Path::new(&home).join(".fonts"));
+        }
+    }
+
+    dirs
+}
+
+/// Font folders from the fontconfig configuration, following `<include>`s
+/// the way fontconfig does, so custom `<dir>`s in fonts.conf are scanned.
+#[cfg(target_os = "linux")]
+mod fontconfig {
+    use std::collections::HashSet;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    use regex::Regex;
+
+    const DEFAULT_CONFIG: &str = "/etc/fonts/fonts.conf";
+
+    fn env_dir(var: &str, home_fallback: &str) -> Option<PathBuf> {
+        std::env::var_os(var)
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| Some(Path::new(&std::env::var_os("HOME")?).join(home_fallback)))
+    }
+
+    fn unescape(text: &str) -> String {
+        text.trim()
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&apos;", "'")
+            .replace("&amp;", "&")
+    }
+
+    /// Resolve a `<dir>` or `<include>` path: `~` is the home folder, the
+    /// `xdg` prefix the XDG data (dirs) or config (includes) folder, and
+    /// relative paths start at the config file's folder.
+    fn expand(path: &str, prefix: Option<&str>, xdg_base: Option<PathBuf>, config_dir: &Path) -> Option<PathBuf> {
+        if prefix == Some("xdg") {
+            return Some(xdg_base?.join(path));
+        }
+        if let Some(rest) = path.strip_prefix('~') {
+            let home = PathBuf::from(std::env::var_os("HOME")?);
+            return Some(home.join(rest.trim_start_matches('/')));
+        }
+        let path = Path::new(path);
+        Some(if path.is_absolute() { path.to_path_buf() } else { config_dir.join(path) })
+    }
+
+    fn read_config(file: &Path, seen: &mut HashSet<PathBuf>, dirs: &mut Vec<PathBuf>) {
+        let key = fs::canonicalize(file).unwrap_or_else(|_| file.to_path_buf());
+        if !seen.insert(key) {
+            return;
+        }
+        let Ok(text) = fs::read_to_string(file) else { return };
+        let config_dir = file.parent().unwrap_or(Path::new("/"));
+
+        let comments = Regex::new(r"(?s)<!--.*?-->").expect("valid regex");
+        let elements = Regex::new(r"(?s)<(dir|include)\b([^>]*)>([^<]*)</(?:dir|include)>").expect("valid regex");
+        let prefix_attr = Regex::new(r#"prefix\s*=\s*["']([^"']*)["']"#).expect("valid regex");
+        let text = comments.replace_all(&text, "");
+
+        for caps in elements.captures_iter(&text) {
+            let prefix = prefix_attr.captures(&caps[2]).map(|p| p[1].to_string());
+            let value = unescape(&caps[3]);
+            if value.is_empty() {
+                continue;
+            }
+            if &caps[1] == "dir" {
+                if let Some(dir) = expand(&value, prefix.as_deref(), env_dir("XDG_DATA_HOME", ".local/share"), config_dir) {
+                    dirs.push(dir);
+                }
+                continue;
+            }
+
+            let Some(target) = expand(&value, prefix.as_deref(), env_dir("XDG_CONFIG_HOME", ".config"), config_dir)
+            else {
+                continue;
+            };
+            if target.is_dir() {
+                // conf.d style folders: every *.conf, in name order
+                let Ok(entries) = fs::read_dir(&target) else { continue };
+                let mut files: Vec<PathBuf> = entries
+                    .flatten()
+                    .map(|e| e.path())
+                    .filter(|p| p.extension().is_some_and(|e| e == "conf"))
+                    .collect();
+                files.sort();
+                for conf in files {
+                    read_config(&conf, seen, dirs);
+                }
+            } else if target.is_file() {
+                read_config(&target, seen, dirs);
+            }
+        }
+    }
+
+    pub(super) fn font_dirs() -> Vec<PathBuf> {
+        let config = std::env::var_os("FONTCONFIG_FILE")
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_CONFIG));
+        let mut dirs = Vec::new();
+        read_config(&config, &mut HashSet::new(), &mut dirs);
+        dirs
+    }
+}
+
+/// Font files under `dir`. WOFF files are web fonts, compressed, and never
+/// installed system-wide, so only sfnt files are collected.
+fn collect_font_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else { return };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+
+        if path.is_dir() {
+            collect_font_files(&path, files);
+            continue;
         }
+
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+
+        if matches!(ext.as_str(), "ttf" | "otf" | "ttc" | "otc") {
+            files.push(path);
+        }
+    }
+}
+
+/// A name table entry, preferring the US English record.
+fn face_name(face: &Face, id: u16) -> Option<String> {
+    let mut fallback = None;
+    for name in face.names() {
+        if name.name_id != id {
+            continue;
+        }
+        let Some(text) = name.to_string().filter(|t| !t.trim().is_empty()) else { continue };
+        if name.language() == Language::English_UnitedStates {
+            return Some(text);
+        }
+        fallback.get_or_insert(text);
+    }
+    fallback
+}
+
+/// The post table's fixed-pitch flag is missing from some programming
+/// fonts, so equal advances of narrow and wide glyphs count as well.
+fn is_monospace(face: &Face) -> bool {
+    if face.is_monospaced() {
+        return true;
     }
+    let advances: Option<Vec<u16>> = ['i', 'M', 'W', '.']
+        .iter()
+        .map(|&c| face.glyph_index(c).and_then(|g| face.glyph_hor_advance(g)))
+        .collect();
+    advances.is_some_and(|a| a[0] > 0 && a.iter().all(|&w| w == a[0]))
+}
 
-    Ok(names.into_iter().collect())
-}
\ No newline at end of file
+fn parse_font_file(path: &Path) -> Vec<FontInfo> {
+    let Ok(data) = fs::read(path) else { return Vec::new() };
+    let count = ttf_parser::fonts_in_collection(&data).unwrap_or(1);
+
+    (0..count)
+        .filter_map(|index| {
+            let face = Face::parse(&data, index).ok()?;
+            // Typographic names group every weight under one family;
+            // the legacy ones split off e.g. "Family Light"
+            let family = face_name(&face, name_id::TYPOGRAPHIC_FAMILY)
+                .or_else(|| face_name(&face, name_id::FAMILY))?;
+            let style = face_name(&face, name_id::TYPOGRAPHIC_SUBFAMILY)
+                .or_else(|| face_name(&face, name_id::SUBFAMILY))
+                .unwrap_or_else(|| "Regular".to_string());
+            Some(FontInfo {
+                family: family.trim().to_string(),
+                style: style.trim().to_string(),
+                weight: face.weight().to_number(),
+                italic: face.is_italic() || face.is_oblique(),
+                monospace: is_monospace(&face),
+                path: path.to_string_lossy().to_string(),
+                index,
+            })
+        })
+        .collect()
+}
+
+/// Scan the font folders, parsing only files that changed since the last
+/// scan.
+fn scan_fonts() -> Vec<FontInfo> {
+    let mut files = Vec::new();
+    let dirs = font_dirs();
+    for dir in &dirs {
+        // Configured folders often nest inside the defaults
+        if dirs.iter().any(|other| other != dir && dir.starts_with(other)) {
+            continue;
+        }
+        collect_font_files(dir, &mut files);
+    }
+    files.sort();
+    files.dedup();
+
+    let mut previous = load_cache();
+    let mut cache = FontCache { version: CACHE_VERSION, files: HashMap::new() };
+
+    for path in files {
+        let Ok(meta) = fs::metadata(&path) else { continue };
+        let modified = meta
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let key = path.to_string_lossy().to_string();
+
+        let entry = match previous.files.remove(&key) {
+            Some(cached) if cached.modified == modified && cached.size == meta.len() => cached,
+            _ => CachedFile { modified, size: meta.len(), faces: parse_font_file(&path) },
+        };
+        cache.files.insert(key, entry);
+    }
+    store_cache(&cache);
+
+    let mut fonts: Vec<FontInfo> = cache.files.into_values().flat_map(|f| f.faces).collect();
+    fonts.sort_by(|a, b| {
+        a.family
+            .to_lowercase()
+            .cmp(&b.family.to_lowercase())
+            .then(a.italic.cmp(&b.italic))
+            .then(a.weight.cmp(&b.weight))
+            .then(a.style.cmp(&b.style))
+    });
+    fonts
+}
+
+/// All installed faces, scanning on first use or when `refresh` is set.
+pub(crate) fn system_fonts(refresh: bool) -> Result<Vec<FontInfo>, String> {
+    let mut cached = FONTS.lock().map_err(|e| e.to_string())?;
+    if refresh || cached.is_none() {
+        *cached = Some(scan_fonts());
+    }
+    Ok(cached.clone().unwrap_or_default())
+}
+
+/// The installed face that best matches a family name (case-insensitive),
+/// weight and slant: the right slant first, then the nearest weight.
+pub(crate) fn resolve_face(family: &str, weight: u16, italic: bool) -> Result<FontInfo, String> {
+    let fonts = system_fonts(false)?;
+    let wanted = family.trim().trim_matches(|c| c == '\'' || c == '"').to_lowercase();
+    fonts
+        .into_iter()
+        .filter(|f| f.family.to_lowercase() == wanted)
+        .min_by_key(|f| ((f.italic != italic) as u32, (i32::from(f.weight) - i32::from(weight)).unsigned_abs()))
+        .ok_or_else(|| format!("Font not found: {}", family.trim()))
+}
+
+/// Installed font faces sorted by family. `monospace_only` keeps fixed-pitch
+/// families for the terminal and editor pickers; `refresh` rescans after
+/// fonts were installed or removed.
+#[tauri::command]
+pub async fn get_system_fonts(
+    monospace_only: Option<bool>,
+    refresh: Option<bool>,
+) -> Result<Vec<FontInfo>, String> {
+    tokio::task::spawn_blocking(move || {
+        let mut fonts = system_fonts(refresh.unwrap_or(false))?;
+        if monospace_only.unwrap_or(false) {
+            fonts.retain(|f| f.monospace);
+        }
+        Ok(fonts)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// The font file for a family, e.g. to load a font the webview can't find
+/// by name. `weight` defaults to 400 (regular).
+#[tauri::command]
+pub async fn resolve_font(family: String, weight: Option<u16>, italic: Option<bool>) -> Result<FontInfo, String> {
+    tokio::task::spawn_blocking(move || resolve_face(&family, weight.unwrap_or(400), italic.unwrap_or(false)))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
@@ -0,0 +1,346 @@
+// src-tauri/src/formatter.rs
+// Formatting editor buffers with external formatters over stdin/stdout
+
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::lsp::find_on_path;
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+/// Formatter output beyond this is reported as an error rather than used.
+const MAX_OUTPUT_BYTES: usize = 16 * 1024 * 1024;
+/// Lines of formatter stderr kept in error messages.
+const MAX_ERROR_LINES: usize = 20;
+
+struct FormatterSpec {
+    id: &'static str,
+    command: &'static str,
+    extensions: &'static [&'static str],
+    /// Installed per project by npm; looked up in `node_modules/.bin` first
+    node_package: bool,
+}
+
+const FORMATTERS: &[FormatterSpec] = &[
+    FormatterSpec {
+        id: "rustfmt",
+        command: "rustfmt",
+        extensions: &["rs"],
+        node_package: false,
+    },
+    FormatterSpec {
+        id: "prettier",
+        command: "prettier",
+        extensions: &[
+            "js", "mjs", "cjs", "jsx", "ts", "mts", "cts", "tsx", "json", "jsonc", "css", "scss", "less", "html",
+            "vue", "svelte", "md", "markdown", "yaml", "yml", "graphql",
+        ],
+        node_package: true,
+    },
+    FormatterSpec {
+        id: "black",
+        command: "black",
+        extensions: &["py", "pyi"],
+        node_package: false,
+    },
+    FormatterSpec {
+        id: "gofmt",
+        command: "gofmt",
+        extensions: &["go"],
+        node_package: false,
+    },
+    FormatterSpec {
+        id: "clang-format",
+        command: "clang-format",
+        extensions: &["c", "h", "cc", "cpp", "cxx", "hpp", "hh", "hxx", "m", "mm", "java", "proto"],
+        node_package: false,
+    },
+];
+
+/// Per-language settings from the editor.
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct FormatterConfig {
+    /// Formatter id to use instead of the default, or `"none"` to skip
+    formatter: Option<String>,
+    /// Path to the formatter binary, when it is not on PATH
+    command: Option<String>,
+    #[serde(default)]
+    extra_args: Vec<String>,
+    timeout_ms: Option<u64>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct FormatResult {
+    /// `None` when no formatter applies to the file
+    formatter: Option<String>,
+    content: String,
+    changed: bool,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct FormatterInfo {
+    id: String,
+    extensions: Vec<String>,
+    path: Option<String>,
+}
+
+fn spec_by_id(id: &str) -> Option<&'static FormatterSpec> {
+    FORMATTERS.iter().find(|f| f.id == id)
+}
+
+fn spec_for_path(path: &Path) -> Option<&'static FormatterSpec> {
+    let ext = path.extension()?.to_string_lossy().to_lowercase();
+    FORMATTERS.iter().find(|f| f.extensions.contains(&ext.as_str()))
+}
+
+/// Binary for `spec`: a project-local npm install next to the file wins over
+/// a global one.
+fn locate(spec: &FormatterSpec, file: &Path) -> Option<PathBuf> {
+    if spec.node_package {
+        let name = if cfg!(target_os = "windows") { format!("{}.cmd", spec.command) } else { spec.command.to_string() };
+        let local = file
+            .ancestors()
+            .skip(1)
+            .map(|dir| dir.join("node_modules").join(".bin").join(&name))
+            .find(|candidate| candidate.is_file());
+        if local.is_some() {
+            return local;
+        }
+    }
+    find_on_path(spec.command)
+}
+
+/// Value of `key` in `[section]` (`""` for the top level) of a TOML file,
+/// unquoted. Only plain `key = value` lines are understood, which covers the
+/// manifest and rustfmt settings read here.
+fn toml_value(text: &str, section: &str, key: &str) -> Option<String> {
+    let mut current = String::new();
+    for line in text.lines().map(str::trim) {
+        if line.starts_with('[') {
+            current = line.trim_matches(|c| c == '[' || c == ']').trim().to_string();
+            continue;
+        }
+        if current != section {
+            continue;
+        }
+        let Some((name, value)) = line.split_once('=') else { continue };
+        if name.split('.').map(str::trim).eq(key.split('.')) {
+            let value = value.split(" #").next().unwrap_or(value).trim();
+            return Some(value.trim_matches(|c| c == '"' || c == '\'').to_string());
+        }
+    }
+    None
+}
+
+/// Edition to pass to rustfmt for `file`: the `edition` of the nearest
+/// Cargo.toml, following `edition.workspace = true` up to the workspace.
+/// `None` when the nearest rustfmt.toml sets an edition itself (rustfmt reads
+/// it from the working directory) or the package uses the default edition.
+fn rustfmt_edition(file: &Path) -> Option<String> {
+    // rustfmt only reads the nearest config file
+    let config = file
+        .ancestors()
+        .skip(1)
+        .flat_map(|dir| ["rustfmt.toml", ".rustfmt.toml"].map(|name| dir.join(name)))
+        .find(|path| path.is_file());
+    if let Some(config) = config {
+        let text = std::fs::read_to_string(config).unwrap_or_default();
+        if toml_value(&text, "", "edition").is_some() {
+            return None;
+        }
+    }
+
+    let mut inherited = false;
+    for dir in file.ancestors().skip(1) {
+        let Ok(manifest) = std::fs::read_to_string(dir.join("Cargo.toml")) else { continue };
+        if inherited {
+            if let Some(edition) = toml_value(&manifest, "workspace.package", "edition") {
+                return Some(edition);
+            }
+            continue;
+        }
+        if !manifest.lines().any(|line| line.trim() == "[package]") {
+            continue;
+        }
+        // `edition.workspace = true` or `edition = { workspace = true }`
+        let edition = toml_value(&manifest, "package", "edition");
+        inherited = toml_value(&manifest, "package", "edition.workspace").as_deref() == Some("true")
+            || edition.as_deref().is_some_and(|e| e.starts_with('{') && e.contains("workspace"));
+        if !inherited {
+            return edition.filter(|e| !e.starts_with('{'));
+        }
+    }
+    None
+}
+
+fn arguments(spec: &FormatterSpec, file: &Path) -> Vec<String> {
+    let file_arg = file.to_string_lossy().to_string();
+    match spec.id {
+        "rustfmt" => {
+            let mut args = vec!["--emit".into(), "stdout".into()];
+            if let Some(edition) = rustfmt_edition(file) {
+                args.extend(["--edition".into(), edition]);
+            }
+            args
+        }
+        "prettier" => vec!["--stdin-filepath".into(), file_arg],
+        "black" => vec!["--quiet".into(), "--stdin-filename".into(), file_arg, "-".into()],
+        "clang-format" => vec![format!("--assume-filename={}", file_arg)],
+        _ => Vec::new(),
+    }
+}
+
+fn error_excerpt(stderr: &[u8]) -> String {
+    let text = String::from_utf8_lossy(stderr);
+    let lines: Vec<&str> = text.lines().filter(|l| !l.trim().is_empty()).collect();
+    let mut excerpt = lines.iter().take(MAX_ERROR_LINES).copied().collect::<Vec<_>>().join("\n");
+    if lines.len() > MAX_ERROR_LINES {
+        excerpt.push_str(&format!("\n… {} more lines", lines.len() - MAX_ERROR_LINES));
+    }
+    excerpt
+}
+
+/// Run `binary` with `input` on stdin and return stdout, killing it after
+/// `timeout`. Output pipes are drained on threads so a chatty formatter can't
+/// block on a full pipe.
+fn run_formatter(
+    name: &str,
+    binary: &Path,
+    args: &[String],
+    cwd: &Path,
+    input: &str,
+    timeout: Duration,
+) -> Result<String, String> {
+    let mut cmd = Command::new(binary);
+    cmd.args(args)
+        .current_dir(cwd)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    #[cfg(target_os = "windows")]
+    {
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let mut child = cmd.spawn().map_err(|e| format!("Failed to start {}: {}", name, e))?;
+
+    let mut stdin = child.stdin.take().ok_or("Formatter has no stdin")?;
+    let input = input.as_bytes().to_vec();
+    let writer = std::thread::spawn(move || {
+        // A formatter that exits early closes the pipe; its status explains why
+        let _ = stdin.write_all(&input);
+    });
+    let mut stdout = child.stdout.take().ok_or("Formatter has no stdout")?;
+    let out_reader = std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = stdout.by_ref().take(MAX_OUTPUT_BYTES as u64 + 1).read_to_end(&mut buf);
+        buf
+    });
+    let mut stderr = child.stderr.take().ok_or("Formatter has no stderr")?;
+    let err_reader = std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = stderr.read_to_end(&mut buf);
+        buf
+    });
+
+    let started = Instant::now();
+    let status = loop {
+        match child.try_wait().map_err(|e| format!("Failed to run {}: {}", name, e))? {
+            Some(status) => break status,
+            None if started.elapsed() >= timeout => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("{} timed out after {} s", name, timeout.as_secs_f32()));
+            }
+            None => std::thread::sleep(Duration::from_millis(10)),
+        }
+    };
+    let _ = writer.join();
+    let output = out_reader.join().unwrap_or_default();
+    let errors = err_reader.join().unwrap_or_default();
+
+    if !status.success() {
+        let detail = error_excerpt(&errors);
+        return Err(if detail.is_empty() {
+            format!("{} failed ({})", name, status)
+        } else {
+            format!("{} failed:\n{}", name, detail)
+        });
+    }
+    if output.len() > MAX_OUTPUT_BYTES {
+        return Err(format!("{} produced too much output", name));
+    }
+    String::from_utf8(output).map_err(|_| format!("{} produced invalid UTF-8", name))
+}
+
+// ─── Commands ─────────────────────────────────────────────────────────────────
+
+/// Formatters the editor knows, with their location if installed.
+#[tauri::command]
+pub fn list_formatters() -> Vec<FormatterInfo> {
+    FORMATTERS
+        .iter()
+        .map(|spec| FormatterInfo {
+            id: spec.id.to_string(),
+            extensions: spec.extensions.iter().map(|e| e.to_string()).collect(),
+            path: find_on_path(spec.command).map(|p| p.to_string_lossy().to_string()),
+        })
+        .collect()
+}
+
+/// Format `content`, the buffer of the file at `path`. The formatter is
+/// picked by extension unless `config` names one; it runs in the file's
+/// folder so project configuration (`rustfmt.toml`, `.prettierrc`, ...)
+/// applies. The file itself is neither read nor written.
+#[tauri::command]
+pub async fn format_document(
+    path: String,
+    content: String,
+    config: Option<FormatterConfig>,
+) -> Result<FormatResult, String> {
+    tokio::task::spawn_blocking(move || {
+        let config = config.unwrap_or_default();
+        let file = PathBuf::from(&path);
+
+        let spec = match config.formatter.as_deref() {
+            Some("none") => None,
+            Some(id) => Some(spec_by_id(id).ok_or_else(|| format!("Unknown formatter: {}", id))?),
+            None => spec_for_path(&file),
+        };
+        let Some(spec) = spec else {
+            return Ok(FormatResult { formatter: None, content, changed: false });
+        };
+
+        let binary = match config.command.as_deref().filter(|c| !c.trim().is_empty()) {
+            Some(command) => PathBuf::from(command),
+            None => locate(spec, &file).ok_or_else(|| {
+                format!("{} not found. Please ensure it is installed and in your PATH.", spec.command)
+            })?,
+        };
+        let mut args = arguments(spec, &file);
+        args.extend(config.extra_args);
+        let cwd = file
+            .parent()
+            .filter(|dir| dir.is_dir())
+            .map(Path::to_path_buf)
+            .unwrap_or_else(std::env::temp_dir);
+        let timeout = config.timeout_ms.map(Duration::from_millis).unwrap_or(DEFAULT_TIMEOUT);
+
+        let formatted = run_formatter(spec.id, &binary, &args, &cwd, &content, timeout)?;
+        Ok(FormatResult {
+            formatter: Some(spec.id.to_string()),
+            changed: formatted != content,
+            content: formatted,
+        })
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
@@ -0,0 +1,117 @@
+// src-tauri/src/git.rs
+// Locating and running the git CLI shared by the version control commands
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::sync::OnceLock;
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+static GIT_BINARY: OnceLock<Option<String>> = OnceLock::new();
+
+fn find_git() -> Result<&'static str, String> {
+    GIT_BINARY
+        .get_or_init(|| {
+            let mut candidates = vec!["git".to_string()];
+            if cfg!(target_os = "windows") {
+                candidates.push("C:\\Program Files\\Git\\cmd\\git.exe".to_string());
+                candidates.push("C:\\Program Files (x86)\\Git\\cmd\\git.exe".to_string());
+            }
+            candidates.into_iter().find(|candidate| {
+                let mut cmd = Command::new(candidate);
+                cmd.arg("--version")
+                    .stdout(std::process::Stdio::null())
+                    .stderr(std::process::Stdio::null());
+                #[cfg(target_os = "windows")]
+                {
+                    const CREATE_NO_WINDOW: u32 = 0x08000000;
+                    cmd.creation_flags(CREATE_NO_WINDOW);
+                }
+                cmd.status().map(|s| s.success()).unwrap_or(false)
+            })
+        })
+        .as_deref()
+        .ok_or_else(|| "Git not found. Please ensure git is installed and in your PATH.".to_string())
+}
+
+/// A `git` command running in `repo`.
+pub(crate) fn git_command(repo: &Path) -> Result<Command, String> {
+    let mut cmd = Command::new(find_git()?);
+    cmd.arg("-C")
+        .arg(repo)
+        // Status refreshes must not write .git/index, or the file watcher
+        // would see our own writes and refresh forever
+        .env("GIT_OPTIONAL_LOCKS", "0")
+        // Paths from the file manager are literal names, never globs
+        .env("GIT_LITERAL_PATHSPECS", "1")
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .env("LC_ALL", "C");
+
+    #[cfg(target_os = "windows")]
+    {
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    Ok(cmd)
+}
+
+fn failure_message(args: &[&str], output: &Output) -> String {
+    // Some failures (e.g. "nothing to commit") are only explained on stdout
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let detail = if stderr.trim().is_empty() { String::from_utf8_lossy(&output.stdout) } else { stderr };
+    format!("git {} failed: {}", args.first().unwrap_or(&""), detail.trim())
+}
+
+/// Run git with `args` and return raw stdout; fails with git's stderr when
+/// the command exits unsuccessfully.
+pub(crate) fn run_git_bytes(repo: &Path, args: &[&str]) -> Result<Vec<u8>, String> {
+    let output = git_command(repo)?
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+    if !output.status.success() {
+        return Err(failure_message(args, &output));
+    }
+    Ok(output.stdout)
+}
+
+/// Like `run_git`, feeding `input` to git's stdin (for `git apply -`).
+pub(crate) fn run_git_with_input(repo: &Path, args: &[&str], input: &[u8]) -> Result<String, String> {
+    let mut child = git_command(repo)?
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(input).map_err(|e| format!("Failed to write to git: {}", e))?;
+    }
+    let output = child.wait_with_output().map_err(|e| format!("Failed to run git: {}", e))?;
+    if !output.status.success() {
+        return Err(failure_message(args, &output));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+pub(crate) fn run_git(repo: &Path, args: &[&str]) -> Result<String, String> {
+    run_git_bytes(repo, args).map(|out| String::from_utf8_lossy(&out).to_string())
+}
+
+/// Work tree root containing `path`, found by looking for `.git` (a folder,
+/// or a file for worktrees and submodules) without spawning git.
+pub(crate) fn repo_root(path: &Path) -> Option<PathBuf> {
+    let start = if path.is_dir() { path } else { path.parent()? };
+    start
+        .ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .map(Path::to_path_buf)
+}
+
+/// `rel` (git's `/`-separated, repo-relative form) as a native absolute path.
+pub(crate) fn absolute_path(root: &Path, rel: &str) -> PathBuf {
+    rel.trim_end_matches('/').split('/').fold(root.to_path_buf(), |p, part| p.join(part))
+}
@@ -0,0 +1,582 @@
+// src-tauri/src/git_commands.rs
+// Day-to-day git operations: staging, commits, diffs, history, branches, stashes
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use tauri::State;
+
+use crate::git::{git_command, repo_root, run_git, run_git_with_input};
+use crate::git_status::{invalidate, GitStatusCache};
+use crate::path_policy::{canonicalize_lenient, check_read, check_write, FsError};
+
+/// Field and record separators for `--format` output; they can't appear in
+/// names or commit subjects.
+const FIELD_SEP: char = '\u{1f}';
+const RECORD_SEP: char = '\u{1e}';
+
+const DEFAULT_LOG_LIMIT: usize = 50;
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GitLineKind {
+    Context,
+    Added,
+    Removed,
+    /// `\ No newline at end of file`
+    NoNewline,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct GitDiffLine {
+    kind: GitLineKind,
+    content: String,
+    old_line: Option<u32>,
+    new_line: Option<u32>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct GitHunk {
+    /// The `@@ -a,b +c,d @@` line; pass it back to stage or unstage the hunk
+    header: String,
+    old_start: u32,
+    old_lines: u32,
+    new_start: u32,
+    new_lines: u32,
+    lines: Vec<GitDiffLine>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct GitFileDiff {
+    path: String,
+    binary: bool,
+    hunks: Vec<GitHunk>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct GitCommit {
+    hash: String,
+    short_hash: String,
+    author_name: String,
+    author_email: String,
+    /// ISO 8601 author date
+    date: String,
+    subject: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct GitLogPage {
+    commits: Vec<GitCommit>,
+    has_more: bool,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct GitBranch {
+    name: String,
+    is_remote: bool,
+    is_current: bool,
+    commit: String,
+    upstream: Option<String>,
+    /// ISO 8601 date of the last commit
+    updated_at: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct GitStash {
+    /// Position in the stash list (`stash@{index}`)
+    index: usize,
+    message: String,
+    date: String,
+}
+
+// ─── Private helpers ──────────────────────────────────────────────────────────
+
+/// Repository root for `path`, which must be inside a work tree.
+fn resolve_repo(path: &str) -> Result<PathBuf, FsError> {
+    let resolved = check_read(path)?;
+    repo_root(&resolved).ok_or_else(|| format!("Not a git repository: {}", path).into())
+}
+
+/// Repository root for commands that change the index or work tree; the
+/// root must be writable under the path policy.
+fn resolve_repo_for_write(path: &str) -> Result<PathBuf, FsError> {
+    let root = resolve_repo(path)?;
+    check_write(&root.to_string_lossy())
+}
+
+/// Repo-relative pathspec for `path`. Deleted files can't be canonicalized,
+/// so their nearest existing folder is, to compare against the canonical root.
+fn pathspec(root: &Path, path: &str) -> Result<String, FsError> {
+    let resolved = canonicalize_lenient(Path::new(path))?;
+    let rel = resolved
+        .strip_prefix(root)
+        .map_err(|_| format!("{} is outside the repository {}", path, root.display()))?;
+    let rel = rel.to_string_lossy().replace('\\', "/");
+    Ok(if rel.is_empty() { ".".to_string() } else { rel })
+}
+
+/// Reject a branch or revision that git would parse as an option.
+fn revision_arg(value: &str) -> Result<&str, FsError> {
+    if value.starts_with('-') {
+        return Err(format!("Invalid revision: {}", value).into());
+    }
+    Ok(value)
+}
+
+fn pathspecs(root: &Path, paths: &[String]) -> Result<Vec<String>, FsError> {
+    if paths.is_empty() {
+        return Err("No paths given".into());
+    }
+    paths.iter().map(|p| pathspec(root, p)).collect()
+}
+
+fn has_head(root: &Path) -> bool {
+    run_git(root, &["rev-parse", "--verify", "--quiet", "HEAD"]).is_ok()
+}
+
+fn parse_range(range: &str) -> (u32, u32) {
+    let mut parts = range.splitn(2, ',');
+    let start = parts.next().and_then(|s| s.parse().ok()).unwrap_or(0);
+    let count = parts.next().map(|c| c.parse().unwrap_or(1)).unwrap_or(1);
+    (start, count)
+}
+
+/// Split a single-file unified diff into its header and hunks (raw text of
+/// each, header line included).
+fn split_patch(diff: &str) -> (String, Vec<String>) {
+    let mut header = String::new();
+    let mut hunks: Vec<String> = Vec::new();
+    for line in diff.split_inclusive('\n') {
+        if line.starts_with("@@") {
+            hunks.push(String::new());
+        }
+        match hunks.last_mut() {
+            Some(hunk) => hunk.push_str(line),
+            None => header.push_str(line),
+        }
+    }
+    (header, hunks)
+}
+
+fn parse_hunk(raw: &str) -> Option<GitHunk> {
+    let mut lines = raw.lines();
+    let header = lines.next()?.to_string();
+    // @@ -old_start,old_lines +new_start,new_lines @@ optional context
+    let ranges = header.strip_prefix("@@ ")?.split(" @@").next()?;
+    let mut parts = ranges.split(' ');
+    let (old_start, old_lines) = parse_range(parts.next()?.strip_prefix('-')?);
+    let (new_start, new_lines) = parse_range(parts.next()?.strip_prefix('+')?);
+
+    let (mut old_no, mut new_no) = (old_start, new_start);
+    let mut out = Vec::new();
+    for line in lines {
+        let (kind, content) = match line.chars().next() {
+            Some('+') => (GitLineKind::Added, &line[1..]),
+            Some('-') => (GitLineKind::Removed, &line[1..]),
+            Some('\\') => (GitLineKind::NoNewline, line),
+            Some(' ') => (GitLineKind::Context, &line[1..]),
+            _ => (GitLineKind::Context, line),
+        };
+        let (old_line, new_line) = match kind {
+            GitLineKind::Added => (None, Some(new_no)),
+            GitLineKind::Removed => (Some(old_no), None),
+            GitLineKind::Context => (Some(old_no), Some(new_no)),
+            GitLineKind::NoNewline => (None, None),
+        };
+        match kind {
+            GitLineKind::Added => new_no += 1,
+            GitLineKind::Removed => old_no += 1,
+            GitLineKind::Context => {
+                old_no += 1;
+                new_no += 1;
+            }
+            GitLineKind::NoNewline => {}
+        }
+        out.push(GitDiffLine { kind, content: content.to_string(), old_line, new_line });
+    }
+
+    Some(GitHunk { header, old_start, old_lines, new_start, new_lines, lines: out })
+}
+
+/// `git diff` that also treats exit code 1 (differences found, as returned
+/// by `--no-index`) as success.
+fn run_diff(root: &Path, args: &[&str]) -> Result<String, String> {
+    let output = git_command(root)?
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+    match output.status.code() {
+        Some(0) | Some(1) => Ok(String::from_utf8_lossy(&output.stdout).to_string()),
+        _ => Err(format!("git diff failed: {}", String::from_utf8_lossy(&output.stderr).trim())),
+    }
+}
+
+fn is_untracked(root: &Path, spec: &str) -> bool {
+    run_git(root, &["ls-files", "--others", "--exclude-standard", "--", spec])
+        .map(|out| !out.trim().is_empty())
+        .unwrap_or(false)
+}
+
+/// Raw unified diff of one file. `against` is `index` (work tree vs index),
+/// `staged` (index vs HEAD) or `head` (work tree vs HEAD).
+fn file_diff_text(root: &Path, spec: &str, against: &str) -> Result<String, String> {
+    let base = ["diff", "--no-color", "--no-ext-diff", "-U3"];
+    match against {
+        "index" if is_untracked(root, spec) => {
+            run_diff(root, &[&base[..], &["--no-index", "--", "/dev/null", spec]].concat())
+        }
+        "index" => run_diff(root, &[&base[..], &["--", spec]].concat()),
+        "staged" => run_diff(root, &[&base[..], &["--cached", "--", spec]].concat()),
+        "head" if has_head(root) => run_diff(root, &[&base[..], &["HEAD", "--", spec]].concat()),
+        "head" => run_diff(root, &[&base[..], &["--cached", "--", spec]].concat()),
+        other => Err(format!("Unknown diff base: {}", other)),
+    }
+}
+
+/// Apply a single hunk of `file`'s diff to the index.
+fn apply_hunk(root: &Path, spec: &str, hunk_header: &str, unstage: bool) -> Result<(), String> {
+    let diff = file_diff_text(root, spec, if unstage { "staged" } else { "index" })?;
+    let (header, hunks) = split_patch(&diff);
+    let hunk = hunks
+        .iter()
+        .find(|h| h.lines().next() == Some(hunk_header))
+        .ok_or("Hunk not found; the file changed since the diff was loaded")?;
+
+    let patch = format!("{}{}", header, hunk);
+    let mut args = vec!["apply", "--cached", "--whitespace=nowarn"];
+    if unstage {
+        args.push("--reverse");
+    }
+    args.push("-");
+    run_git_with_input(root, &args, patch.as_bytes()).map(|_| ())
+}
+
+fn parse_log(output: &str) -> Vec<GitCommit> {
+    output
+        .split(RECORD_SEP)
+        .map(|r| r.trim_start_matches('\n'))
+        .filter(|r| !r.is_empty())
+        .filter_map(|record| {
+            let f: Vec<&str> = record.split(FIELD_SEP).collect();
+            (f.len() >= 6).then(|| GitCommit {
+                hash: f[0].to_string(),
+                short_hash: f[1].to_string(),
+                author_name: f[2].to_string(),
+                author_email: f[3].to_string(),
+                date: f[4].to_string(),
+                subject: f[5].to_string(),
+            })
+        })
+        .collect()
+}
+
+// ─── Staging ──────────────────────────────────────────────────────────────────
+
+/// Stage files or folders (including deletions).
+#[tauri::command]
+pub async fn git_stage(cache: State<'_, GitStatusCache>, repo: String, paths: Vec<String>) -> Result<(), FsError> {
+    let repos = cache.repos();
+    tokio::task::spawn_blocking(move || {
+        let root = resolve_repo_for_write(&repo)?;
+        let specs = pathspecs(&root, &paths)?;
+        let mut args = vec!["add", "--all", "--"];
+        args.extend(specs.iter().map(String::as_str));
+        run_git(&root, &args)?;
+        invalidate(&repos, &root);
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[tauri::command]
+pub async fn git_unstage(cache: State<'_, GitStatusCache>, repo: String, paths: Vec<String>) -> Result<(), FsError> {
+    let repos = cache.repos();
+    tokio::task::spawn_blocking(move || {
+        let root = resolve_repo_for_write(&repo)?;
+        let specs = pathspecs(&root, &paths)?;
+        // Before the first commit there is no HEAD to restore from
+        let mut args = if has_head(&root) {
+            vec!["restore", "--staged", "--"]
+        } else {
+            vec!["rm", "--cached", "-r", "--quiet", "--"]
+        };
+        args.extend(specs.iter().map(String::as_str));
+        run_git(&root, &args)?;
+        invalidate(&repos, &root);
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Stage one hunk of a file's unstaged changes, identified by the header
+/// returned from `git_diff_file`.
+#[tauri::command]
+pub async fn git_stage_hunk(
+    cache: State<'_, GitStatusCache>,
+    path: String,
+    hunk_header: String,
+) -> Result<(), FsError> {
+    let repos = cache.repos();
+    tokio::task::spawn_blocking(move || {
+        let root = resolve_repo_for_write(&path)?;
+        apply_hunk(&root, &pathspec(&root, &path)?, &hunk_header, false)?;
+        invalidate(&repos, &root);
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Remove one hunk of a file's staged changes from the index.
+#[tauri::command]
+pub async fn git_unstage_hunk(
+    cache: State<'_, GitStatusCache>,
+    path: String,
+    hunk_header: String,
+) -> Result<(), FsError> {
+    let repos = cache.repos();
+    tokio::task::spawn_blocking(move || {
+        let root = resolve_repo_for_write(&path)?;
+        apply_hunk(&root, &pathspec(&root, &path)?, &hunk_header, true)?;
+        invalidate(&repos, &root);
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+// ─── Commits and history ──────────────────────────────────────────────────────
+
+/// Commit the staged changes and return the new commit.
+#[tauri::command]
+pub async fn git_commit(
+    cache: State<'_, GitStatusCache>,
+    repo: String,
+    message: String,
+    amend: Option<bool>,
+) -> Result<GitCommit, FsError> {
+    let repos = cache.repos();
+    tokio::task::spawn_blocking(move || {
+        if message.trim().is_empty() {
+            return Err("Commit message is required".into());
+        }
+        let root = resolve_repo_for_write(&repo)?;
+        // The message goes through stdin; only surrounding blank lines and
+        // trailing spaces are cleaned up, so lines like "#42: ..." survive
+        let mut args = vec!["commit", "--file=-", "--cleanup=whitespace"];
+        if amend.unwrap_or(false) {
+            args.push("--amend");
+        }
+        run_git_with_input(&root, &args, message.as_bytes())?;
+        invalidate(&repos, &root);
+
+        let format = format!("--format=%H{0}%h{0}%an{0}%ae{0}%aI{0}%s{1}", FIELD_SEP, RECORD_SEP);
+        parse_log(&run_git(&root, &["log", "-1", &format])?)
+            .into_iter()
+            .next()
+            .ok_or_else(|| "Commit created but could not be read back".into())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// One page of history, newest first. `file` limits it to commits touching
+/// that path (following renames).
+#[tauri::command]
+pub async fn git_log(
+    repo: String,
+    skip: Option<usize>,
+    limit: Option<usize>,
+    file: Option<String>,
+) -> Result<GitLogPage, FsError> {
+    tokio::task::spawn_blocking(move || {
+        let root = resolve_repo(&repo)?;
+        if !has_head(&root) {
+            return Ok(GitLogPage { commits: Vec::new(), has_more: false });
+        }
+        let limit = limit.unwrap_or(DEFAULT_LOG_LIMIT).max(1);
+        let format = format!("--format=%H{0}%h{0}%an{0}%ae{0}%aI{0}%s{1}", FIELD_SEP, RECORD_SEP);
+        let skip_arg = format!("--skip={}", skip.unwrap_or(0));
+        // One extra commit tells whether another page exists
+        let count_arg = format!("--max-count={}", limit + 1);
+        let spec = file.as_deref().map(|f| pathspec(&root, f)).transpose()?;
+
+        let mut args = vec!["log", format.as_str(), skip_arg.as_str(), count_arg.as_str()];
+        if let Some(spec) = spec.as_deref() {
+            args.extend(["--follow", "--", spec]);
+        }
+
+        let mut commits = parse_log(&run_git(&root, &args)?);
+        let has_more = commits.len() > limit;
+        commits.truncate(limit);
+        Ok(GitLogPage { commits, has_more })
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Diff of one file, see `file_diff_text` for `against` (default `index`).
+#[tauri::command]
+pub async fn git_diff_file(path: String, against: Option<String>) -> Result<GitFileDiff, FsError> {
+    tokio::task::spawn_blocking(move || {
+        let root = resolve_repo(&path)?;
+        let spec = pathspec(&root, &path)?;
+        let diff = file_diff_text(&root, &spec, against.as_deref().unwrap_or("index"))?;
+
+        let (header, hunks) = split_patch(&diff);
+        let binary = header.lines().any(|l| l.starts_with("Binary files ") || l == "GIT binary patch");
+        Ok(GitFileDiff {
+            path,
+            binary,
+            hunks: hunks.iter().filter_map(|h| parse_hunk(h)).collect(),
+        })
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+// ─── Branches ─────────────────────────────────────────────────────────────────
+
+/// Local branches, then remote-tracking branches.
+#[tauri::command]
+pub async fn git_branches(repo: String) -> Result<Vec<GitBranch>, FsError> {
+    tokio::task::spawn_blocking(move || {
+        let root = resolve_repo(&repo)?;
+        let format = format!(
+            "--format=%(refname){0}%(refname:short){0}%(objectname:short){0}%(upstream:short){0}%(HEAD){0}%(committerdate:iso-strict)",
+            FIELD_SEP
+        );
+        let output = run_git(&root, &["for-each-ref", &format, "refs/heads", "refs/remotes"])?;
+
+        let branches = output
+            .lines()
+            .filter_map(|line| {
+                let f: Vec<&str> = line.split(FIELD_SEP).collect();
+                if f.len() < 6 || f[0].ends_with("/HEAD") {
+                    return None;
+                }
+                Some(GitBranch {
+                    name: f[1].to_string(),
+                    is_remote: f[0].starts_with("refs/remotes/"),
+                    is_current: f[4] == "*",
+                    commit: f[2].to_string(),
+                    upstream: (!f[3].is_empty()).then(|| f[3].to_string()),
+                    updated_at: f[5].to_string(),
+                })
+            })
+            .collect();
+        Ok(branches)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Create a branch at `start_point` (default HEAD), switching to it when
+/// `checkout` is set.
+#[tauri::command]
+pub async fn git_create_branch(
+    cache: State<'_, GitStatusCache>,
+    repo: String,
+    name: String,
+    start_point: Option<String>,
+    checkout: Option<bool>,
+) -> Result<(), FsError> {
+    let repos = cache.repos();
+    tokio::task::spawn_blocking(move || {
+        let root = resolve_repo_for_write(&repo)?;
+        revision_arg(&name)?;
+        run_git(&root, &["check-ref-format", "--branch", &name])
+            .map_err(|_| format!("Invalid branch name: {}", name))?;
+
+        let mut args = if checkout.unwrap_or(false) { vec!["switch", "-c"] } else { vec!["branch"] };
+        args.push(&name);
+        if let Some(start) = start_point.as_deref().filter(|s| !s.is_empty()) {
+            args.push(revision_arg(start)?);
+        }
+        run_git(&root, &args)?;
+        invalidate(&repos, &root);
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Switch to `name`. A remote branch name without a local branch creates a
+/// tracking branch.
+#[tauri::command]
+pub async fn git_checkout_branch(cache: State<'_, GitStatusCache>, repo: String, name: String) -> Result<(), FsError> {
+    let repos = cache.repos();
+    tokio::task::spawn_blocking(move || {
+        let root = resolve_repo_for_write(&repo)?;
+        run_git(&root, &["switch", revision_arg(&name)?])?;
+        invalidate(&repos, &root);
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+// ─── Stashes ──────────────────────────────────────────────────────────────────
+
+#[tauri::command]
+pub async fn git_stash_list(repo: String) -> Result<Vec<GitStash>, FsError> {
+    tokio::task::spawn_blocking(move || {
+        let root = resolve_repo(&repo)?;
+        let format = format!("--format=%gs{0}%cI", FIELD_SEP);
+        let output = run_git(&root, &["stash", "list", &format])?;
+        Ok(output
+            .lines()
+            .enumerate()
+            .map(|(index, line)| {
+                let (message, date) = line.split_once(FIELD_SEP).unwrap_or((line, ""));
+                GitStash { index, message: message.to_string(), date: date.to_string() }
+            })
+            .collect())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[tauri::command]
+pub async fn git_stash_push(
+    cache: State<'_, GitStatusCache>,
+    repo: String,
+    message: Option<String>,
+    include_untracked: Option<bool>,
+) -> Result<(), FsError> {
+    let repos = cache.repos();
+    tokio::task::spawn_blocking(move || {
+        let root = resolve_repo_for_write(&repo)?;
+        let mut args = vec!["stash", "push"];
+        if include_untracked.unwrap_or(false) {
+            args.push("--include-untracked");
+        }
+        if let Some(message) = message.as_deref().filter(|m| !m.trim().is_empty()) {
+            args.extend(["--message", message]);
+        }
+        run_git(&root, &args)?;
+        invalidate(&repos, &root);
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Apply and drop a stash (the latest by default). On conflicts git keeps
+/// the stash and the error explains what to resolve.
+#[tauri::command]
+pub async fn git_stash_pop(cache: State<'_, GitStatusCache>, repo: String, index: Option<usize>) -> Result<(), FsError> {
+    let repos = cache.repos();
+    tokio::task::spawn_blocking(move || {
+        let root = resolve_repo_for_write(&repo)?;
+        let stash_ref = format!("stash@{{{}}}", index.unwrap_or(0));
+        let result = run_git(&root, &["stash", "pop", &stash_ref]);
+        invalidate(&repos, &root);
+        result?;
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
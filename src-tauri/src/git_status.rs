@@ -0,0 +1,471 @@
+// src-tauri/src/git_status.rs
+// Cached `git status` per repository, invalidated by a file watcher
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, State};
+
+use crate::git::{absolute_path, repo_root, run_git_bytes};
+use crate::path_policy::{check_read, FsError};
+
+/// Emitted (debounced) when files in a watched repository change, so views
+/// can re-query their status.
+const CHANGED_EVENT: &str = "git-status-changed";
+const CHANGE_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Repositories whose status and watcher are kept; the least recently used
+/// one is dropped beyond this.
+const MAX_CACHED_REPOS: usize = 8;
+
+/// How long a status stays valid when the repo couldn't be watched (e.g.
+/// the inotify watch limit was reached).
+const UNWATCHED_TTL: Duration = Duration::from_secs(2);
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum GitFileState {
+    Ignored,
+    Untracked,
+    Modified,
+    Added,
+    Deleted,
+    Renamed,
+    Copied,
+    TypeChanged,
+    Conflicted,
+}
+
+impl GitFileState {
+    fn from_code(code: char) -> Option<Self> {
+        match code {
+            'M' => Some(GitFileState::Modified),
+            'A' => Some(GitFileState::Added),
+            'D' => Some(GitFileState::Deleted),
+            'R' => Some(GitFileState::Renamed),
+            'C' => Some(GitFileState::Copied),
+            'T' => Some(GitFileState::TypeChanged),
+            _ => None,
+        }
+    }
+
+    fn is_tracked_change(&self) -> bool {
+        !matches!(self, GitFileState::Ignored | GitFileState::Untracked | GitFileState::Conflicted)
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct GitFileStatus {
+    pub(crate) path: String,
+    /// Repo-relative path with `/` separators; folders end with `/`
+    pub(crate) rel_path: String,
+    pub(crate) state: GitFileState,
+    /// Has changes in the index
+    pub(crate) staged: bool,
+    /// Has changes in the work tree not yet staged
+    pub(crate) unstaged: bool,
+    /// Source path of a rename or copy
+    pub(crate) orig_path: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RepoInfo {
+    pub(crate) root: String,
+    /// `None` when HEAD is detached
+    pub(crate) branch: Option<String>,
+    /// Commit id of HEAD; `None` before the first commit
+    pub(crate) head: Option<String>,
+    pub(crate) upstream: Option<String>,
+    pub(crate) ahead: u32,
+    pub(crate) behind: u32,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RepoStatus {
+    pub(crate) repo: RepoInfo,
+    pub(crate) files: Vec<GitFileStatus>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct EntryGitStatus {
+    path: String,
+    /// For folders: the most significant state of anything inside
+    state: GitFileState,
+    staged: bool,
+    unstaged: bool,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct DirectoryGitStatus {
+    /// `None` when the directory is not inside a repository
+    repo: Option<RepoInfo>,
+    /// Children of the directory that have a status; clean entries are omitted
+    entries: Vec<EntryGitStatus>,
+}
+
+// ─── Parsing ──────────────────────────────────────────────────────────────────
+
+/// Parse `git status --porcelain=v2 --branch -z` output.
+fn parse_status(root: &Path, output: &[u8]) -> RepoStatus {
+    let mut repo = RepoInfo {
+        root: root.to_string_lossy().to_string(),
+        branch: None,
+        head: None,
+        upstream: None,
+        ahead: 0,
+        behind: 0,
+    };
+    let mut files = Vec::new();
+
+    let mut records = output
+        .split(|&b| b == 0)
+        .filter(|r| !r.is_empty())
+        .map(|r| String::from_utf8_lossy(r).to_string());
+
+    let file = |rel: &str, state, staged, unstaged, orig_path: Option<String>| GitFileStatus {
+        path: absolute_path(root, rel).to_string_lossy().to_string(),
+        rel_path: rel.to_string(),
+        state,
+        staged,
+        unstaged,
+        orig_path,
+    };
+
+    while let Some(record) = records.next() {
+        if let Some(header) = record.strip_prefix("# ") {
+            let (key, value) = header.split_once(' ').unwrap_or((header, ""));
+            match key {
+                "branch.oid" if value != "(initial)" => repo.head = Some(value.to_string()),
+                "branch.head" if value != "(detached)" => repo.branch = Some(value.to_string()),
+                "branch.upstream" => repo.upstream = Some(value.to_string()),
+                "branch.ab" => {
+                    for part in value.split_whitespace() {
+                        if let Some(n) = part.strip_prefix('+') {
+                            repo.ahead = n.parse().unwrap_or(0);
+                        } else if let Some(n) = part.strip_prefix('-') {
+                            repo.behind = n.parse().unwrap_or(0);
+                        }
+                    }
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        let (kind, rest) = record.split_at(1.min(record.len()));
+        let rest = rest.strip_prefix(' ').unwrap_or(rest);
+        match kind {
+            "1" | "2" => {
+                // <XY> <sub> <mH> <mI> <mW> <hH> <hI> [<Xscore>] <path>
+                let field_count = if kind == "1" { 8 } else { 9 };
+                let fields: Vec<&str> = rest.splitn(field_count, ' ').collect();
+                let (Some(xy), Some(rel)) = (fields.first(), fields.last()) else { continue };
+                let mut codes = xy.chars();
+                let (x, y) = (codes.next().unwrap_or('.'), codes.next().unwrap_or('.'));
+                let state = GitFileState::from_code(x)
+                    .or_else(|| GitFileState::from_code(y))
+                    .unwrap_or(GitFileState::Modified);
+                // Renames and copies are followed by the original path
+                let orig_path = if kind == "2" { records.next() } else { None };
+                files.push(file(rel, state, x != '.', y != '.', orig_path));
+            }
+            "u" => {
+                let rel = rest.splitn(10, ' ').last().unwrap_or_default();
+                files.push(file(rel, GitFileState::Conflicted, false, true, None));
+            }
+            "?" => files.push(file(rest, GitFileState::Untracked, false, true, None)),
+            "!" => files.push(file(rest, GitFileState::Ignored, false, false, None)),
+            _ => {}
+        }
+    }
+
+    RepoStatus { repo, files }
+}
+
+fn load_status(root: &Path) -> Result<RepoStatus, String> {
+    let output = run_git_bytes(
+        root,
+        &["status", "--porcelain=v2", "--branch", "-z", "--untracked-files=normal", "--ignored"],
+    )?;
+    Ok(parse_status(root, &output))
+}
+
+/// Status of the direct children of `dir`. Folders summarize their
+/// contents; a folder that is itself untracked or ignored passes that state
+/// to everything listed inside it.
+fn directory_status(status: &RepoStatus, root: &Path, dir: &Path) -> Vec<EntryGitStatus> {
+    let rel_dir = match dir.strip_prefix(root) {
+        Ok(r) => r.to_string_lossy().replace('\\', "/"),
+        Err(_) => return Vec::new(),
+    };
+    let prefix = if rel_dir.is_empty() { String::new() } else { format!("{}/", rel_dir) };
+
+    // Listing inside an untracked or ignored folder: git only reports the folder
+    if let Some(parent) = status
+        .files
+        .iter()
+        .find(|f| f.rel_path.ends_with('/') && prefix.starts_with(&f.rel_path))
+    {
+        return std::fs::read_dir(dir)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|entry| EntryGitStatus {
+                        path: entry.path().to_string_lossy().to_string(),
+                        state: parent.state,
+                        staged: false,
+                        unstaged: parent.unstaged,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+    }
+
+    let mut children: HashMap<String, EntryGitStatus> = HashMap::new();
+    for f in &status.files {
+        let Some(below) = f.rel_path.strip_prefix(&prefix) else { continue };
+        let (child, nested) = match below.trim_end_matches('/').split_once('/') {
+            Some((child, _)) => (child, true),
+            None => (below.trim_end_matches('/'), false),
+        };
+        if child.is_empty() {
+            continue;
+        }
+        // Ignored files don't make the folder containing them interesting
+        if nested && f.state == GitFileState::Ignored {
+            continue;
+        }
+        let state = if nested && f.state.is_tracked_change() { GitFileState::Modified } else { f.state };
+
+        let path = absolute_path(root, &format!("{}{}", prefix, child)).to_string_lossy().to_string();
+        let entry = children.entry(child.to_string()).or_insert(EntryGitStatus {
+            path,
+            state,
+            staged: false,
+            unstaged: false,
+        });
+        entry.state = entry.state.max(state);
+        entry.staged |= f.staged;
+        entry.unstaged |= f.unstaged;
+    }
+
+    let mut entries: Vec<EntryGitStatus> = children.into_values().collect();
+    entries.sort_by(|a, b| a.path.cmp(&b.path));
+    entries
+}
+
+// ─── Cache ────────────────────────────────────────────────────────────────────
+
+struct CachedRepo {
+    status: Option<Arc<RepoStatus>>,
+    refreshed_at: Instant,
+    last_used: Instant,
+    /// Ignored folders from the last status; changes inside them (build
+    /// output, dependencies) don't invalidate anything
+    ignored_dirs: Vec<PathBuf>,
+    watcher: Option<RecommendedWatcher>,
+}
+
+type RepoMap = Arc<Mutex<HashMap<PathBuf, CachedRepo>>>;
+
+pub struct GitStatusCache {
+    repos: RepoMap,
+}
+
+impl Default for GitStatusCache {
+    fn default() -> Self {
+        Self {
+            repos: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+/// Changes under `.git` that don't affect status (object writes, reflogs,
+/// lock files) are ignored.
+fn is_relevant_change(root: &Path, path: &Path, ignored_dirs: &[PathBuf]) -> bool {
+    if ignored_dirs.iter().any(|dir| path.starts_with(dir)) {
+        return false;
+    }
+    match path.strip_prefix(root.join(".git")) {
+        Ok(inside) => {
+            let first = inside.components().next().map(|c| c.as_os_str().to_string_lossy().to_string());
+            let is_lock = path.extension().map(|e| e == "lock").unwrap_or(false);
+            !is_lock && !matches!(first.as_deref(), Some("objects") | Some("logs"))
+        }
+        Err(_) => true,
+    }
+}
+
+fn start_watcher(app: &AppHandle, repos: &RepoMap, root: &Path) -> Option<RecommendedWatcher> {
+    let app = app.clone();
+    let repos_for_events = repos.clone();
+    let watched_root = root.to_path_buf();
+    let pending = Arc::new(AtomicBool::new(false));
+
+    let handler = move |event: notify::Result<notify::Event>| {
+        let Ok(event) = event else { return };
+        {
+            let Ok(mut map) = repos_for_events.lock() else { return };
+            let Some(cached) = map.get_mut(&watched_root) else { return };
+            if !event.paths.iter().any(|p| is_relevant_change(&watched_root, p, &cached.ignored_dirs)) {
+                return;
+            }
+            cached.status = None;
+        }
+        if !pending.swap(true, Ordering::AcqRel) {
+            let app = app.clone();
+            let pending = pending.clone();
+            let root = watched_root.to_string_lossy().to_string();
+            std::thread::spawn(move || {
+                std::thread::sleep(CHANGE_DEBOUNCE);
+                pending.store(false, Ordering::Release);
+                let _ = app.emit(CHANGED_EVENT, root);
+            });
+        }
+    };
+
+    let mut watcher = match notify::recommended_watcher(handler) {
+        Ok(w) => w,
+        Err(e) => {
+            log::warn!("git status: failed to create watcher: {}", e);
+            return None;
+        }
+    };
+    if let Err(e) = watcher.watch(root, RecursiveMode::Recursive) {
+        log::warn!("git status: cannot watch {}: {}", root.display(), e);
+        return None;
+    }
+    Some(watcher)
+}
+
+/// Cached status for the repository at `root`, reloading it when files
+/// changed since the last call.
+pub(crate) fn repo_status(app: &AppHandle, repos: &RepoMap, root: &Path) -> Result<Arc<RepoStatus>, String> {
+    {
+        let mut map = repos.lock().map_err(|e| e.to_string())?;
+        if let Some(cached) = map.get_mut(root) {
+            cached.last_used = Instant::now();
+            let fresh = cached.watcher.is_some() || cached.refreshed_at.elapsed() < UNWATCHED_TTL;
+            if let (Some(status), true) = (&cached.status, fresh) {
+                return Ok(status.clone());
+            }
+        }
+    }
+
+    let status = Arc::new(load_status(root)?);
+    let ignored_dirs: Vec<PathBuf> = status
+        .files
+        .iter()
+        .filter(|f| f.state == GitFileState::Ignored && f.rel_path.ends_with('/'))
+        .map(|f| absolute_path(root, &f.rel_path))
+        .collect();
+
+    let needs_watcher = !repos.lock().map_err(|e| e.to_string())?.contains_key(root);
+    let watcher = if needs_watcher { start_watcher(app, repos, root) } else { None };
+
+    let mut map = repos.lock().map_err(|e| e.to_string())?;
+    let now = Instant::now();
+    let cached = map.entry(root.to_path_buf()).or_insert_with(|| CachedRepo {
+        status: None,
+        refreshed_at: now,
+        last_used: now,
+        ignored_dirs: Vec::new(),
+        watcher: None,
+    });
+    cached.status = Some(status.clone());
+    cached.refreshed_at = now;
+    cached.last_used = now;
+    cached.ignored_dirs = ignored_dirs;
+    if watcher.is_some() {
+        cached.watcher = watcher;
+    }
+
+    // Evicted watchers are dropped after the lock is released, since their
+    // event threads take the same lock
+    let mut evicted = Vec::new();
+    while map.len() > MAX_CACHED_REPOS {
+        let Some(oldest) = map.iter().min_by_key(|(_, c)| c.last_used).map(|(k, _)| k.clone()) else {
+            break;
+        };
+        evicted.extend(map.remove(&oldest));
+    }
+    drop(map);
+    drop(evicted);
+
+    Ok(status)
+}
+
+/// Forget the cached status of the repository at `root`, e.g. after running
+/// a git command that changes it.
+pub(crate) fn invalidate(repos: &RepoMap, root: &Path) {
+    if let Ok(mut map) = repos.lock() {
+        if let Some(cached) = map.get_mut(root) {
+            cached.status = None;
+        }
+    }
+}
+
+impl GitStatusCache {
+    pub(crate) fn repos(&self) -> RepoMap {
+        self.repos.clone()
+    }
+}
+
+// ─── Commands ─────────────────────────────────────────────────────────────────
+
+/// Full status of the repository containing `path`, or `None` outside a
+/// repository.
+#[tauri::command]
+pub async fn get_git_repo_status(
+    app: AppHandle,
+    cache: State<'_, GitStatusCache>,
+    path: String,
+) -> Result<Option<RepoStatus>, FsError> {
+    let repos = cache.repos();
+    tokio::task::spawn_blocking(move || {
+        let resolved = check_read(&path)?;
+        let Some(root) = repo_root(&resolved) else { return Ok(None) };
+        Ok(Some(repo_status(&app, &repos, &root)?.as_ref().clone()))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Branch info plus the status of each child of `path`, for decorating a
+/// directory listing.
+#[tauri::command]
+pub async fn get_git_directory_status(
+    app: AppHandle,
+    cache: State<'_, GitStatusCache>,
+    path: String,
+) -> Result<DirectoryGitStatus, FsError> {
+    let repos = cache.repos();
+    tokio::task::spawn_blocking(move || {
+        let dir = check_read(&path)?;
+        let Some(root) = repo_root(&dir) else {
+            return Ok(DirectoryGitStatus { repo: None, entries: Vec::new() });
+        };
+        let status = repo_status(&app, &repos, &root)?;
+        Ok(DirectoryGitStatus {
+            repo: Some(status.repo.clone()),
+            entries: directory_status(&status, &root, &dir),
+        })
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Drop the cached status so the next query reloads it.
+#[tauri::command]
+pub fn refresh_git_status(cache: State<'_, GitStatusCache>, path: String) -> Result<(), FsError> {
+    let resolved = check_read(&path)?;
+    if let Some(root) = repo_root(&resolved) {
+        invalidate(&cache.repos, &root);
+    }
+    Ok(())
+}
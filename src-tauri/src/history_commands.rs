@@ -0,0 +1,335 @@
+use std::fs;
+use std::path::Path;
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tauri::State;
+
+use crate::diff::{diff_text, DiffHunk};
+use crate::history_db::HistoryDb;
+use crate::path_policy::{check_read, check_write, FsError};
+
+/// Larger files are not kept in history.
+const MAX_FILE_BYTES: usize = 2 * 1024 * 1024;
+/// Versions kept per file besides its original; older ones are dropped first.
+const MAX_VERSIONS_PER_FILE: i64 = 50;
+/// Versions older than this are dropped, except each file's newest.
+const MAX_AGE_DAYS: i64 = 30;
+/// Budget for all stored contents; the oldest versions go when it's exceeded.
+const MAX_TOTAL_BYTES: i64 = 256 * 1024 * 1024;
+
+const DEFAULT_CONTEXT: usize = 3;
+
+// ─── Public data structures ───────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryVersion {
+    pub id: i64,
+    pub path: String,
+    pub hash: String,
+    pub size: i64,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryVersionContent {
+    pub version: HistoryVersion,
+    pub content: String,
+}
+
+/// A stored version compared with the file as it is now.
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryDiff {
+    pub version: HistoryVersion,
+    /// The file no longer exists; the diff is against an empty file
+    pub file_missing: bool,
+    pub identical: bool,
+    pub hunks: Vec<DiffHunk>,
+    pub added: usize,
+    pub removed: usize,
+}
+
+// ─── Private helpers ──────────────────────────────────────────────────────────
+
+fn now_iso() -> String {
+    chrono::Utc::now().format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string()
+}
+
+fn path_key(path: &Path) -> String {
+    path.to_string_lossy().to_string()
+}
+
+/// Key of a file in the history. Deleted files still have history, so
+/// paths that no longer exist are resolved as write targets.
+fn history_key(path: &str) -> Result<String, FsError> {
+    let resolved = check_read(path).or_else(|_| check_write(path))?;
+    Ok(path_key(&resolved))
+}
+
+fn sha256_hex(content: &[u8]) -> String {
+    Sha256::digest(content).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+const VERSION_COLUMNS: &str = "id, path, hash, size, created_at";
+
+fn row_to_version(row: &rusqlite::Row) -> rusqlite::Result<HistoryVersion> {
+    Ok(HistoryVersion {
+        id: row.get(0)?,
+        path: row.get(1)?,
+        hash: row.get(2)?,
+        size: row.get(3)?,
+        created_at: row.get(4)?,
+    })
+}
+
+fn fetch_version(conn: &Connection, id: i64) -> Result<HistoryVersion, String> {
+    conn.query_row(
+        &format!("SELECT {} FROM history_versions WHERE id=?1", VERSION_COLUMNS),
+        params![id],
+        row_to_version,
+    )
+    .map_err(|e| match e {
+        rusqlite::Error::QueryReturnedNoRows => format!("History version not found: {}", id),
+        other => other.to_string(),
+    })
+}
+
+fn fetch_content(conn: &Connection, hash: &str) -> Result<Vec<u8>, String> {
+    conn.query_row("SELECT content FROM history_blobs WHERE hash=?1", params![hash], |row| row.get(0))
+        .map_err(|e| e.to_string())
+}
+
+fn prune(conn: &Connection, path: &str) -> Result<(), String> {
+    // The file's first version is the original kept by `record_original`
+    conn.execute(
+        "DELETE FROM history_versions
+         WHERE path=?1
+           AND id <> (SELECT MIN(id) FROM history_versions WHERE path=?1)
+           AND id NOT IN (
+               SELECT id FROM history_versions WHERE path=?1 ORDER BY id DESC LIMIT ?2
+           )",
+        params![path, MAX_VERSIONS_PER_FILE],
+    )
+    .map_err(|e| e.to_string())?;
+    conn.execute(
+        "DELETE FROM history_versions
+         WHERE path=?1
+           AND julianday('now') - julianday(created_at) > ?2
+           AND id <> (SELECT MAX(id) FROM history_versions WHERE path=?1)",
+        params![path, MAX_AGE_DAYS],
+    )
+    .map_err(|e| e.to_string())?;
+
+    // Over budget: drop the oldest versions of any file, a batch at a time
+    loop {
+        conn.execute(
+            "DELETE FROM history_blobs WHERE hash NOT IN (SELECT hash FROM history_versions)",
+            [],
+        )
+        .map_err(|e| e.to_string())?;
+        let total: i64 = conn
+            .query_row("SELECT COALESCE(SUM(size), 0) FROM history_blobs", [], |row| row.get(0))
+            .map_err(|e| e.to_string())?;
+        if total <= MAX_TOTAL_BYTES {
+            return Ok(());
+        }
+        let removed = conn
+            .execute(
+                "DELETE FROM history_versions WHERE id IN (SELECT id FROM history_versions ORDER BY id ASC LIMIT 20)",
+                [],
+            )
+            .map_err(|e| e.to_string())?;
+        if removed == 0 {
+            return Ok(());
+        }
+    }
+}
+
+/// Store `content` as the newest version of `path`, unless it is too large
+/// or unchanged since the last version. Returns the new version, if any.
+fn record_version(
+    conn: &Connection,
+    path: &str,
+    content: &[u8],
+    created_at: &str,
+) -> Result<Option<HistoryVersion>, String> {
+    if content.len() > MAX_FILE_BYTES {
+        return Ok(None);
+    }
+    let hash = sha256_hex(content);
+    let latest: Option<String> = conn
+        .query_row(
+            "SELECT hash FROM history_versions WHERE path=?1 ORDER BY id DESC LIMIT 1",
+            params![path],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+    if latest.as_deref() == Some(hash.as_str()) {
+        return Ok(None);
+    }
+
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    tx.execute(
+        "INSERT OR IGNORE INTO history_blobs (hash, size, content) VALUES (?1, ?2, ?3)",
+        params![hash, content.len() as i64, content],
+    )
+    .map_err(|e| e.to_string())?;
+    tx.execute(
+        "INSERT INTO history_versions (path, hash, size, created_at) VALUES (?1, ?2, ?3, ?4)",
+        params![path, hash, content.len() as i64, created_at],
+    )
+    .map_err(|e| e.to_string())?;
+    let id = tx.last_insert_rowid();
+    prune(&tx, path)?;
+    tx.commit().map_err(|e| e.to_string())?;
+
+    fetch_version(conn, id).map(Some)
+}
+
+/// Before the first write to a file, keep what is on disk, so the original
+/// can always be restored. It doesn't count toward `MAX_VERSIONS_PER_FILE`.
+fn record_original(conn: &Connection, path: &Path) -> Result<(), String> {
+    let key = path_key(path);
+    let known: i64 = conn
+        .query_row("SELECT COUNT(*) FROM history_versions WHERE path=?1", params![key], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    if known > 0 {
+        return Ok(());
+    }
+    let Ok(meta) = fs::metadata(path) else { return Ok(()) };
+    if !meta.is_file() || meta.len() as usize > MAX_FILE_BYTES {
+        return Ok(());
+    }
+    let content = fs::read(path).map_err(|e| format!("Failed to read {}: {}", key, e))?;
+    // Stamped with the capture time, not the file's mtime, so age-based
+    // pruning doesn't drop it right away for an old file
+    record_version(conn, &key, &content, &now_iso()).map(|_| ())
+}
+
+/// History hook for `write_file`: keep the file's previous content the first
+/// time, including for writes that aren't recorded themselves (autosaves).
+/// Failures are logged and never fail the write itself.
+pub(crate) fn before_write(db: &HistoryDb, path: &Path) {
+    let result = db
+        .0
+        .lock()
+        .map_err(|e| e.to_string())
+        .and_then(|conn| record_original(&conn, path));
+    if let Err(e) = result {
+        log::warn!("history: failed to keep original of {}: {}", path.display(), e);
+    }
+}
+
+pub(crate) fn after_write(db: &HistoryDb, path: &Path, content: &[u8]) {
+    let result = db
+        .0
+        .lock()
+        .map_err(|e| e.to_string())
+        .and_then(|conn| record_version(&conn, &path_key(path), content, &now_iso()));
+    if let Err(e) = result {
+        log::warn!("history: failed to record {}: {}", path.display(), e);
+    }
+}
+
+// ─── Commands ─────────────────────────────────────────────────────────────────
+
+/// Stored versions of a file, newest first.
+#[tauri::command]
+pub fn list_file_history(state: State<'_, HistoryDb>, path: String) -> Result<Vec<HistoryVersion>, FsError> {
+    let key = history_key(&path)?;
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM history_versions WHERE path=?1 ORDER BY id DESC",
+            VERSION_COLUMNS
+        ))
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map(params![key], row_to_version)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows)
+}
+
+#[tauri::command]
+pub fn get_history_version(state: State<'_, HistoryDb>, id: i64) -> Result<HistoryVersionContent, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let version = fetch_version(&conn, id)?;
+    let content = String::from_utf8_lossy(&fetch_content(&conn, &version.hash)?).to_string();
+    Ok(HistoryVersionContent { version, content })
+}
+
+/// Line diff from a stored version to the file's current content.
+/// `context` is the number of unchanged lines around each change (default 3).
+#[tauri::command]
+pub fn diff_history_version(
+    state: State<'_, HistoryDb>,
+    id: i64,
+    context: Option<usize>,
+) -> Result<HistoryDiff, FsError> {
+    let (version, old) = {
+        let conn = state.0.lock().map_err(|e| e.to_string())?;
+        let version = fetch_version(&conn, id)?;
+        let content = fetch_content(&conn, &version.hash)?;
+        (version, content)
+    };
+
+    let path = check_read(&version.path)?;
+    let (current, file_missing) = match fs::read(&path) {
+        Ok(bytes) => (bytes, false),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => (Vec::new(), true),
+        Err(e) => return Err(format!("Failed to read {}: {}", version.path, e).into()),
+    };
+
+    let result = diff_text(
+        &String::from_utf8_lossy(&old),
+        &String::from_utf8_lossy(&current),
+        context.unwrap_or(DEFAULT_CONTEXT),
+    );
+    Ok(HistoryDiff {
+        identical: !file_missing && old == current,
+        file_missing,
+        hunks: result.hunks,
+        added: result.added,
+        removed: result.removed,
+        version,
+    })
+}
+
+/// Write a stored version back to its file. The content being replaced is
+/// recorded first, so a restore can itself be undone.
+#[tauri::command]
+pub fn restore_history_version(state: State<'_, HistoryDb>, id: i64) -> Result<HistoryVersion, FsError> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let version = fetch_version(&conn, id)?;
+    let content = fetch_content(&conn, &version.hash)?;
+    let path = check_write(&version.path)?;
+
+    if let Ok(current) = fs::read(&path) {
+        record_version(&conn, &version.path, &current, &now_iso())?;
+    }
+    fs::write(&path, &content).map_err(|e| format!("Failed to restore {}: {}", version.path, e))?;
+    let restored = record_version(&conn, &version.path, &content, &now_iso())?;
+
+    // Restoring the content the file already had records nothing new
+    Ok(restored.unwrap_or(version))
+}
+
+#[tauri::command]
+pub fn clear_file_history(state: State<'_, HistoryDb>, path: String) -> Result<(), FsError> {
+    let key = history_key(&path)?;
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM history_versions WHERE path=?1", params![key])
+        .map_err(|e| e.to_string())?;
+    conn.execute(
+        "DELETE FROM history_blobs WHERE hash NOT IN (SELECT hash FROM history_versions)",
+        [],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
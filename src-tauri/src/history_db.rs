@@ -0,0 +1,38 @@
+use rusqlite::{Connection, Result};
+use std::path::Path;
+use std::sync::Mutex;
+
+/// Managed state wrapping the SQLite connection behind a Mutex.
+pub struct HistoryDb(pub Mutex<Connection>);
+
+/// Open (or create) the SQLite database at `path` and run all CREATE TABLE
+/// migrations. Returns the ready-to-use connection.
+pub fn initialize(path: &Path) -> Result<Connection> {
+    let conn = Connection::open(path)?;
+
+    conn.execute_batch(
+        "
+        PRAGMA journal_mode = WAL;
+
+        -- File contents by SHA-256, shared by identical versions
+        CREATE TABLE IF NOT EXISTS history_blobs (
+            hash    TEXT PRIMARY KEY,
+            size    INTEGER NOT NULL,
+            content BLOB NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS history_versions (
+            id         INTEGER PRIMARY KEY AUTOINCREMENT,
+            path       TEXT NOT NULL,
+            hash       TEXT NOT NULL,
+            size       INTEGER NOT NULL,
+            created_at TEXT NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_history_path ON history_versions(path, id);
+        CREATE INDEX IF NOT EXISTS idx_history_hash ON history_versions(hash);
+        ",
+    )?;
+
+    Ok(conn)
+}
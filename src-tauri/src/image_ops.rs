@@ -0,0 +1,510 @@
+// src-tauri/src/image_ops.rs
+// Image viewer edits saved natively: rotate, flip, crop, resize, convert
+
+use std::fs;
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use image::{DynamicImage, ImageFormat};
+use serde::{Deserialize, Serialize};
+
+use crate::files::get_unique_path;
+use crate::media_info::read_exif_orientation;
+use crate::path_policy::{check_read, check_write};
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+const DEFAULT_JPEG_QUALITY: u8 = 90;
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ImageOperation {
+    /// Clockwise rotation in multiples of 90 degrees
+    Rotate { degrees: i32 },
+    FlipHorizontal,
+    FlipVertical,
+    Crop { x: u32, y: u32, width: u32, height: u32 },
+    /// Resize to fit within the given box; a missing side keeps the aspect ratio
+    Resize { width: Option<u32>, height: Option<u32> },
+    /// Bake the EXIF orientation into the pixels
+    AutoOrient,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct ImageSaveOptions {
+    /// Destination file; defaults to overwriting the source (or a sibling file
+    /// with the new extension when converting)
+    output_path: Option<String>,
+    /// "png", "jpeg" or "webp"; defaults to the source format
+    format: Option<String>,
+    /// JPEG quality 1–100; WebP is always saved losslessly and rejects it
+    quality: Option<u8>,
+    /// Drop EXIF/XMP metadata (camera, GPS, ...) from the output
+    #[serde(default)]
+    strip_metadata: bool,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ImageSaveResult {
+    path: String,
+    width: u32,
+    height: u32,
+    /// True when the pixels were not re-encoded
+    lossless: bool,
+}
+
+fn parse_format(format: &str) -> Result<ImageFormat, String> {
+    match format.to_lowercase().as_str() {
+        "png" => Ok(ImageFormat::Png),
+        "jpg" | "jpeg" => Ok(ImageFormat::Jpeg),
+        "webp" => Ok(ImageFormat::WebP),
+        other => Err(format!("Unsupported output format: {}", other)),
+    }
+}
+
+fn format_extension(format: ImageFormat) -> &'static str {
+    format.extensions_str().first().copied().unwrap_or("img")
+}
+
+fn apply_orientation(img: DynamicImage, orientation: u32) -> DynamicImage {
+    match orientation {
+        2 => img.fliph(),
+        3 => img.rotate180(),
+        4 => img.flipv(),
+        5 => img.rotate90().fliph(),
+        6 => img.rotate90(),
+        7 => img.rotate270().fliph(),
+        8 => img.rotate270(),
+        _ => img,
+    }
+}
+
+fn normalize_degrees(degrees: i32) -> Result<i32, String> {
+    let normalized = degrees.rem_euclid(360);
+    if normalized % 90 != 0 {
+        return Err(format!("Rotation must be a multiple of 90 degrees, got {}", degrees));
+    }
+    Ok(normalized)
+}
+
+fn apply_operation(img: DynamicImage, op: &ImageOperation) -> Result<DynamicImage, String> {
+    Ok(match op {
+        ImageOperation::Rotate { degrees } => match normalize_degrees(*degrees)? {
+            90 => img.rotate90(),
+            180 => img.rotate180(),
+            270 => img.rotate270(),
+            _ => img,
+        },
+        ImageOperation::FlipHorizontal => img.fliph(),
+        ImageOperation::FlipVertical => img.flipv(),
+        ImageOperation::Crop { x, y, width, height } => {
+            let fits_x = x.checked_add(*width).is_some_and(|r| r <= img.width());
+            let fits_y = y.checked_add(*height).is_some_and(|b| b <= img.height());
+            if *width == 0 || *height == 0 || !fits_x || !fits_y {
+                return Err("Crop rectangle is outside the image".to_string());
+            }
+            img.crop_imm(*x, *y, *width, *height)
+        }
+        ImageOperation::Resize { width, height } => {
+            let filter = image::imageops::FilterType::Lanczos3;
+            match (width, height) {
+                (Some(w), Some(h)) => img.resize(*w, *h, filter),
+                (Some(w), None) => img.resize(*w, u32::MAX, filter),
+                (None, Some(h)) => img.resize(u32::MAX, *h, filter),
+                (None, None) => img,
+            }
+        }
+        // Orientation is handled before the operation list is applied
+        ImageOperation::AutoOrient => img,
+    })
+}
+
+// ─── Lossless JPEG path ───────────────────────────────────────────────────────
+
+fn find_jpegtran() -> Option<&'static str> {
+    let mut cmd = Command::new("jpegtran");
+    cmd.arg("-version").stdout(Stdio::null()).stderr(Stdio::null());
+
+    #[cfg(target_os = "windows")]
+    {
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    cmd.status().ok().map(|_| "jpegtran")
+}
+
+/// Rotate/flip a JPEG via jpegtran, which moves DCT blocks instead of
+/// re-encoding. `-perfect` makes it fail rather than trim edge blocks when the
+/// size isn't a multiple of the MCU, in which case we fall back to re-encoding.
+fn jpegtran_transform(source: &Path, output: &Path, ops: &[ImageOperation], strip: bool) -> Result<(), String> {
+    let jpegtran = find_jpegtran().ok_or("jpegtran not available")?;
+    let copy = if strip { "none" } else { "all" };
+
+    let mut current = source.to_path_buf();
+    for (i, op) in ops.iter().enumerate() {
+        let transform: &[&str] = match op {
+            ImageOperation::Rotate { degrees } => match normalize_degrees(*degrees)? {
+                90 => &["-rotate", "90"],
+                180 => &["-rotate", "180"],
+                270 => &["-rotate", "270"],
+                _ => continue,
+            },
+            ImageOperation::FlipHorizontal => &["-flip", "horizontal"],
+            ImageOperation::FlipVertical => &["-flip", "vertical"],
+            _ => return Err("Operation cannot be applied losslessly".to_string()),
+        };
+
+        let step_output = output.with_extension(format!("step{}.jpg", i));
+        let mut cmd = Command::new(jpegtran);
+        cmd.args(["-copy", copy, "-perfect"])
+            .args(transform)
+            .arg("-outfile")
+            .arg(&step_output)
+            .arg(&current)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+
+        #[cfg(target_os = "windows")]
+        {
+            const CREATE_NO_WINDOW: u32 = 0x08000000;
+            cmd.creation_flags(CREATE_NO_WINDOW);
+        }
+
+        let status = cmd.status().map_err(|e| format!("jpegtran execution failed: {}", e));
+        if current != source {
+            let _ = fs::remove_file(&current);
+        }
+        if !status?.success() {
+            let _ = fs::remove_file(&step_output);
+            return Err("jpegtran could not transform this image losslessly".to_string());
+        }
+        current = step_output;
+    }
+
+    if current == source {
+        // Nothing to transform; only metadata handling remains
+        let data = fs::read(source).map_err(|e| format!("Failed to read image: {}", e))?;
+        let data = if strip { strip_jpeg_metadata(&data).ok_or("Malformed JPEG")? } else { data };
+        fs::write(output, data).map_err(|e| format!("Failed to write image: {}", e))
+    } else {
+        fs::rename(&current, output).map_err(|e| format!("Failed to write image: {}", e))
+    }
+}
+
+/// Remove EXIF and XMP APP1 segments from a JPEG without touching the image data.
+fn strip_jpeg_metadata(data: &[u8]) -> Option<Vec<u8>> {
+    if data.len() < 4 || data[0..2] != [0xFF, 0xD8] {
+        return None;
+    }
+
+    let mut out = vec![0xFF, 0xD8];
+    let mut i = 2;
+    while i + 4 <= data.len() {
+        if data[i] != 0xFF {
+            return None;
+        }
+        let marker = data[i + 1];
+        if marker == 0xFF {
+            // Fill byte
+            i += 1;
+            continue;
+        }
+        if marker == 0xDA {
+            // Start of scan: the rest is entropy-coded data
+            out.extend_from_slice(&data[i..]);
+            return Some(out);
+        }
+
+        let len = u16::from_be_bytes([data[i + 2], data[i + 3]]) as usize;
+        let end = i + 2 + len;
+        if len < 2 || end > data.len() {
+            return None;
+        }
+        let payload = &data[i + 4..end];
+        let is_metadata = marker == 0xE1
+            && (payload.starts_with(b"Exif\0") || payload.starts_with(b"http://ns.adobe.com/xap/1.0/"));
+        if !is_metadata {
+            out.extend_from_slice(&data[i..end]);
+        }
+        i = end;
+    }
+
+    None
+}
+
+// ─── Metadata ─────────────────────────────────────────────────────────────────
+
+/// The source's raw EXIF block (TIFF data), with the orientation reset to
+/// normal since re-encoded pixels are always upright.
+fn source_exif(source: &Path) -> Option<Vec<u8>> {
+    let file = fs::File::open(source).ok()?;
+    let exif = exif::Reader::new().read_from_container(&mut BufReader::new(file)).ok()?;
+    let mut tiff = exif.buf().to_vec();
+    reset_orientation(&mut tiff)?;
+    Some(tiff)
+}
+
+/// Set the IFD0 Orientation tag, if present, to 1 in place.
+fn reset_orientation(tiff: &mut [u8]) -> Option<()> {
+    let big_endian = match tiff.get(0..2)? {
+        b"MM" => true,
+        b"II" => false,
+        _ => return None,
+    };
+    let read16 = |b: &[u8], at: usize| -> Option<u16> {
+        let bytes: [u8; 2] = b.get(at..at + 2)?.try_into().ok()?;
+        Some(if big_endian { u16::from_be_bytes(bytes) } else { u16::from_le_bytes(bytes) })
+    };
+    let read32 = |b: &[u8], at: usize| -> Option<u32> {
+        let bytes: [u8; 4] = b.get(at..at + 4)?.try_into().ok()?;
+        Some(if big_endian { u32::from_be_bytes(bytes) } else { u32::from_le_bytes(bytes) })
+    };
+
+    let ifd = read32(tiff, 4)? as usize;
+    let count = read16(tiff, ifd)? as usize;
+    for n in 0..count {
+        let entry = ifd + 2 + n * 12;
+        if read16(tiff, entry)? == 0x0112 {
+            // A SHORT value sits in the first two bytes of the value field
+            let one = if big_endian { 1u16.to_be_bytes() } else { 1u16.to_le_bytes() };
+            tiff.get_mut(entry + 8..entry + 10)?.copy_from_slice(&one);
+            break;
+        }
+    }
+    Some(())
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+/// Insert an APP1 Exif segment after SOI and any JFIF APP0 segment.
+fn jpeg_with_exif(data: &[u8], tiff: &[u8]) -> Option<Vec<u8>> {
+    let len = u16::try_from(2 + 6 + tiff.len()).ok()?;
+    if data.get(0..2)? != [0xFF, 0xD8] {
+        return None;
+    }
+    let mut at = 2;
+    if data.get(at..at + 2)? == [0xFF, 0xE0] {
+        at += 2 + u16::from_be_bytes([*data.get(at + 2)?, *data.get(at + 3)?]) as usize;
+    }
+
+    let mut out = Vec::with_capacity(data.len() + tiff.len() + 10);
+    out.extend_from_slice(data.get(..at)?);
+    out.extend_from_slice(&[0xFF, 0xE1]);
+    out.extend_from_slice(&len.to_be_bytes());
+    out.extend_from_slice(b"Exif\0\0");
+    out.extend_from_slice(tiff);
+    out.extend_from_slice(&data[at..]);
+    Some(out)
+}
+
+/// Insert an eXIf chunk right after IHDR.
+fn png_with_exif(data: &[u8], tiff: &[u8]) -> Option<Vec<u8>> {
+    // 8-byte signature, then IHDR: length, type, 13 bytes of data, CRC
+    const IHDR_END: usize = 8 + 4 + 4 + 13 + 4;
+    if data.get(12..16)? != b"IHDR" || data.len() < IHDR_END {
+        return None;
+    }
+
+    let mut chunk = b"eXIf".to_vec();
+    chunk.extend_from_slice(tiff);
+    let mut out = Vec::with_capacity(data.len() + chunk.len() + 8);
+    out.extend_from_slice(&data[..IHDR_END]);
+    out.extend_from_slice(&u32::try_from(tiff.len()).ok()?.to_be_bytes());
+    out.extend_from_slice(&chunk);
+    out.extend_from_slice(&crc32(&chunk).to_be_bytes());
+    out.extend_from_slice(&data[IHDR_END..]);
+    Some(out)
+}
+
+/// Append an EXIF chunk, switching a simple WebP to the extended (VP8X)
+/// layout that metadata requires.
+fn webp_with_exif(data: &[u8], tiff: &[u8], width: u32, height: u32, alpha: bool) -> Option<Vec<u8>> {
+    if data.get(0..4)? != b"RIFF" || data.get(8..12)? != b"WEBP" {
+        return None;
+    }
+    const EXIF_FLAG: u8 = 0x08;
+    const ALPHA_FLAG: u8 = 0x10;
+
+    let mut out = b"RIFF\0\0\0\0WEBP".to_vec();
+    if data.get(12..16)? == b"VP8X" {
+        out.extend_from_slice(&data[12..]);
+        out[20] |= EXIF_FLAG;
+    } else {
+        let flags = EXIF_FLAG | if alpha { ALPHA_FLAG } else { 0 };
+        out.extend_from_slice(b"VP8X");
+        out.extend_from_slice(&10u32.to_le_bytes());
+        out.extend_from_slice(&[flags, 0, 0, 0]);
+        out.extend_from_slice(&(width - 1).to_le_bytes()[..3]);
+        out.extend_from_slice(&(height - 1).to_le_bytes()[..3]);
+        out.extend_from_slice(&data[12..]);
+    }
+
+    out.extend_from_slice(b"EXIF");
+    out.extend_from_slice(&u32::try_from(tiff.len()).ok()?.to_le_bytes());
+    out.extend_from_slice(tiff);
+    if tiff.len() % 2 == 1 {
+        out.push(0);
+    }
+    let riff_size = u32::try_from(out.len() - 8).ok()?;
+    out[4..8].copy_from_slice(&riff_size.to_le_bytes());
+    Some(out)
+}
+
+/// Write `tiff` into an encoded file in place. Only the formats this module
+/// encodes itself (JPEG, PNG, WebP) can carry it.
+fn embed_exif(path: &Path, format: ImageFormat, tiff: &[u8], img: &DynamicImage) -> Result<(), String> {
+    let data = fs::read(path).map_err(|e| format!("Failed to read image: {}", e))?;
+    let with_exif = match format {
+        ImageFormat::Jpeg => jpeg_with_exif(&data, tiff),
+        ImageFormat::Png => png_with_exif(&data, tiff),
+        ImageFormat::WebP => webp_with_exif(&data, tiff, img.width(), img.height(), img.color().has_alpha()),
+        other => {
+            log::debug!("image_ops: {:?} output can't carry EXIF; saving without it", other);
+            return Ok(());
+        }
+    }
+    .ok_or("Failed to keep the image's EXIF metadata; save with metadata stripped instead")?;
+    fs::write(path, with_exif).map_err(|e| format!("Failed to write image: {}", e))
+}
+
+// ─── Re-encode path ───────────────────────────────────────────────────────────
+
+fn encode(img: &DynamicImage, output: &Path, format: ImageFormat, quality: u8) -> Result<(), String> {
+    let file = fs::File::create(output).map_err(|e| format!("Failed to create file: {}", e))?;
+    let mut writer = BufWriter::new(file);
+
+    let result = match format {
+        // JPEG has no alpha channel
+        ImageFormat::Jpeg => DynamicImage::ImageRgb8(img.to_rgb8())
+            .write_with_encoder(image::codecs::jpeg::JpegEncoder::new_with_quality(&mut writer, quality)),
+        // The image crate only encodes lossless WebP
+        ImageFormat::WebP => DynamicImage::ImageRgba8(img.to_rgba8())
+            .write_with_encoder(image::codecs::webp::WebPEncoder::new_lossless(&mut writer)),
+        other => img.write_to(&mut writer, other),
+    };
+
+    result.map_err(|e| format!("Failed to encode image: {}", e))
+}
+
+fn resolve_output(
+    source: &Path,
+    source_format: ImageFormat,
+    format: ImageFormat,
+    output_path: Option<String>,
+) -> Result<PathBuf, String> {
+    let output = match output_path {
+        Some(p) => PathBuf::from(p),
+        None if format == source_format => source.to_path_buf(),
+        None => get_unique_path(source.with_extension(format_extension(format))),
+    };
+    Ok(check_write(&output.to_string_lossy())?)
+}
+
+/// Apply the viewer's edits to `path` and save the result.
+///
+/// JPEG rotations and flips go through jpegtran when it is installed so the
+/// image isn't recompressed; everything else is decoded, transformed and
+/// re-encoded here. Re-encoding bakes the orientation into the pixels and
+/// copies the source's EXIF with its orientation reset, unless
+/// `strip_metadata` is set. WebP is only written losslessly, so a `quality`
+/// for WebP output is an error.
+#[tauri::command]
+pub async fn apply_image_operations(
+    path: String,
+    operations: Vec<ImageOperation>,
+    options: Option<ImageSaveOptions>,
+) -> Result<ImageSaveResult, String> {
+    tokio::task::spawn_blocking(move || {
+        let options = options.unwrap_or_default();
+        let source = &check_read(&path)?;
+
+        let source_format = image::ImageReader::open(source)
+            .and_then(|r| r.with_guessed_format())
+            .map_err(|e| format!("Failed to open image: {}", e))?
+            .format()
+            .ok_or("Unrecognized image format")?;
+        let format = match &options.format {
+            Some(f) => parse_format(f)?,
+            None => source_format,
+        };
+        if format == ImageFormat::WebP && options.quality.is_some() {
+            return Err("WebP is saved losslessly; quality only applies to JPEG".to_string());
+        }
+        let quality = options.quality.unwrap_or(DEFAULT_JPEG_QUALITY).clamp(1, 100);
+        let output = resolve_output(source, source_format, format, options.output_path.clone())?;
+
+        // Write beside the destination and rename, so overwriting the source is safe
+        let partial = output.with_extension(format!("part.{}", format_extension(format)));
+        let orientation = read_exif_orientation(source).unwrap_or(1);
+
+        let lossless_candidate = source_format == ImageFormat::Jpeg
+            && format == ImageFormat::Jpeg
+            && orientation == 1
+            && operations.iter().all(|op| matches!(
+                op,
+                ImageOperation::Rotate { .. } | ImageOperation::FlipHorizontal | ImageOperation::FlipVertical | ImageOperation::AutoOrient
+            ));
+        let lossless_ops: Vec<ImageOperation> = operations
+            .iter()
+            .filter(|op| !matches!(op, ImageOperation::AutoOrient))
+            .cloned()
+            .collect();
+
+        let lossless = lossless_candidate
+            && match jpegtran_transform(source, &partial, &lossless_ops, options.strip_metadata) {
+                Ok(()) => true,
+                Err(e) => {
+                    log::debug!("image_ops: lossless path unavailable: {}", e);
+                    let _ = fs::remove_file(&partial);
+                    false
+                }
+            };
+
+        let (width, height) = if lossless {
+            image::image_dimensions(&partial).map_err(|e| format!("Failed to read image: {}", e))?
+        } else {
+            let img = image::ImageReader::open(source)
+                .and_then(|r| r.with_guessed_format())
+                .map_err(|e| format!("Failed to open image: {}", e))?
+                .decode()
+                .map_err(|e| format!("Failed to decode image: {}", e))?;
+
+            let mut img = apply_orientation(img, orientation);
+            for op in &operations {
+                img = apply_operation(img, op)?;
+            }
+
+            let exif = if options.strip_metadata { None } else { source_exif(source) };
+            let written = encode(&img, &partial, format, quality)
+                .and_then(|()| exif.map_or(Ok(()), |tiff| embed_exif(&partial, format, &tiff, &img)));
+            if let Err(e) = written {
+                let _ = fs::remove_file(&partial);
+                return Err(e);
+            }
+            (img.width(), img.height())
+        };
+
+        fs::rename(&partial, &output).map_err(|e| format!("Failed to write image: {}", e))?;
+
+        Ok(ImageSaveResult {
+            path: output.to_string_lossy().to_string(),
+            width,
+            height,
+            lossless,
+        })
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
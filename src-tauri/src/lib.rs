@@ -1,11 +1,43 @@
 mod terminal;
 mod files;
+mod path_policy;
+mod diff;
+mod checksums;
+mod file_attributes;
+mod git;
+mod git_status;
+mod git_commands;
+mod search;
+mod lsp;
+mod file_index;
+mod workspace_db;
+mod workspace_commands;
+mod recovery;
+mod history_db;
+mod history_commands;
+mod formatter;
+mod editorconfig;
+mod disk_space;
+#[cfg(target_os = "linux")]
+mod volumes;
 mod file_settings;
+mod ffmpeg;
+mod thumbnails;
+mod media_info;
+mod transcode;
+mod video_tools;
+mod image_ops;
 mod fonts;
+mod font_preview;
 mod planner_db;
 mod planner_commands;
 mod gantt_db;
 mod gantt_commands;
+mod locations_db;
+mod locations_commands;
+mod sync_db;
+mod sync_commands;
+mod folder_sync;
 
 use log::{debug, error, info};
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -26,6 +58,13 @@ fn set_close_to_tray(enabled: bool) {
 pub fn run() {
     tauri::Builder::default()
         .manage(terminal::TerminalState::default())
+        .manage(thumbnails::ThumbnailService::default())
+        .manage(transcode::TranscodeState::default())
+        .manage(sync_commands::SyncJobs::default())
+        .manage(git_status::GitStatusCache::default())
+        .manage(search::SearchJobs::default())
+        .manage(lsp::LspState::default())
+        .manage(workspace_commands::WorkspaceIndexes::default())
         .plugin(
             tauri_plugin_log::Builder::new()
                 .targets([
@@ -51,6 +90,9 @@ pub fn run() {
                 .map_err(|e| { error!("setup: create_dir_all({:?}) failed: {e}", data_dir); e })?;
             debug!("setup: app data directory ready");
 
+            path_policy::load_allowed_roots(&data_dir);
+            info!("setup: allowed folders loaded");
+
             let planner_path = data_dir.join("planner.db");
             debug!("setup: initializing planner DB at {:?}", planner_path);
             let conn = planner_db::initialize(&planner_path)
@@ -65,6 +107,37 @@ pub fn run() {
             app.manage(gantt_db::GanttDb(std::sync::Mutex::new(gconn)));
             info!("setup: gantt DB initialized");
 
+            let locations_path = data_dir.join("locations.db");
+            debug!("setup: initializing locations DB at {:?}", locations_path);
+            let lconn = locations_db::initialize(&locations_path)
+                .map_err(|e| { error!("setup: locations_db::initialize failed: {e}"); e })?;
+            app.manage(locations_db::LocationsDb(std::sync::Mutex::new(lconn)));
+            info!("setup: locations DB initialized");
+
+            let sync_path = data_dir.join("sync.db");
+            debug!("setup: initializing sync DB at {:?}", sync_path);
+            let sconn = sync_db::initialize(&sync_path)
+                .map_err(|e| { error!("setup: sync_db::initialize failed: {e}"); e })?;
+            app.manage(sync_db::SyncDb(std::sync::Mutex::new(sconn)));
+            info!("setup: sync DB initialized");
+
+            let workspace_path = data_dir.join("workspaces.db");
+            debug!("setup: initializing workspace DB at {:?}", workspace_path);
+            let wconn = workspace_db::initialize(&workspace_path)
+                .map_err(|e| { error!("setup: workspace_db::initialize failed: {e}"); e })?;
+            app.manage(workspace_db::WorkspaceDb(std::sync::Mutex::new(wconn)));
+            info!("setup: workspace DB initialized");
+
+            let history_path = data_dir.join("history.db");
+            debug!("setup: initializing history DB at {:?}", history_path);
+            let hconn = history_db::initialize(&history_path)
+                .map_err(|e| { error!("setup: history_db::initialize failed: {e}"); e })?;
+            app.manage(history_db::HistoryDb(std::sync::Mutex::new(hconn)));
+            info!("setup: history DB initialized");
+
+            #[cfg(target_os = "linux")]
+            volumes::start_mount_watcher(app.handle().clone());
+
             // ── System tray ──────────────────────────────────────────────
             let show_item = MenuItemBuilder::with_id("show", "Show").build(app)?;
             let hide_item = MenuItemBuilder::with_id("hide", "Hide").build(app)?;
@@ -102,7 +175,7 @@ pub fn run() {
                         }
                     }
                     "quit" => {
-                        app.exit(0);
+                        recovery::request_exit(app);
                     }
                     _ => {}
                 })
@@ -121,9 +194,12 @@ pub fn run() {
             let window_clone = window.clone();
             window.on_window_event(move |event| {
                 if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+                    api.prevent_close();
                     if CLOSE_TO_TRAY.load(Ordering::Relaxed) {
-                        api.prevent_close();
                         let _ = window_clone.hide();
+                    } else {
+                        // Let the editor snapshot unsaved buffers first
+                        recovery::request_exit(window_clone.app_handle());
                     }
                 }
             });
@@ -156,16 +232,110 @@ pub fn run() {
             files::get_directory_sizes,
             files::get_available_drives,
             files::save_screenshot,
-            files::get_playable_video,
             files::get_file_info,
 
+            disk_space::get_disk_space,
+
+            path_policy::get_allowed_roots,
+            path_policy::set_allowed_roots,
+
+            diff::diff_files,
+            diff::diff_directories,
+
+            checksums::hash_file,
+            checksums::verify_checksums,
+
+            file_attributes::set_permissions,
+            file_attributes::set_executable,
+            file_attributes::set_readonly,
+            file_attributes::set_owner,
+            file_attributes::set_file_times,
+            file_attributes::touch_file,
+
+            git_status::get_git_repo_status,
+            git_status::get_git_directory_status,
+            git_status::refresh_git_status,
+
+            git_commands::git_stage,
+            git_commands::git_unstage,
+            git_commands::git_stage_hunk,
+            git_commands::git_unstage_hunk,
+            git_commands::git_commit,
+            git_commands::git_log,
+            git_commands::git_diff_file,
+            git_commands::git_branches,
+            git_commands::git_create_branch,
+            git_commands::git_checkout_branch,
+            git_commands::git_stash_list,
+            git_commands::git_stash_push,
+            git_commands::git_stash_pop,
+
+            search::find_in_files,
+            search::cancel_search,
+            search::replace_in_files,
+
+            lsp::lsp_available_servers,
+            lsp::lsp_start,
+            lsp::lsp_send,
+            lsp::lsp_stop,
+            lsp::lsp_list_servers,
+
+            workspace_commands::list_workspaces,
+            workspace_commands::create_workspace,
+            workspace_commands::update_workspace,
+            workspace_commands::delete_workspace,
+            workspace_commands::open_workspace,
+            workspace_commands::close_workspace,
+            workspace_commands::get_workspace_index_status,
+            workspace_commands::find_workspace_files,
+            workspace_commands::get_workspace_tabs,
+            workspace_commands::save_workspace_tabs,
+
+            recovery::save_buffer_snapshot,
+            recovery::discard_buffer_snapshot,
+            recovery::list_recoverable_buffers,
+            recovery::get_buffer_snapshot,
+            recovery::hot_exit_ready,
+
+            history_commands::list_file_history,
+            history_commands::get_history_version,
+            history_commands::diff_history_version,
+            history_commands::restore_history_version,
+            history_commands::clear_file_history,
+
+            formatter::format_document,
+            formatter::list_formatters,
+
+            editorconfig::resolve_editorconfig,
+
             file_settings::get_thumbnail_cache_size,
             file_settings::clear_thumbnail_cache,
             file_settings::get_folder_size,
             file_settings::count_files_in_directory,
             file_settings::enforce_cache_limit,
+            file_settings::set_thumbnail_cache_limit,
+
+            thumbnails::get_thumbnail,
+            thumbnails::prioritize_thumbnails,
+            thumbnails::cancel_pending_thumbnails,
+
+            media_info::get_media_info,
+
+            transcode::start_transcode,
+            transcode::cancel_transcode,
+            transcode::list_transcode_jobs,
+            transcode::get_playable_video,
+
+            video_tools::trim_video,
+            video_tools::export_frame,
+            video_tools::generate_contact_sheet,
+            video_tools::create_animation,
+
+            image_ops::apply_image_operations,
 
             fonts::get_system_fonts,
+            fonts::resolve_font,
+            font_preview::render_font_preview,
 
             planner_commands::get_events,
             planner_commands::create_event,
@@ -212,6 +382,27 @@ pub fn run() {
             gantt_commands::create_gantt_milestone,
             gantt_commands::update_gantt_milestone,
             gantt_commands::delete_gantt_milestone,
+
+            locations_commands::get_bookmarks,
+            locations_commands::add_bookmark,
+            locations_commands::rename_bookmark,
+            locations_commands::remove_bookmark,
+            locations_commands::reorder_bookmarks,
+            locations_commands::record_location_access,
+            locations_commands::get_recent_locations,
+            locations_commands::get_location_suggestions,
+            locations_commands::remove_recent_location,
+            locations_commands::clear_recent_locations,
+            locations_commands::get_last_location,
+            locations_commands::get_user_directories,
+
+            sync_commands::list_sync_profiles,
+            sync_commands::create_sync_profile,
+            sync_commands::update_sync_profile,
+            sync_commands::delete_sync_profile,
+            sync_commands::plan_sync,
+            sync_commands::start_sync,
+            sync_commands::cancel_sync,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
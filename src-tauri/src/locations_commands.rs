@@ -0,0 +1,386 @@
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicI64, Ordering};
+
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use uuid::Uuid;
+
+use crate::locations_db::LocationsDb;
+
+/// Oldest entries beyond this are dropped whenever a new access is recorded.
+const MAX_RECENT_LOCATIONS: i64 = 500;
+
+/// Last timestamp handed out by `now_iso`, in milliseconds.
+static LAST_STAMP_MS: AtomicI64 = AtomicI64::new(0);
+
+/// Frecency: access count weighted by how recently the location was used,
+/// bucketed by age in days (the same scheme browsers use for URL ranking).
+const FRECENCY_SCORE: &str = "access_count * CASE
+        WHEN julianday('now') - julianday(last_accessed) < 4  THEN 100
+        WHEN julianday('now') - julianday(last_accessed) < 14 THEN 70
+        WHEN julianday('now') - julianday(last_accessed) < 31 THEN 50
+        WHEN julianday('now') - julianday(last_accessed) < 90 THEN 30
+        ELSE 10
+    END";
+
+// ─── Public data structures ───────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub id: String,
+    pub path: String,
+    pub name: String,
+    pub sort_order: i32,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecentLocation {
+    pub path: String,
+    pub name: String,
+    pub is_dir: bool,
+    pub access_count: i64,
+    pub first_accessed: String,
+    pub last_accessed: String,
+    pub score: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UserDirectory {
+    /// Stable identifier: `home`, `desktop`, `documents`, `downloads`, ...
+    pub kind: String,
+    pub name: String,
+    pub path: String,
+}
+
+// ─── Private helpers ──────────────────────────────────────────────────────────
+
+fn new_id() -> String {
+    Uuid::new_v4().to_string()
+}
+
+/// Millisecond timestamp, strictly increasing within a run so accesses
+/// recorded in quick succession still sort in the order they happened.
+fn now_iso() -> String {
+    let now = chrono::Utc::now().timestamp_millis();
+    let previous = LAST_STAMP_MS
+        .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |last| Some(now.max(last + 1)))
+        .unwrap_or(now);
+    let stamp = chrono::DateTime::from_timestamp_millis(now.max(previous + 1)).unwrap_or_else(chrono::Utc::now);
+    stamp.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string()
+}
+
+fn display_name(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string())
+}
+
+/// `kind` filter for recent locations: `"file"`, `"folder"` or anything else
+/// for both.
+fn kind_clause(kind: Option<&str>) -> &'static str {
+    match kind {
+        Some("file") => "is_dir = 0",
+        Some("folder") => "is_dir = 1",
+        _ => "1 = 1",
+    }
+}
+
+fn row_to_bookmark(row: &rusqlite::Row) -> rusqlite::Result<Bookmark> {
+    Ok(Bookmark {
+        id: row.get(0)?,
+        path: row.get(1)?,
+        name: row.get(2)?,
+        sort_order: row.get(3)?,
+        created_at: row.get::<_, Option<String>>(4)?.unwrap_or_default(),
+    })
+}
+
+fn row_to_recent(row: &rusqlite::Row) -> rusqlite::Result<RecentLocation> {
+    Ok(RecentLocation {
+        path: row.get(0)?,
+        name: row.get(1)?,
+        is_dir: row.get::<_, i32>(2)? != 0,
+        access_count: row.get(3)?,
+        first_accessed: row.get::<_, Option<String>>(4)?.unwrap_or_default(),
+        last_accessed: row.get::<_, Option<String>>(5)?.unwrap_or_default(),
+        score: row.get(6)?,
+    })
+}
+
+fn fetch_bookmark(conn: &rusqlite::Connection, id: &str) -> Result<Bookmark, String> {
+    conn.query_row(
+        "SELECT id, path, name, sort_order, created_at FROM bookmarks WHERE id=?1",
+        params![id],
+        row_to_bookmark,
+    )
+    .map_err(|e| e.to_string())
+}
+
+fn user_directory_candidates() -> Vec<(&'static str, Option<PathBuf>)> {
+    vec![
+        ("home", dirs::home_dir()),
+        ("desktop", dirs::desktop_dir()),
+        ("documents", dirs::document_dir()),
+        ("downloads", dirs::download_dir()),
+        ("pictures", dirs::picture_dir()),
+        ("music", dirs::audio_dir()),
+        ("videos", dirs::video_dir()),
+        ("public", dirs::public_dir()),
+        ("templates", dirs::template_dir()),
+    ]
+}
+
+// ─── Bookmarks ────────────────────────────────────────────────────────────────
+
+#[tauri::command]
+pub fn get_bookmarks(state: State<'_, LocationsDb>) -> Result<Vec<Bookmark>, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, path, name, sort_order, created_at
+             FROM   bookmarks
+             ORDER  BY sort_order ASC, created_at ASC",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map([], row_to_bookmark)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows)
+}
+
+/// Pin `path`. Pinning an already pinned path returns the existing bookmark.
+#[tauri::command]
+pub fn add_bookmark(
+    state: State<'_, LocationsDb>,
+    path: String,
+    name: Option<String>,
+) -> Result<Bookmark, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+
+    let existing: Option<String> = conn
+        .query_row("SELECT id FROM bookmarks WHERE path=?1", params![path], |row| row.get(0))
+        .optional()
+        .map_err(|e| e.to_string())?;
+    if let Some(id) = existing {
+        return fetch_bookmark(&conn, &id);
+    }
+
+    let id = new_id();
+    let name = name.filter(|n| !n.trim().is_empty()).unwrap_or_else(|| display_name(&path));
+    let sort_order: i32 = conn
+        .query_row("SELECT COALESCE(MAX(sort_order) + 1, 0) FROM bookmarks", [], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "INSERT INTO bookmarks (id, path, name, sort_order, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![id, path, name, sort_order, now_iso()],
+    )
+    .map_err(|e| e.to_string())?;
+
+    fetch_bookmark(&conn, &id)
+}
+
+#[tauri::command]
+pub fn rename_bookmark(state: State<'_, LocationsDb>, id: String, name: String) -> Result<Bookmark, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let rows = conn
+        .execute("UPDATE bookmarks SET name=?1 WHERE id=?2", params![name, id])
+        .map_err(|e| e.to_string())?;
+    if rows == 0 {
+        return Err(format!("Bookmark not found: {}", id));
+    }
+    fetch_bookmark(&conn, &id)
+}
+
+#[tauri::command]
+pub fn remove_bookmark(state: State<'_, LocationsDb>, id: String) -> Result<(), String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM bookmarks WHERE id=?1", params![id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Persist a new order; `ids` lists bookmarks top to bottom.
+#[tauri::command]
+pub fn reorder_bookmarks(state: State<'_, LocationsDb>, ids: Vec<String>) -> Result<(), String> {
+    let mut conn = state.0.lock().map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    for (index, id) in ids.iter().enumerate() {
+        tx.execute("UPDATE bookmarks SET sort_order=?1 WHERE id=?2", params![index as i32, id])
+            .map_err(|e| e.to_string())?;
+    }
+    tx.commit().map_err(|e| e.to_string())
+}
+
+// ─── Recent locations ─────────────────────────────────────────────────────────
+
+/// Record that a file was opened or a folder visited.
+#[tauri::command]
+pub fn record_location_access(
+    state: State<'_, LocationsDb>,
+    path: String,
+    is_dir: bool,
+) -> Result<(), String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let now = now_iso();
+
+    conn.execute(
+        "INSERT INTO recent_locations (path, name, is_dir, access_count, first_accessed, last_accessed)
+         VALUES (?1, ?2, ?3, 1, ?4, ?4)
+         ON CONFLICT(path) DO UPDATE SET
+             access_count  = access_count + 1,
+             is_dir        = excluded.is_dir,
+             last_accessed = excluded.last_accessed",
+        params![path, display_name(&path), is_dir as i32, now],
+    )
+    .map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "DELETE FROM recent_locations WHERE path NOT IN (
+             SELECT path FROM recent_locations ORDER BY last_accessed DESC LIMIT ?1
+         )",
+        params![MAX_RECENT_LOCATIONS],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Most recently used locations first. `kind` is `"file"`, `"folder"` or
+/// omitted for both.
+#[tauri::command]
+pub fn get_recent_locations(
+    state: State<'_, LocationsDb>,
+    kind: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<RecentLocation>, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let sql = format!(
+        "SELECT path, name, is_dir, access_count, first_accessed, last_accessed, {score}
+         FROM   recent_locations
+         WHERE  {kind}
+         ORDER  BY last_accessed DESC
+         LIMIT  ?1",
+        score = FRECENCY_SCORE,
+        kind = kind_clause(kind.as_deref()),
+    );
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map(params![limit.unwrap_or(20)], row_to_recent)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows)
+}
+
+/// Frecency-ranked locations whose path contains `query` (case-insensitive).
+/// Entries that no longer exist on disk are skipped.
+#[tauri::command]
+pub fn get_location_suggestions(
+    state: State<'_, LocationsDb>,
+    query: Option<String>,
+    kind: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<RecentLocation>, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let limit = limit.unwrap_or(10) as usize;
+    let pattern = format!("%{}%", query.unwrap_or_default().trim().to_lowercase());
+
+    let sql = format!(
+        "SELECT path, name, is_dir, access_count, first_accessed, last_accessed, {score} AS score
+         FROM   recent_locations
+         WHERE  {kind} AND lower(path) LIKE ?1
+         ORDER  BY score DESC, last_accessed DESC",
+        score = FRECENCY_SCORE,
+        kind = kind_clause(kind.as_deref()),
+    );
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map(params![pattern], row_to_recent)
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .filter(|r| Path::new(&r.path).exists())
+        .take(limit)
+        .collect();
+
+    Ok(rows)
+}
+
+#[tauri::command]
+pub fn remove_recent_location(state: State<'_, LocationsDb>, path: String) -> Result<(), String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM recent_locations WHERE path=?1", params![path])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn clear_recent_locations(state: State<'_, LocationsDb>, kind: Option<String>) -> Result<(), String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let sql = format!("DELETE FROM recent_locations WHERE {}", kind_clause(kind.as_deref()));
+    conn.execute(&sql, []).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Folder to open the file manager in: the most recently visited folder that
+/// still exists, otherwise the home directory.
+#[tauri::command]
+pub fn get_last_location(state: State<'_, LocationsDb>) -> Result<String, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare("SELECT path FROM recent_locations WHERE is_dir = 1 ORDER BY last_accessed DESC LIMIT 20")
+        .map_err(|e| e.to_string())?;
+
+    let last = stmt
+        .query_map([], |row| row.get::<_, String>(0))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .find(|p| Path::new(p).is_dir());
+
+    if let Some(path) = last {
+        return Ok(path);
+    }
+    dirs::home_dir()
+        .map(|p| p.to_string_lossy().to_string())
+        .ok_or_else(|| "Could not determine home directory".to_string())
+}
+
+// ─── User directories ─────────────────────────────────────────────────────────
+
+/// Standard user folders (XDG user dirs on Linux, known folders on Windows)
+/// that exist on this machine.
+#[tauri::command]
+pub fn get_user_directories() -> Result<Vec<UserDirectory>, String> {
+    let home = dirs::home_dir();
+    let dirs = user_directory_candidates()
+        .into_iter()
+        .filter_map(|(kind, path)| {
+            let path = path?;
+            // XDG marks a disabled directory by pointing it at $HOME
+            if kind != "home" && Some(&path) == home.as_ref() {
+                return None;
+            }
+            if !path.is_dir() {
+                return None;
+            }
+            Some(UserDirectory {
+                kind: kind.to_string(),
+                // Use the folder's own name so localized XDG dirs read naturally
+                name: if kind == "home" { "Home".to_string() } else { display_name(&path.to_string_lossy()) },
+                path: path.to_string_lossy().to_string(),
+            })
+        })
+        .collect();
+    Ok(dirs)
+}
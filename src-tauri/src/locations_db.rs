@@ -0,0 +1,39 @@
+use rusqlite::{Connection, Result};
+use std::path::Path;
+use std::sync::Mutex;
+
+/// Managed state wrapping the SQLite connection behind a Mutex.
+pub struct LocationsDb(pub Mutex<Connection>);
+
+/// Open (or create) the SQLite database at `path` and run all CREATE TABLE
+/// migrations. Returns the ready-to-use connection.
+pub fn initialize(path: &Path) -> Result<Connection> {
+    let conn = Connection::open(path)?;
+
+    conn.execute_batch(
+        "
+        PRAGMA journal_mode = WAL;
+
+        CREATE TABLE IF NOT EXISTS bookmarks (
+            id         TEXT PRIMARY KEY,
+            path       TEXT NOT NULL UNIQUE,
+            name       TEXT NOT NULL,
+            sort_order INTEGER DEFAULT 0,
+            created_at TEXT
+        );
+
+        CREATE TABLE IF NOT EXISTS recent_locations (
+            path           TEXT PRIMARY KEY,
+            name           TEXT NOT NULL,
+            is_dir         INTEGER DEFAULT 0,
+            access_count   INTEGER DEFAULT 0,
+            first_accessed TEXT,
+            last_accessed  TEXT
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_recent_last_accessed ON recent_locations(last_accessed);
+        ",
+    )?;
+
+    Ok(conn)
+}
@@ -0,0 +1,453 @@
+// src-tauri/src/lsp.rs
+// Language servers for the editor: process management and JSON-RPC framing.
+// The frontend is the LSP client; messages pass through unchanged.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, State};
+use uuid::Uuid;
+
+use crate::git::repo_root;
+use crate::path_policy::{check_read, FsError};
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+/// Event carrying one message from a server: `{ server_id, message }`.
+const MESSAGE_EVENT: &str = "lsp-message";
+/// Event carrying server lifecycle changes, see `LspStatus`.
+const STATUS_EVENT: &str = "lsp-status";
+
+/// Crashes tolerated within `RESTART_WINDOW` before giving up on a server.
+const MAX_RESTARTS: usize = 3;
+const RESTART_WINDOW: Duration = Duration::from_secs(180);
+/// Time a stopping server gets to exit after the `exit` notification.
+const STOP_GRACE: Duration = Duration::from_secs(2);
+
+struct ServerSpec {
+    command: &'static str,
+    args: &'static [&'static str],
+    languages: &'static [&'static str],
+    /// Files marking a workspace root for this server
+    root_markers: &'static [&'static str],
+}
+
+const SERVERS: &[ServerSpec] = &[
+    ServerSpec {
+        command: "rust-analyzer",
+        args: &[],
+        languages: &["rust"],
+        root_markers: &["Cargo.toml"],
+    },
+    ServerSpec {
+        command: "typescript-language-server",
+        args: &["--stdio"],
+        languages: &["typescript", "typescriptreact", "javascript", "javascriptreact"],
+        root_markers: &["tsconfig.json", "jsconfig.json", "package.json"],
+    },
+    ServerSpec {
+        command: "pyright-langserver",
+        args: &["--stdio"],
+        languages: &["python"],
+        root_markers: &["pyproject.toml", "setup.py", "setup.cfg", "pyrightconfig.json"],
+    },
+];
+
+fn spec_for(language: &str) -> Option<&'static ServerSpec> {
+    SERVERS.iter().find(|s| s.languages.contains(&language))
+}
+
+/// Workspace root for a file or folder: the nearest ancestor with one of the
+/// server's marker files, else the git work tree, else the folder itself.
+fn workspace_root(spec: &ServerSpec, path: &Path) -> PathBuf {
+    let start = if path.is_dir() { path } else { path.parent().unwrap_or(path) };
+    start
+        .ancestors()
+        .find(|dir| spec.root_markers.iter().any(|marker| dir.join(marker).is_file()))
+        .map(Path::to_path_buf)
+        .or_else(|| repo_root(start))
+        .unwrap_or_else(|| start.to_path_buf())
+}
+
+/// Full path of `name` on PATH. npm installs language servers as `.cmd`
+/// shims on Windows, which `Command::new` does not resolve by itself.
+pub(crate) fn find_on_path(name: &str) -> Option<PathBuf> {
+    let extensions: &[&str] = if cfg!(target_os = "windows") { &[".exe", ".cmd", ".bat"] } else { &[""] };
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path)
+        .flat_map(|dir| extensions.iter().map(move |ext| dir.join(format!("{}{}", name, ext))))
+        .find(|candidate| candidate.is_file())
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ServerState {
+    Running,
+    /// Crashed and relaunched; the client must initialize it again
+    Restarted,
+    Stopped,
+    /// Crashed too often, or could not be relaunched
+    Failed,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct LspStatus {
+    server_id: String,
+    state: ServerState,
+    message: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+struct LspMessage {
+    server_id: String,
+    message: Value,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct LspServerInfo {
+    id: String,
+    command: String,
+    root: String,
+    languages: Vec<String>,
+    state: ServerState,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct AvailableServer {
+    command: String,
+    languages: Vec<String>,
+    path: Option<String>,
+}
+
+struct LspServer {
+    id: String,
+    spec: &'static ServerSpec,
+    binary: PathBuf,
+    root: PathBuf,
+    /// Messages for the writer thread, which owns the server's stdin
+    outbox: Mutex<Option<Sender<Value>>>,
+    child: Mutex<Option<Child>>,
+    state: Mutex<ServerState>,
+    stopping: AtomicBool,
+    crashes: Mutex<Vec<Instant>>,
+}
+
+impl LspServer {
+    fn info(&self) -> LspServerInfo {
+        LspServerInfo {
+            id: self.id.clone(),
+            command: self.spec.command.to_string(),
+            root: self.root.to_string_lossy().to_string(),
+            languages: self.spec.languages.iter().map(|l| l.to_string()).collect(),
+            state: self.state.lock().map(|s| *s).unwrap_or(ServerState::Failed),
+        }
+    }
+
+    fn set_state(&self, app: &AppHandle, state: ServerState, message: Option<String>) {
+        if let Ok(mut current) = self.state.lock() {
+            *current = state;
+        }
+        let _ = app.emit(
+            STATUS_EVENT,
+            LspStatus {
+                server_id: self.id.clone(),
+                state,
+                message,
+            },
+        );
+    }
+}
+
+/// Running language servers by id.
+pub struct LspState {
+    servers: Arc<Mutex<HashMap<String, Arc<LspServer>>>>,
+}
+
+impl Default for LspState {
+    fn default() -> Self {
+        Self {
+            servers: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+// ─── JSON-RPC framing ─────────────────────────────────────────────────────────
+
+/// Read one `Content-Length` framed message. `Ok(None)` at end of stream.
+fn read_message(reader: &mut impl BufRead) -> Result<Option<Vec<u8>>, String> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        let read = reader
+            .read_line(&mut header)
+            .map_err(|e| format!("Failed to read from language server: {}", e))?;
+        if read == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+    let length = length.ok_or("Language server sent a message without Content-Length")?;
+    let mut body = vec![0; length];
+    reader
+        .read_exact(&mut body)
+        .map_err(|e| format!("Failed to read from language server: {}", e))?;
+    Ok(Some(body))
+}
+
+fn write_message(writer: &mut impl Write, message: &Value) -> Result<(), String> {
+    let body = serde_json::to_vec(message).map_err(|e| format!("Failed to encode message: {}", e))?;
+    write!(writer, "Content-Length: {}\r\n\r\n", body.len())
+        .and_then(|_| writer.write_all(&body))
+        .and_then(|_| writer.flush())
+        .map_err(|e| format!("Failed to write to language server: {}", e))
+}
+
+// ─── Process management ───────────────────────────────────────────────────────
+
+fn launch(app: &AppHandle, server: &Arc<LspServer>) -> Result<(), String> {
+    let mut cmd = Command::new(&server.binary);
+    cmd.args(server.spec.args)
+        .current_dir(&server.root)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    #[cfg(target_os = "windows")]
+    {
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("Failed to start {}: {}", server.spec.command, e))?;
+    let stdout = child.stdout.take().ok_or("Language server has no stdout")?;
+    let stderr = child.stderr.take();
+    let mut stdin = child.stdin.take().ok_or("Language server has no stdin")?;
+    *server.child.lock().map_err(|e| e.to_string())? = Some(child);
+
+    // A slow server must not block the command that sends to it
+    let (outbox, messages) = mpsc::channel::<Value>();
+    *server.outbox.lock().map_err(|e| e.to_string())? = Some(outbox);
+    let name = server.spec.command;
+    std::thread::spawn(move || {
+        for message in messages {
+            if let Err(e) = write_message(&mut stdin, &message) {
+                log::warn!("{}: {}", name, e);
+                break;
+            }
+        }
+    });
+
+    if let Some(stderr) = stderr {
+        let name = server.spec.command;
+        std::thread::spawn(move || {
+            for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                log::debug!("{}: {}", name, line);
+            }
+        });
+    }
+
+    let app = app.clone();
+    let server = server.clone();
+    std::thread::spawn(move || {
+        let mut reader = BufReader::new(stdout);
+        loop {
+            match read_message(&mut reader) {
+                Ok(Some(body)) => match serde_json::from_slice::<Value>(&body) {
+                    Ok(message) => {
+                        let _ = app.emit(
+                            MESSAGE_EVENT,
+                            LspMessage {
+                                server_id: server.id.clone(),
+                                message,
+                            },
+                        );
+                    }
+                    Err(e) => log::warn!("{}: invalid message: {}", server.spec.command, e),
+                },
+                Ok(None) => break,
+                Err(e) => {
+                    log::warn!("{}", e);
+                    break;
+                }
+            }
+        }
+        on_exit(&app, &server);
+    });
+
+    Ok(())
+}
+
+/// Called when a server's stdout closes: reap it, then restart it unless it
+/// was stopped on purpose or keeps crashing.
+fn on_exit(app: &AppHandle, server: &Arc<LspServer>) {
+    if let Ok(mut outbox) = server.outbox.lock() {
+        outbox.take();
+    }
+    let child = server.child.lock().ok().and_then(|mut child| child.take());
+    let status = child.and_then(|mut child| child.wait().ok());
+
+    if server.stopping.load(Ordering::Relaxed) {
+        server.set_state(app, ServerState::Stopped, None);
+        return;
+    }
+
+    let exit = status.map(|s| s.to_string()).unwrap_or_else(|| "exited".to_string());
+    let crashes = {
+        let Ok(mut crashes) = server.crashes.lock() else { return };
+        let now = Instant::now();
+        crashes.retain(|t| now.duration_since(*t) < RESTART_WINDOW);
+        crashes.push(now);
+        crashes.len()
+    };
+    if crashes > MAX_RESTARTS {
+        log::warn!("{} {} too often; not restarting", server.spec.command, exit);
+        server.set_state(app, ServerState::Failed, Some(format!("{} {} repeatedly", server.spec.command, exit)));
+        return;
+    }
+
+    log::info!("{} {}; restarting", server.spec.command, exit);
+    std::thread::sleep(Duration::from_secs(crashes as u64));
+    if server.stopping.load(Ordering::Relaxed) {
+        server.set_state(app, ServerState::Stopped, None);
+        return;
+    }
+    match launch(app, server) {
+        Ok(()) => server.set_state(app, ServerState::Restarted, Some(format!("{} {}", server.spec.command, exit))),
+        Err(e) => server.set_state(app, ServerState::Failed, Some(e)),
+    }
+}
+
+fn get_server(state: &LspState, server_id: &str) -> Result<Arc<LspServer>, String> {
+    state
+        .servers
+        .lock()
+        .map_err(|e| e.to_string())?
+        .get(server_id)
+        .cloned()
+        .ok_or_else(|| format!("Language server {} is not running", server_id))
+}
+
+// ─── Commands ─────────────────────────────────────────────────────────────────
+
+/// Language servers the editor knows, with their location if installed.
+#[tauri::command]
+pub fn lsp_available_servers() -> Vec<AvailableServer> {
+    SERVERS
+        .iter()
+        .map(|spec| AvailableServer {
+            command: spec.command.to_string(),
+            languages: spec.languages.iter().map(|l| l.to_string()).collect(),
+            path: find_on_path(spec.command).map(|p| p.to_string_lossy().to_string()),
+        })
+        .collect()
+}
+
+/// Start the server for `language` in the workspace containing `path` (a file
+/// or folder), or return the one already running there. The caller sends
+/// `initialize` once it is running.
+#[tauri::command]
+pub fn lsp_start(
+    app: AppHandle,
+    state: State<'_, LspState>,
+    language: String,
+    path: String,
+) -> Result<LspServerInfo, FsError> {
+    let path = check_read(&path)?;
+    let spec = spec_for(&language).ok_or_else(|| format!("No language server for {}", language))?;
+    let root = workspace_root(spec, &path);
+
+    let mut servers = state.servers.lock().map_err(|e| e.to_string())?;
+    if let Some(existing) = servers.values().find(|s| {
+        std::ptr::eq(s.spec, spec) && s.root == root && !s.stopping.load(Ordering::Relaxed)
+    }) {
+        if existing.info().state != ServerState::Failed {
+            return Ok(existing.info());
+        }
+    }
+
+    let binary = find_on_path(spec.command)
+        .ok_or_else(|| format!("{} not found. Please ensure it is installed and in your PATH.", spec.command))?;
+    let server = Arc::new(LspServer {
+        id: Uuid::new_v4().to_string(),
+        spec,
+        binary,
+        root,
+        outbox: Mutex::new(None),
+        child: Mutex::new(None),
+        state: Mutex::new(ServerState::Running),
+        stopping: AtomicBool::new(false),
+        crashes: Mutex::new(Vec::new()),
+    });
+    launch(&app, &server)?;
+    servers.retain(|_, s| s.info().state != ServerState::Failed);
+    servers.insert(server.id.clone(), server.clone());
+    Ok(server.info())
+}
+
+/// Queue a request, response or notification for a server. Messages are
+/// written in the order they are sent; a failed write ends the server's
+/// writer and shows up as a crash.
+#[tauri::command]
+pub fn lsp_send(state: State<'_, LspState>, server_id: String, message: Value) -> Result<(), String> {
+    let server = get_server(&state, &server_id)?;
+    let outbox = server.outbox.lock().map_err(|e| e.to_string())?;
+    outbox
+        .as_ref()
+        .and_then(|outbox| outbox.send(message).ok())
+        .ok_or_else(|| format!("{} is not running", server.spec.command))
+}
+
+/// Stop a server. Send `shutdown` first for a clean exit; the process is
+/// killed if it is still alive shortly after the `exit` notification.
+#[tauri::command]
+pub fn lsp_stop(state: State<'_, LspState>, server_id: String) -> Result<(), String> {
+    let server = state
+        .servers
+        .lock()
+        .map_err(|e| e.to_string())?
+        .remove(&server_id)
+        .ok_or_else(|| format!("Language server {} is not running", server_id))?;
+    server.stopping.store(true, Ordering::Relaxed);
+
+    // Dropping the sender ends the writer once `exit` is written
+    if let Ok(mut outbox) = server.outbox.lock() {
+        if let Some(outbox) = outbox.take() {
+            let _ = outbox.send(serde_json::json!({ "jsonrpc": "2.0", "method": "exit" }));
+        }
+    }
+
+    std::thread::spawn(move || {
+        std::thread::sleep(STOP_GRACE);
+        if let Ok(mut child) = server.child.lock() {
+            if let Some(child) = child.as_mut() {
+                let _ = child.kill();
+            }
+        }
+    });
+    Ok(())
+}
+
+#[tauri::command]
+pub fn lsp_list_servers(state: State<'_, LspState>) -> Result<Vec<LspServerInfo>, String> {
+    let servers = state.servers.lock().map_err(|e| e.to_string())?;
+    Ok(servers.values().map(|s| s.info()).collect())
+}
@@ -0,0 +1,329 @@
+// src-tauri/src/media_info.rs
+// Media metadata extraction: ffprobe for audio/video, EXIF for images
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::ffmpeg::{create_ffmpeg_command, find_ffprobe};
+use crate::file_settings::{cache_key, get_cache_dir, touch_cache_entry};
+use crate::path_policy::check_read;
+use crate::thumbnails::is_image_path;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct StreamInfo {
+    index: u32,
+    codec_type: String,
+    codec_name: Option<String>,
+    codec_long_name: Option<String>,
+    width: Option<u32>,
+    height: Option<u32>,
+    frame_rate: Option<f64>,
+    bit_rate: Option<u64>,
+    channels: Option<u32>,
+    channel_layout: Option<String>,
+    sample_rate: Option<u32>,
+    language: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct GpsInfo {
+    latitude: f64,
+    longitude: f64,
+    altitude: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ExifInfo {
+    orientation: Option<u32>,
+    camera_make: Option<String>,
+    camera_model: Option<String>,
+    lens_model: Option<String>,
+    date_taken: Option<String>,
+    exposure_time: Option<String>,
+    f_number: Option<f64>,
+    iso: Option<u32>,
+    focal_length: Option<f64>,
+    gps: Option<GpsInfo>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct MediaInfo {
+    /// "image", "video" or "audio"
+    kind: String,
+    format_name: Option<String>,
+    /// Duration in seconds
+    duration: Option<f64>,
+    /// Overall bitrate in bits per second
+    bit_rate: Option<u64>,
+    width: Option<u32>,
+    height: Option<u32>,
+    frame_rate: Option<f64>,
+    video_codec: Option<String>,
+    audio_codec: Option<String>,
+    audio_channels: Option<u32>,
+    sample_rate: Option<u32>,
+    streams: Vec<StreamInfo>,
+    exif: Option<ExifInfo>,
+}
+
+// ─── ffprobe ──────────────────────────────────────────────────────────────────
+
+/// ffprobe reports most numbers as strings ("12.5", "128000").
+fn json_number<T: std::str::FromStr>(value: &Value) -> Option<T> {
+    match value {
+        Value::String(s) => s.parse().ok(),
+        Value::Number(n) => n.to_string().parse().ok(),
+        _ => None,
+    }
+}
+
+fn json_string(value: &Value) -> Option<String> {
+    value.as_str().map(|s| s.to_string())
+}
+
+/// Parse an ffprobe rational such as "30000/1001"; "0/0" means unknown.
+fn parse_frame_rate(value: &Value) -> Option<f64> {
+    let s = value.as_str()?;
+    let (num, den) = s.split_once('/')?;
+    let num: f64 = num.parse().ok()?;
+    let den: f64 = den.parse().ok()?;
+    if den == 0.0 || num == 0.0 {
+        return None;
+    }
+    Some(num / den)
+}
+
+fn parse_stream(stream: &Value) -> StreamInfo {
+    let frame_rate = parse_frame_rate(&stream["avg_frame_rate"])
+        .or_else(|| parse_frame_rate(&stream["r_frame_rate"]));
+
+    StreamInfo {
+        index: stream["index"].as_u64().unwrap_or(0) as u32,
+        codec_type: json_string(&stream["codec_type"]).unwrap_or_else(|| "unknown".to_string()),
+        codec_name: json_string(&stream["codec_name"]),
+        codec_long_name: json_string(&stream["codec_long_name"]),
+        width: stream["width"].as_u64().map(|v| v as u32),
+        height: stream["height"].as_u64().map(|v| v as u32),
+        frame_rate,
+        bit_rate: json_number(&stream["bit_rate"]),
+        channels: stream["channels"].as_u64().map(|v| v as u32),
+        channel_layout: json_string(&stream["channel_layout"]),
+        sample_rate: json_number(&stream["sample_rate"]),
+        language: json_string(&stream["tags"]["language"]),
+    }
+}
+
+fn probe_media(path: &Path) -> Result<MediaInfo, String> {
+    let ffprobe_path = find_ffprobe()?;
+
+    let output = create_ffmpeg_command(&ffprobe_path)
+        .args(["-v", "quiet", "-print_format", "json", "-show_format", "-show_streams"])
+        .arg(path)
+        .stderr(std::process::Stdio::null())
+        .output()
+        .map_err(|e| format!("FFprobe execution failed: {}", e))?;
+
+    if !output.status.success() {
+        return Err("FFprobe could not read this file".to_string());
+    }
+
+    let probe: Value = serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("Failed to parse FFprobe output: {}", e))?;
+
+    let streams: Vec<StreamInfo> = probe["streams"]
+        .as_array()
+        .map(|s| s.iter().map(parse_stream).collect())
+        .unwrap_or_default();
+
+    // Cover art in audio files shows up as a single-frame video stream.
+    let attached_pic = |index: u32| {
+        probe["streams"]
+            .as_array()
+            .and_then(|s| s.iter().find(|st| st["index"].as_u64() == Some(index as u64)))
+            .map(|st| st["disposition"]["attached_pic"].as_u64() == Some(1))
+            .unwrap_or(false)
+    };
+
+    let video = streams
+        .iter()
+        .find(|s| s.codec_type == "video" && !attached_pic(s.index));
+    let audio = streams.iter().find(|s| s.codec_type == "audio");
+
+    let kind = if video.is_some() { "video" } else if audio.is_some() { "audio" } else { "image" };
+
+    Ok(MediaInfo {
+        kind: kind.to_string(),
+        format_name: json_string(&probe["format"]["format_long_name"])
+            .or_else(|| json_string(&probe["format"]["format_name"])),
+        duration: json_number(&probe["format"]["duration"]),
+        bit_rate: json_number(&probe["format"]["bit_rate"]),
+        width: video.and_then(|v| v.width),
+        height: video.and_then(|v| v.height),
+        frame_rate: video.and_then(|v| v.frame_rate),
+        video_codec: video.and_then(|v| v.codec_name.clone()),
+        audio_codec: audio.and_then(|a| a.codec_name.clone()),
+        audio_channels: audio.and_then(|a| a.channels),
+        sample_rate: audio.and_then(|a| a.sample_rate),
+        streams,
+        exif: None,
+    })
+}
+
+/// Container duration in seconds, used to turn ffmpeg progress into a percentage.
+pub(crate) fn probe_duration(path: &Path) -> Option<f64> {
+    probe_media(path).ok()?.duration
+}
+
+// ─── EXIF ─────────────────────────────────────────────────────────────────────
+
+fn exif_string(exif: &exif::Exif, tag: exif::Tag) -> Option<String> {
+    match &exif.get_field(tag, exif::In::PRIMARY)?.value {
+        exif::Value::Ascii(parts) => parts
+            .first()
+            .map(|bytes| String::from_utf8_lossy(bytes).trim().to_string())
+            .filter(|s| !s.is_empty()),
+        _ => None,
+    }
+}
+
+fn exif_rational(exif: &exif::Exif, tag: exif::Tag) -> Option<f64> {
+    match &exif.get_field(tag, exif::In::PRIMARY)?.value {
+        exif::Value::Rational(values) => values.first().map(|r| r.to_f64()),
+        exif::Value::SRational(values) => values.first().map(|r| r.to_f64()),
+        _ => None,
+    }
+}
+
+fn exif_uint(exif: &exif::Exif, tag: exif::Tag) -> Option<u32> {
+    exif.get_field(tag, exif::In::PRIMARY)?.value.get_uint(0)
+}
+
+/// Convert a degrees/minutes/seconds triple plus N/S/E/W reference to decimal degrees.
+fn gps_coordinate(exif: &exif::Exif, tag: exif::Tag, ref_tag: exif::Tag) -> Option<f64> {
+    let exif::Value::Rational(dms) = &exif.get_field(tag, exif::In::PRIMARY)?.value else {
+        return None;
+    };
+    if dms.len() < 3 {
+        return None;
+    }
+    let degrees = dms[0].to_f64() + dms[1].to_f64() / 60.0 + dms[2].to_f64() / 3600.0;
+    let reference = exif_string(exif, ref_tag).unwrap_or_default();
+    if reference.starts_with('S') || reference.starts_with('W') {
+        Some(-degrees)
+    } else {
+        Some(degrees)
+    }
+}
+
+fn read_exif(path: &Path) -> Option<ExifInfo> {
+    let file = fs::File::open(path).ok()?;
+    let mut reader = std::io::BufReader::new(file);
+    let exif = exif::Reader::new().read_from_container(&mut reader).ok()?;
+
+    let exposure_time = exif
+        .get_field(exif::Tag::ExposureTime, exif::In::PRIMARY)
+        .and_then(|field| match &field.value {
+            exif::Value::Rational(v) => v.first().copied(),
+            _ => None,
+        })
+        .map(|r| {
+            // Short exposures read as fractions ("1/250"), long ones as seconds ("2.5")
+            if r.num <= 1 { format!("{}/{}", r.num, r.denom) } else { format!("{:.1}", r.to_f64()) }
+        });
+
+    let gps = gps_coordinate(&exif, exif::Tag::GPSLatitude, exif::Tag::GPSLatitudeRef)
+        .zip(gps_coordinate(&exif, exif::Tag::GPSLongitude, exif::Tag::GPSLongitudeRef))
+        .map(|(latitude, longitude)| {
+            // GPSAltitudeRef 1 means below sea level
+            let below = exif_uint(&exif, exif::Tag::GPSAltitudeRef) == Some(1);
+            let altitude = exif_rational(&exif, exif::Tag::GPSAltitude)
+                .map(|a| if below { -a } else { a });
+            GpsInfo { latitude, longitude, altitude }
+        });
+
+    Some(ExifInfo {
+        orientation: exif_uint(&exif, exif::Tag::Orientation),
+        camera_make: exif_string(&exif, exif::Tag::Make),
+        camera_model: exif_string(&exif, exif::Tag::Model),
+        lens_model: exif_string(&exif, exif::Tag::LensModel),
+        date_taken: exif_string(&exif, exif::Tag::DateTimeOriginal)
+            .or_else(|| exif_string(&exif, exif::Tag::DateTime)),
+        exposure_time,
+        f_number: exif_rational(&exif, exif::Tag::FNumber),
+        iso: exif_uint(&exif, exif::Tag::PhotographicSensitivity),
+        focal_length: exif_rational(&exif, exif::Tag::FocalLength),
+        gps,
+    })
+}
+
+/// EXIF orientation (1–8) of an image, if it has one.
+pub(crate) fn read_exif_orientation(path: &Path) -> Option<u32> {
+    read_exif(path).and_then(|e| e.orientation)
+}
+
+fn image_info(path: &Path) -> Result<MediaInfo, String> {
+    let mut info = match image::image_dimensions(path) {
+        Ok((width, height)) => MediaInfo {
+            kind: "image".to_string(),
+            width: Some(width),
+            height: Some(height),
+            ..Default::default()
+        },
+        // Formats the image crate can't read (HEIC, AVIF, ...) often still probe.
+        Err(_) => probe_media(path)?,
+    };
+
+    info.kind = "image".to_string();
+    info.format_name = info.format_name.or_else(|| {
+        image::ImageFormat::from_path(path)
+            .ok()
+            .map(|f| format!("{:?}", f).to_uppercase())
+    });
+    info.exif = read_exif(path);
+    Ok(info)
+}
+
+// ─── Command ──────────────────────────────────────────────────────────────────
+
+/// Media info is cached as JSON next to the thumbnails, under the same key scheme.
+fn cache_path(path: &Path) -> Result<PathBuf, String> {
+    let key = cache_key(path, "media_info")?;
+    Ok(get_cache_dir()?.join(format!("{}.json", key)))
+}
+
+#[tauri::command]
+pub async fn get_media_info(path: String) -> Result<MediaInfo, String> {
+    tokio::task::spawn_blocking(move || {
+        let source = &check_read(&path)?;
+        if !source.is_file() {
+            return Err("Path is not a file".to_string());
+        }
+
+        let cached = cache_path(source)?;
+        if let Ok(json) = fs::read(&cached) {
+            if let Ok(info) = serde_json::from_slice::<MediaInfo>(&json) {
+                touch_cache_entry(&cached);
+                return Ok(info);
+            }
+        }
+
+        let info = if is_image_path(source) {
+            image_info(source)?
+        } else {
+            probe_media(source)?
+        };
+
+        if let Ok(json) = serde_json::to_vec(&info) {
+            if let Err(e) = fs::write(&cached, json) {
+                log::warn!("media_info: failed to cache info for {:?}: {}", source, e);
+            }
+        }
+
+        Ok(info)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
@@ -0,0 +1,420 @@
+// src-tauri/src/path_policy.rs
+// Central checks for paths coming from the webview: canonicalization,
+// allowed roots and protection of system locations.
+
+use std::fmt;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{OnceLock, RwLock};
+
+use serde::Serialize;
+use tauri::AppHandle;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+/// Roots the user configured, kept by the backend in `allowed_roots.json`
+/// so the webview can't replace them behind the user's back. Empty means
+/// the defaults.
+static CONFIGURED_ROOTS: RwLock<Vec<PathBuf>> = RwLock::new(Vec::new());
+
+/// The home folder and mounted volumes; recomputed when a path falls
+/// outside them, since a volume may have been mounted since.
+static DEFAULT_ROOTS: RwLock<Vec<PathBuf>> = RwLock::new(Vec::new());
+
+static ROOTS_FILE: OnceLock<PathBuf> = OnceLock::new();
+
+const ROOTS_FILE_NAME: &str = "allowed_roots.json";
+
+/// Trees that are never modified, whatever the allowed roots say.
+#[cfg(target_os = "windows")]
+const PROTECTED_TREES: &[&str] = &[
+    "C:\\Windows",
+    "C:\\Program Files",
+    "C:\\Program Files (x86)",
+    "C:\\ProgramData",
+];
+
+#[cfg(target_os = "macos")]
+const PROTECTED_TREES: &[&str] = &[
+    "/System", "/Library", "/bin", "/sbin", "/usr", "/private", "/dev", "/Volumes/Recovery",
+];
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const PROTECTED_TREES: &[&str] = &[
+    "/bin", "/boot", "/dev", "/etc", "/lib", "/lib32", "/lib64", "/libx32", "/proc",
+    "/sbin", "/snap", "/sys", "/usr", "/var/lib",
+];
+
+/// Error returned by file commands. Serialized with a `kind` tag so the
+/// frontend can tell policy denials from ordinary I/O failures; every variant
+/// carries a human-readable `message`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FsError {
+    InvalidPath { path: String, message: String },
+    NotFound { path: String, message: String },
+    OutsideAllowedRoots { path: String, message: String },
+    ProtectedPath { path: String, message: String },
+    Io { message: String },
+}
+
+impl FsError {
+    fn invalid(path: &Path, reason: &str) -> Self {
+        FsError::InvalidPath {
+            path: path.to_string_lossy().to_string(),
+            message: format!("Invalid path {}: {}", path.display(), reason),
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            FsError::InvalidPath { message, .. }
+            | FsError::NotFound { message, .. }
+            | FsError::OutsideAllowedRoots { message, .. }
+            | FsError::ProtectedPath { message, .. }
+            | FsError::Io { message } => message,
+        }
+    }
+}
+
+impl fmt::Display for FsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl From<String> for FsError {
+    fn from(message: String) -> Self {
+        FsError::Io { message }
+    }
+}
+
+impl From<&str> for FsError {
+    fn from(message: &str) -> Self {
+        FsError::Io { message: message.to_string() }
+    }
+}
+
+impl From<FsError> for String {
+    fn from(e: FsError) -> Self {
+        e.to_string()
+    }
+}
+
+/// Drop the `\\?\` prefix `fs::canonicalize` adds on Windows so paths
+/// compare against (and round-trip to) what the user typed.
+fn strip_verbatim(path: PathBuf) -> PathBuf {
+    #[cfg(target_os = "windows")]
+    {
+        let s = path.to_string_lossy();
+        if let Some(rest) = s.strip_prefix(r"\\?\UNC\") {
+            return PathBuf::from(format!(r"\\{}", rest));
+        }
+        if let Some(rest) = s.strip_prefix(r"\\?\") {
+            return PathBuf::from(rest);
+        }
+    }
+    path
+}
+
+fn canonicalize(path: &Path) -> Result<PathBuf, FsError> {
+    if path.as_os_str().is_empty() {
+        return Err(FsError::invalid(path, "empty path"));
+    }
+    if !path.is_absolute() {
+        return Err(FsError::invalid(path, "path must be absolute"));
+    }
+    fs::canonicalize(path).map(strip_verbatim).map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            FsError::NotFound {
+                path: path.to_string_lossy().to_string(),
+                message: format!("Path not found: {}", path.display()),
+            }
+        } else {
+            FsError::Io { message: format!("Failed to resolve {}: {}", path.display(), e) }
+        }
+    })
+}
+
+/// A single path component usable as a new file or folder name.
+pub(crate) fn validate_name(name: &str) -> Result<(), FsError> {
+    let mut components = Path::new(name).components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(_)), None) if !name.contains(['/', '\\']) => Ok(()),
+        _ => Err(FsError::invalid(Path::new(name), "name must not contain path separators")),
+    }
+}
+
+/// Canonical form of a path that may not exist yet: the parent is resolved
+/// and the final component is appended as-is.
+fn canonicalize_target(path: &Path) -> Result<PathBuf, FsError> {
+    if path.exists() {
+        return canonicalize(path);
+    }
+    let parent = path.parent().ok_or_else(|| FsError::invalid(path, "no parent directory"))?;
+    let name = path.file_name().ok_or_else(|| FsError::invalid(path, "no file name"))?;
+    validate_name(&name.to_string_lossy())?;
+    Ok(canonicalize(parent)?.join(name))
+}
+
+/// Canonical form of a path that may be gone, e.g. a deleted file still
+/// known to git: the deepest existing ancestor is resolved and the missing
+/// components are appended. An existing file's own name is kept, so a
+/// symlink stays the link itself.
+pub(crate) fn canonicalize_lenient(path: &Path) -> Result<PathBuf, FsError> {
+    if path.is_dir() {
+        return canonicalize(path);
+    }
+    let mut missing = Vec::new();
+    let mut existing = path;
+    loop {
+        match existing.components().next_back() {
+            Some(Component::Normal(name)) => missing.push(name),
+            _ => return Err(FsError::invalid(path, "path must not end in '.' or '..'")),
+        }
+        existing = existing.parent().ok_or_else(|| FsError::invalid(path, "no existing parent"))?;
+        if fs::symlink_metadata(existing).is_ok() {
+            break;
+        }
+    }
+    let mut resolved = canonicalize(existing)?;
+    resolved.extend(missing.iter().rev());
+    Ok(resolved)
+}
+
+/// The home folder plus mounted volumes other than the system root.
+fn default_roots() -> Vec<PathBuf> {
+    let mut roots: Vec<PathBuf> = dirs::home_dir().into_iter().collect();
+
+    #[cfg(target_os = "windows")]
+    {
+        let system = std::env::var("SystemDrive").unwrap_or_else(|_| "C:".to_string()).to_uppercase();
+        for letter in b'A'..=b'Z' {
+            let drive = format!("{}:", letter as char);
+            let root = PathBuf::from(format!("{}\\", drive));
+            if drive != system && root.exists() {
+                roots.push(root);
+            }
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    roots.push(PathBuf::from("/Volumes"));
+
+    #[cfg(target_os = "linux")]
+    roots.extend(crate::volumes::mount_points().into_iter().filter(|p| p.parent().is_some()));
+
+    roots
+        .into_iter()
+        .filter_map(|root| fs::canonicalize(&root).ok().map(strip_verbatim))
+        .collect()
+}
+
+fn refresh_default_roots() -> Result<Vec<PathBuf>, FsError> {
+    let roots = default_roots();
+    *DEFAULT_ROOTS.write().map_err(|e| e.to_string())? = roots.clone();
+    Ok(roots)
+}
+
+/// Roots in force: the configured ones, or the defaults.
+fn effective_roots() -> Result<Vec<PathBuf>, FsError> {
+    let configured = CONFIGURED_ROOTS.read().map_err(|e| e.to_string())?.clone();
+    if !configured.is_empty() {
+        return Ok(configured);
+    }
+    refresh_default_roots()
+}
+
+fn ensure_allowed(path: &Path) -> Result<(), FsError> {
+    let inside = |roots: &[PathBuf]| roots.iter().any(|root| path.starts_with(root));
+    let configured = CONFIGURED_ROOTS.read().map_err(|e| e.to_string())?.clone();
+    let allowed = if configured.is_empty() {
+        let known = DEFAULT_ROOTS.read().map_err(|e| e.to_string())?.clone();
+        inside(&known) || inside(&refresh_default_roots()?)
+    } else {
+        inside(&configured)
+    };
+    if allowed {
+        return Ok(());
+    }
+    Err(FsError::OutsideAllowedRoots {
+        path: path.to_string_lossy().to_string(),
+        message: format!("{} is outside the allowed folders", path.display()),
+    })
+}
+
+fn in_protected_tree(path: &Path) -> bool {
+    PROTECTED_TREES.iter().any(|tree| {
+        #[cfg(target_os = "windows")]
+        {
+            path.to_string_lossy().to_lowercase().starts_with(&tree.to_lowercase())
+        }
+        #[cfg(not(target_os = "windows"))]
+        {
+            path.starts_with(tree)
+        }
+    })
+}
+
+fn is_home(path: &Path) -> bool {
+    dirs::home_dir().map(|h| h == path).unwrap_or(false)
+}
+
+/// Filesystem roots and the home folder can be written into, but never
+/// removed or renamed themselves.
+fn is_protected(path: &Path) -> bool {
+    in_protected_tree(path) || path.parent().is_none() || is_home(path)
+}
+
+fn ensure_not_protected(path: &Path) -> Result<(), FsError> {
+    if is_protected(path) {
+        return Err(FsError::ProtectedPath {
+            path: path.to_string_lossy().to_string(),
+            message: format!("{} is a protected system location", path.display()),
+        });
+    }
+    Ok(())
+}
+
+/// Resolve an existing path for reading.
+pub(crate) fn check_read(path: &str) -> Result<PathBuf, FsError> {
+    let resolved = canonicalize(Path::new(path))?;
+    ensure_allowed(&resolved)?;
+    Ok(resolved)
+}
+
+/// Resolve a path that will be created or overwritten.
+pub(crate) fn check_write(path: &str) -> Result<PathBuf, FsError> {
+    let resolved = canonicalize_target(Path::new(path))?;
+    ensure_allowed(&resolved)?;
+    if in_protected_tree(&resolved) {
+        return Err(FsError::ProtectedPath {
+            path: resolved.to_string_lossy().to_string(),
+            message: format!("{} is a protected system location", resolved.display()),
+        });
+    }
+    Ok(resolved)
+}
+
+/// Resolve an existing path that will be deleted, renamed or moved away.
+pub(crate) fn check_modify(path: &str) -> Result<PathBuf, FsError> {
+    // Resolve the parent only, so deleting a symlink targets the link itself
+    let raw = Path::new(path);
+    let resolved = match (raw.parent(), raw.file_name()) {
+        (Some(parent), Some(name)) => canonicalize(parent)?.join(name),
+        _ => canonicalize(raw)?,
+    };
+    if fs::symlink_metadata(&resolved).is_err() {
+        return Err(FsError::NotFound {
+            path: path.to_string(),
+            message: format!("Path not found: {}", path),
+        });
+    }
+    ensure_allowed(&resolved)?;
+    ensure_not_protected(&resolved)?;
+    Ok(resolved)
+}
+
+// ─── Allowed roots ────────────────────────────────────────────────────────────
+
+/// Load the configured roots saved in `data_dir`. Called once at startup.
+/// Roots that can't be resolved (e.g. an unplugged drive) are kept as
+/// written, so a missing folder never lifts the restriction.
+pub(crate) fn load_allowed_roots(data_dir: &Path) {
+    let file = ROOTS_FILE.get_or_init(|| data_dir.join(ROOTS_FILE_NAME));
+    let saved: Vec<String> = fs::read(file)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default();
+    let roots = saved
+        .iter()
+        .map(|root| {
+            canonicalize(Path::new(root)).unwrap_or_else(|e| {
+                log::warn!("path_policy: keeping unresolved allowed folder {}: {}", root, e);
+                PathBuf::from(root)
+            })
+        })
+        .collect();
+    if let Ok(mut configured) = CONFIGURED_ROOTS.write() {
+        *configured = roots;
+    }
+    let _ = refresh_default_roots();
+}
+
+fn save_allowed_roots(roots: &[PathBuf]) -> Result<(), FsError> {
+    let file = ROOTS_FILE.get().ok_or("Allowed folders are not loaded yet")?;
+    let list: Vec<String> = roots.iter().map(|r| r.to_string_lossy().to_string()).collect();
+    let json = serde_json::to_vec_pretty(&list).map_err(|e| e.to_string())?;
+    let tmp = file.with_extension("json.tmp");
+    fs::write(&tmp, json)
+        .and_then(|_| fs::rename(&tmp, file))
+        .map_err(|e| format!("Failed to save allowed folders: {}", e).into())
+}
+
+/// Ask the user, in a native dialog the webview can't script, before file
+/// access is extended to `folders`.
+fn confirm_widening(app: &AppHandle, folders: &[&PathBuf]) -> bool {
+    let list: Vec<String> = folders.iter().map(|f| format!("  {}", f.display())).collect();
+    app.dialog()
+        .message(format!(
+            "File operations are about to be allowed in:\n\n{}\n\nOnly continue if you changed the Allowed Folders setting yourself.",
+            list.join("\n")
+        ))
+        .title("Allow access to more folders?")
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancel)
+        .blocking_show()
+}
+
+fn to_strings(roots: &[PathBuf]) -> Vec<String> {
+    roots.iter().map(|r| r.to_string_lossy().to_string()).collect()
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AllowedRoots {
+    /// Folders the user configured; empty when the defaults apply
+    configured: Vec<String>,
+    /// Folders in force right now
+    effective: Vec<String>,
+}
+
+#[tauri::command]
+pub fn get_allowed_roots() -> Result<AllowedRoots, FsError> {
+    let configured = to_strings(&CONFIGURED_ROOTS.read().map_err(|e| e.to_string())?);
+    Ok(AllowedRoots { configured, effective: to_strings(&effective_roots()?) })
+}
+
+/// Replace the configured roots; an empty list restores the defaults.
+/// Every root must resolve to an existing folder. Narrowing applies at once;
+/// anything that extends access must be confirmed by the user in a native
+/// dialog first.
+#[tauri::command]
+pub async fn set_allowed_roots(app: AppHandle, roots: Vec<String>) -> Result<AllowedRoots, FsError> {
+    tokio::task::spawn_blocking(move || {
+        let mut requested = Vec::new();
+        for root in roots.iter().map(|r| r.trim()).filter(|r| !r.is_empty()) {
+            let resolved = canonicalize(Path::new(root))?;
+            if !resolved.is_dir() {
+                return Err(FsError::invalid(&resolved, "not a folder"));
+            }
+            requested.push(resolved);
+        }
+
+        let current = effective_roots()?;
+        let next = if requested.is_empty() { default_roots() } else { requested.clone() };
+        let widened: Vec<&PathBuf> =
+            next.iter().filter(|root| !current.iter().any(|c| root.starts_with(c))).collect();
+        if !widened.is_empty() && !confirm_widening(&app, &widened) {
+            return Err(FsError::OutsideAllowedRoots {
+                path: widened[0].to_string_lossy().to_string(),
+                message: "Access to the new folders was not allowed".to_string(),
+            });
+        }
+
+        save_allowed_roots(&requested)?;
+        let applied = AllowedRoots { configured: to_strings(&requested), effective: to_strings(&next) };
+        *CONFIGURED_ROOTS.write().map_err(|e| e.to_string())? = requested;
+        Ok(applied)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
@@ -0,0 +1,198 @@
+// src-tauri/src/recovery.rs
+// Crash recovery for unsaved editor buffers and the hot-exit handshake
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Event asking the frontend to snapshot its dirty buffers before quitting.
+const HOT_EXIT_EVENT: &str = "hot-exit";
+/// How long quitting waits for the frontend's `hot_exit_ready`.
+const HOT_EXIT_TIMEOUT: Duration = Duration::from_secs(2);
+
+static EXITING: AtomicBool = AtomicBool::new(false);
+static EXIT_READY: AtomicBool = AtomicBool::new(false);
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct BufferSnapshotInput {
+    buffer_id: String,
+    name: String,
+    path: Option<String>,
+    /// Editor content, in whatever form the editor keeps it
+    content: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BufferSnapshot {
+    buffer_id: String,
+    name: String,
+    path: Option<String>,
+    content: String,
+    /// Unix seconds
+    saved_at: i64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RecoverableBuffer {
+    buffer_id: String,
+    name: String,
+    path: Option<String>,
+    saved_at: i64,
+    size: u64,
+    /// The file on disk was modified after the snapshot was taken
+    file_changed: bool,
+}
+
+fn now_secs() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+fn recovery_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data dir: {}", e))?
+        .join("recovery");
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create recovery dir: {}", e))?;
+    Ok(dir)
+}
+
+/// Snapshot file of a buffer. Ids come from the frontend, so only plain
+/// names are accepted.
+fn snapshot_path(dir: &Path, buffer_id: &str) -> Result<PathBuf, String> {
+    let valid = !buffer_id.is_empty()
+        && buffer_id.len() <= 64
+        && buffer_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(format!("Invalid buffer id: {}", buffer_id));
+    }
+    Ok(dir.join(format!("{}.json", buffer_id)))
+}
+
+fn read_snapshot(path: &Path) -> Result<BufferSnapshot, String> {
+    let raw = fs::read(path).map_err(|e| format!("Failed to read snapshot: {}", e))?;
+    serde_json::from_slice(&raw).map_err(|e| format!("Failed to parse snapshot: {}", e))
+}
+
+fn modified_secs(path: &str) -> Option<i64> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    modified.duration_since(UNIX_EPOCH).ok().map(|d| d.as_secs() as i64)
+}
+
+/// Quit the app, giving the frontend a moment to snapshot dirty buffers
+/// first. Used instead of `app.exit` wherever the app is closed on purpose.
+pub(crate) fn request_exit(app: &AppHandle) {
+    if EXITING.swap(true, Ordering::AcqRel) {
+        return;
+    }
+    EXIT_READY.store(false, Ordering::Release);
+    if app.emit(HOT_EXIT_EVENT, ()).is_err() {
+        app.exit(0);
+        return;
+    }
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let started = Instant::now();
+        while !EXIT_READY.load(Ordering::Acquire) && started.elapsed() < HOT_EXIT_TIMEOUT {
+            std::thread::sleep(Duration::from_millis(25));
+        }
+        if !EXIT_READY.load(Ordering::Acquire) {
+            log::warn!("hot exit: frontend did not answer; quitting anyway");
+        }
+        app.exit(0);
+    });
+}
+
+// ─── Commands ─────────────────────────────────────────────────────────────────
+
+/// Store the latest content of an unsaved buffer, replacing its previous
+/// snapshot. Written to a temporary file first so a crash mid-write keeps
+/// the older snapshot.
+#[tauri::command]
+pub async fn save_buffer_snapshot(app: AppHandle, snapshot: BufferSnapshotInput) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        let dir = recovery_dir(&app)?;
+        let path = snapshot_path(&dir, &snapshot.buffer_id)?;
+        let stored = BufferSnapshot {
+            buffer_id: snapshot.buffer_id,
+            name: snapshot.name,
+            path: snapshot.path,
+            content: snapshot.content,
+            saved_at: now_secs(),
+        };
+        let json = serde_json::to_vec(&stored).map_err(|e| format!("Failed to encode snapshot: {}", e))?;
+        let temp = path.with_extension("json.tmp");
+        fs::write(&temp, json).map_err(|e| format!("Failed to write snapshot: {}", e))?;
+        fs::rename(&temp, &path).map_err(|e| format!("Failed to write snapshot: {}", e))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Forget a buffer's snapshot, e.g. once it was saved or closed.
+#[tauri::command]
+pub fn discard_buffer_snapshot(app: AppHandle, buffer_id: String) -> Result<(), String> {
+    let path = snapshot_path(&recovery_dir(&app)?, &buffer_id)?;
+    match fs::remove_file(&path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(format!("Failed to discard snapshot: {}", e)),
+    }
+}
+
+/// Snapshots left behind by the last session, newest first.
+#[tauri::command]
+pub fn list_recoverable_buffers(app: AppHandle) -> Result<Vec<RecoverableBuffer>, String> {
+    let dir = recovery_dir(&app)?;
+    let entries = fs::read_dir(&dir).map_err(|e| format!("Failed to read recovery dir: {}", e))?;
+
+    let mut buffers = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().map(|e| e != "json").unwrap_or(true) {
+            continue;
+        }
+        let snapshot = match read_snapshot(&path) {
+            Ok(s) => s,
+            Err(e) => {
+                log::warn!("recovery: skipping {}: {}", path.display(), e);
+                continue;
+            }
+        };
+        let file_changed = snapshot
+            .path
+            .as_deref()
+            .and_then(modified_secs)
+            .map(|modified| modified > snapshot.saved_at)
+            .unwrap_or(false);
+        buffers.push(RecoverableBuffer {
+            size: snapshot.content.len() as u64,
+            buffer_id: snapshot.buffer_id,
+            name: snapshot.name,
+            path: snapshot.path,
+            saved_at: snapshot.saved_at,
+            file_changed,
+        });
+    }
+    buffers.sort_by(|a, b| b.saved_at.cmp(&a.saved_at));
+    Ok(buffers)
+}
+
+#[tauri::command]
+pub fn get_buffer_snapshot(app: AppHandle, buffer_id: String) -> Result<BufferSnapshot, String> {
+    read_snapshot(&snapshot_path(&recovery_dir(&app)?, &buffer_id)?)
+}
+
+/// The frontend finished snapshotting after a `hot-exit` event.
+#[tauri::command]
+pub fn hot_exit_ready() {
+    EXIT_READY.store(true, Ordering::Release);
+}
@@ -0,0 +1,421 @@
+// src-tauri/src/search.rs
+// Project-wide find and replace with streamed results
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use ignore::overrides::OverrideBuilder;
+use ignore::WalkBuilder;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, State};
+
+use crate::path_policy::{check_read, check_write, FsError};
+
+/// Event carrying the matches of one file while a search runs.
+const RESULTS_EVENT: &str = "search-results";
+
+const DEFAULT_MAX_RESULTS: usize = 10_000;
+/// Larger files are skipped; they are almost never source code.
+const MAX_FILE_BYTES: u64 = 10 * 1024 * 1024;
+/// Long lines (minified code) are cut around the match in previews.
+const MAX_PREVIEW_CHARS: usize = 400;
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct SearchOptions {
+    query: String,
+    #[serde(default)]
+    is_regex: bool,
+    #[serde(default)]
+    case_sensitive: bool,
+    #[serde(default)]
+    whole_word: bool,
+    #[serde(default)]
+    include_globs: Vec<String>,
+    #[serde(default)]
+    exclude_globs: Vec<String>,
+    /// Skip files matched by `.gitignore`/`.ignore` (default true)
+    respect_gitignore: Option<bool>,
+    #[serde(default)]
+    include_hidden: bool,
+    max_results: Option<usize>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct SearchMatch {
+    /// 1-based line number
+    line: usize,
+    /// 1-based column in UTF-16 units, matching JavaScript string indices
+    column: usize,
+    /// Match length in UTF-16 units
+    length: usize,
+    /// Byte offset in the file; identifies the match for `replace_in_files`
+    offset: usize,
+    match_text: String,
+    line_text: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct FileMatches {
+    search_id: String,
+    path: String,
+    matches: Vec<SearchMatch>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct SearchSummary {
+    search_id: String,
+    files_searched: usize,
+    files_matched: usize,
+    match_count: usize,
+    /// The result limit was reached before the search finished
+    truncated: bool,
+    cancelled: bool,
+}
+
+/// The matches to replace in one file, as returned by `find_in_files`.
+#[derive(Deserialize, Debug, Clone)]
+pub struct ReplaceTarget {
+    path: String,
+    /// Byte offsets of the matches to replace; others are left alone
+    offsets: Vec<usize>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct FileReplacement {
+    path: String,
+    replacements: usize,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ReplaceResult {
+    files: Vec<FileReplacement>,
+    total: usize,
+}
+
+/// Cancellation flags of running searches by id.
+pub struct SearchJobs {
+    jobs: Arc<Mutex<HashMap<String, Arc<AtomicBool>>>>,
+}
+
+impl Default for SearchJobs {
+    fn default() -> Self {
+        Self {
+            jobs: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+// ─── Matching ─────────────────────────────────────────────────────────────────
+
+fn build_regex(options: &SearchOptions) -> Result<Regex, String> {
+    if options.query.is_empty() {
+        return Err("Search text is empty".to_string());
+    }
+    let mut pattern = if options.is_regex { options.query.clone() } else { regex::escape(&options.query) };
+    if options.whole_word {
+        pattern = format!(r"\b(?:{})\b", pattern);
+    }
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!options.case_sensitive)
+        .multi_line(true)
+        .size_limit(10 * 1024 * 1024)
+        .build()
+        .map_err(|e| format!("Invalid regular expression: {}", e))
+}
+
+fn utf16_len(s: &str) -> usize {
+    s.encode_utf16().count()
+}
+
+/// Text of a file worth searching: not too large, not binary, valid UTF-8.
+fn read_text(path: &Path) -> Option<String> {
+    let meta = fs::metadata(path).ok()?;
+    if meta.len() > MAX_FILE_BYTES {
+        return None;
+    }
+    let bytes = fs::read(path).ok()?;
+    if bytes.iter().take(8192).any(|&b| b == 0) {
+        return None;
+    }
+    String::from_utf8(bytes).ok()
+}
+
+fn preview(line: &str, match_start: usize) -> String {
+    if line.chars().count() <= MAX_PREVIEW_CHARS {
+        return line.to_string();
+    }
+    // Keep some context before the match
+    let mut start = match_start.saturating_sub(MAX_PREVIEW_CHARS / 4);
+    while !line.is_char_boundary(start) {
+        start -= 1;
+    }
+    line[start..].chars().take(MAX_PREVIEW_CHARS).collect()
+}
+
+fn find_matches(content: &str, regex: &Regex, limit: usize) -> Vec<SearchMatch> {
+    let mut matches = Vec::new();
+    let mut line_no = 1;
+    let mut line_start = 0;
+    let mut scanned = 0;
+
+    for m in regex.find_iter(content) {
+        if matches.len() >= limit {
+            break;
+        }
+        if m.start() == m.end() {
+            continue;
+        }
+        let skipped = &content[scanned..m.start()];
+        line_no += skipped.matches('\n').count();
+        if let Some(i) = skipped.rfind('\n') {
+            line_start = scanned + i + 1;
+        }
+        scanned = m.start();
+
+        let line_end = content[m.start()..].find('\n').map(|i| m.start() + i).unwrap_or(content.len());
+        let line = content[line_start..line_end].trim_end_matches('\r');
+        let in_line = m.start() - line_start;
+
+        matches.push(SearchMatch {
+            line: line_no,
+            column: utf16_len(&content[line_start..m.start()]) + 1,
+            length: utf16_len(m.as_str()),
+            offset: m.start(),
+            match_text: m.as_str().to_string(),
+            line_text: preview(line, in_line.min(line.len())),
+        });
+    }
+    matches
+}
+
+fn build_walker(root: &Path, options: &SearchOptions) -> Result<ignore::Walk, String> {
+    let mut overrides = OverrideBuilder::new(root);
+    for glob in options.include_globs.iter().map(|g| g.trim()).filter(|g| !g.is_empty()) {
+        overrides.add(glob).map_err(|e| format!("Invalid include glob '{}': {}", glob, e))?;
+    }
+    for glob in options.exclude_globs.iter().map(|g| g.trim()).filter(|g| !g.is_empty()) {
+        overrides
+            .add(&format!("!{}", glob))
+            .map_err(|e| format!("Invalid exclude glob '{}': {}", glob, e))?;
+    }
+    let overrides = overrides.build().map_err(|e| format!("Invalid globs: {}", e))?;
+
+    let respect = options.respect_gitignore.unwrap_or(true);
+    Ok(WalkBuilder::new(root)
+        .hidden(!options.include_hidden)
+        .git_ignore(respect)
+        .git_global(respect)
+        .git_exclude(respect)
+        .ignore(respect)
+        .parents(respect)
+        .overrides(overrides)
+        .build())
+}
+
+fn run_search(
+    app: &AppHandle,
+    search_id: &str,
+    root: &Path,
+    options: &SearchOptions,
+    cancelled: &AtomicBool,
+) -> Result<SearchSummary, String> {
+    let regex = build_regex(options)?;
+    let max_results = options.max_results.unwrap_or(DEFAULT_MAX_RESULTS);
+    let mut summary = SearchSummary {
+        search_id: search_id.to_string(),
+        files_searched: 0,
+        files_matched: 0,
+        match_count: 0,
+        truncated: false,
+        cancelled: false,
+    };
+
+    for entry in build_walker(root, options)?.flatten() {
+        if cancelled.load(Ordering::Relaxed) {
+            summary.cancelled = true;
+            break;
+        }
+        if !entry.file_type().map(|t| t.is_file()).unwrap_or(false) {
+            continue;
+        }
+        let Some(content) = read_text(entry.path()) else { continue };
+        summary.files_searched += 1;
+
+        let matches = find_matches(&content, &regex, max_results - summary.match_count);
+        if matches.is_empty() {
+            continue;
+        }
+        summary.files_matched += 1;
+        summary.match_count += matches.len();
+        let _ = app.emit(
+            RESULTS_EVENT,
+            FileMatches {
+                search_id: search_id.to_string(),
+                path: entry.path().to_string_lossy().to_string(),
+                matches,
+            },
+        );
+
+        if summary.match_count >= max_results {
+            summary.truncated = true;
+            break;
+        }
+    }
+
+    Ok(summary)
+}
+
+// ─── Replacing ────────────────────────────────────────────────────────────────
+
+/// New content of a file with the matches at `offsets` replaced. Fails when
+/// the file no longer matches at one of the offsets.
+fn replace_content(
+    content: &str,
+    regex: &Regex,
+    offsets: &[usize],
+    replacement: &str,
+    expand: bool,
+) -> Result<(String, usize), String> {
+    let mut offsets = offsets.to_vec();
+    offsets.sort_unstable();
+    offsets.dedup();
+
+    let mut result = String::with_capacity(content.len());
+    let mut copied_to = 0;
+    for &offset in &offsets {
+        if offset < copied_to || offset > content.len() || !content.is_char_boundary(offset) {
+            return Err("the file changed since it was searched".to_string());
+        }
+        let caps = regex
+            .captures_at(content, offset)
+            .filter(|c| c.get(0).map(|m| m.start() == offset && !m.is_empty()).unwrap_or(false))
+            .ok_or("the file changed since it was searched")?;
+        let whole = caps.get(0).expect("group 0 always exists");
+
+        result.push_str(&content[copied_to..offset]);
+        if expand {
+            caps.expand(replacement, &mut result);
+        } else {
+            result.push_str(replacement);
+        }
+        copied_to = whole.end();
+    }
+    result.push_str(&content[copied_to..]);
+    Ok((result, offsets.len()))
+}
+
+fn temp_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".replace-tmp");
+    path.with_file_name(name)
+}
+
+// ─── Commands ─────────────────────────────────────────────────────────────────
+
+/// Search every text file under `root`. Matches stream as `search-results`
+/// events (one per file, tagged with `search_id`); the summary is returned
+/// when the search ends.
+#[tauri::command]
+pub async fn find_in_files(
+    app: AppHandle,
+    jobs: State<'_, SearchJobs>,
+    root: String,
+    options: SearchOptions,
+    search_id: String,
+) -> Result<SearchSummary, FsError> {
+    let root = check_read(&root)?;
+    let cancelled = Arc::new(AtomicBool::new(false));
+    jobs.jobs
+        .lock()
+        .map_err(|e| e.to_string())?
+        .insert(search_id.clone(), cancelled.clone());
+
+    let jobs = jobs.jobs.clone();
+    tokio::task::spawn_blocking(move || {
+        let result = run_search(&app, &search_id, &root, &options, &cancelled);
+        if let Ok(mut running) = jobs.lock() {
+            running.remove(&search_id);
+        }
+        Ok(result?)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[tauri::command]
+pub fn cancel_search(jobs: State<'_, SearchJobs>, search_id: String) -> Result<(), String> {
+    if let Some(cancelled) = jobs.jobs.lock().map_err(|e| e.to_string())?.get(&search_id) {
+        cancelled.store(true, Ordering::Relaxed);
+    }
+    Ok(())
+}
+
+/// Replace the previewed matches. `options` must be the ones used for the
+/// search; with `is_regex`, `$1`/`${name}` in `replacement` refer to groups.
+/// All files are checked and written to temporary files first, so either
+/// every file is updated or none is.
+#[tauri::command]
+pub async fn replace_in_files(
+    options: SearchOptions,
+    replacement: String,
+    targets: Vec<ReplaceTarget>,
+) -> Result<ReplaceResult, FsError> {
+    tokio::task::spawn_blocking(move || {
+        let regex = build_regex(&options)?;
+
+        // Compute every new file before touching the disk
+        let mut pending = Vec::new();
+        for target in targets.iter().filter(|t| !t.offsets.is_empty()) {
+            let path = check_write(&target.path)?;
+            let original = fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read {}: {}", target.path, e))?;
+            let (updated, count) = replace_content(&original, &regex, &target.offsets, &replacement, options.is_regex)
+                .map_err(|e| format!("{}: {}", target.path, e))?;
+            pending.push((path, target.path.clone(), original, updated, count));
+        }
+
+        // Stage to temporary files next to the originals
+        let mut staged: Vec<PathBuf> = Vec::new();
+        let stage_result = pending.iter().try_for_each(|(path, display, _, updated, _)| {
+            let temp = temp_path(path);
+            fs::write(&temp, updated).map_err(|e| format!("Failed to write {}: {}", display, e))?;
+            staged.push(temp.clone());
+            if let Ok(meta) = fs::metadata(path) {
+                let _ = fs::set_permissions(&temp, meta.permissions());
+            }
+            Ok::<(), String>(())
+        });
+        if let Err(e) = stage_result {
+            for temp in &staged {
+                let _ = fs::remove_file(temp);
+            }
+            return Err(e.into());
+        }
+
+        // Swap them in; on failure put back the files already replaced
+        for (i, (path, display, _, _, _)) in pending.iter().enumerate() {
+            if let Err(e) = fs::rename(temp_path(path), path) {
+                for (done_path, _, original, _, _) in &pending[..i] {
+                    let _ = fs::write(done_path, original);
+                }
+                for (rest_path, _, _, _, _) in &pending[i..] {
+                    let _ = fs::remove_file(temp_path(rest_path));
+                }
+                return Err(format!("Failed to replace {}: {}", display, e).into());
+            }
+        }
+
+        let files: Vec<FileReplacement> = pending
+            .into_iter()
+            .map(|(_, display, _, _, count)| FileReplacement { path: display, replacements: count })
+            .collect();
+        let total = files.iter().map(|f| f.replacements).sum();
+        Ok(ReplaceResult { files, total })
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
@@ -0,0 +1,507 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, State};
+use uuid::Uuid;
+
+use crate::disk_space::ensure_space;
+use crate::folder_sync::{
+    build_plan, execute_plan, scan, snapshot_state, Filters, SyncActionKind, SyncFailure, SyncMode, SyncOptions,
+    SyncPlan, SyncedFile,
+};
+use crate::path_policy::{check_read, check_write};
+use crate::sync_db::SyncDb;
+
+/// Event emitted for progress updates and state changes of a sync job.
+const PROGRESS_EVENT: &str = "sync-progress";
+
+/// Minimum time between progress events while files are being copied.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+// ─── Public data structures ───────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncProfile {
+    pub id: String,
+    pub name: String,
+    pub left_path: String,
+    pub right_path: String,
+    pub mode: SyncMode,
+    pub include_globs: Vec<String>,
+    pub exclude_globs: Vec<String>,
+    pub propagate_deletions: bool,
+    pub compare_hash: bool,
+    pub last_run_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SyncProfileInput {
+    pub name: String,
+    pub left_path: String,
+    pub right_path: String,
+    pub mode: SyncMode,
+    #[serde(default)]
+    pub include_globs: Vec<String>,
+    #[serde(default)]
+    pub exclude_globs: Vec<String>,
+    #[serde(default)]
+    pub propagate_deletions: bool,
+    #[serde(default)]
+    pub compare_hash: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncProgress {
+    pub job_id: String,
+    pub profile_id: String,
+    /// `planning`, `running`, `completed`, `failed` or `cancelled`
+    pub status: String,
+    pub processed: usize,
+    pub total: usize,
+    pub bytes_done: u64,
+    pub bytes_total: u64,
+    pub current_path: Option<String>,
+    /// Conflicts left for the user to resolve; they are never overwritten
+    pub conflicts: usize,
+    /// Actions that failed, reported once the job finishes
+    pub failures: Vec<SyncFailure>,
+    pub error: Option<String>,
+}
+
+struct RunningSync {
+    profile_id: String,
+    cancelled: Arc<AtomicBool>,
+}
+
+/// Running sync jobs by id, so they can be cancelled.
+pub struct SyncJobs {
+    jobs: Arc<Mutex<HashMap<String, RunningSync>>>,
+}
+
+impl Default for SyncJobs {
+    fn default() -> Self {
+        Self {
+            jobs: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+// ─── Private helpers ──────────────────────────────────────────────────────────
+
+fn new_id() -> String {
+    Uuid::new_v4().to_string()
+}
+
+fn now_iso() -> String {
+    chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string()
+}
+
+const PROFILE_COLUMNS: &str = "id, name, left_path, right_path, mode, include_globs, exclude_globs,
+     propagate_deletions, compare_hash, last_run_at, created_at, updated_at";
+
+fn row_to_profile(row: &rusqlite::Row) -> rusqlite::Result<SyncProfile> {
+    let globs = |idx: usize| -> rusqlite::Result<Vec<String>> {
+        let raw: String = row.get(idx)?;
+        Ok(serde_json::from_str(&raw).unwrap_or_default())
+    };
+    Ok(SyncProfile {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        left_path: row.get(2)?,
+        right_path: row.get(3)?,
+        mode: SyncMode::parse(&row.get::<_, String>(4)?),
+        include_globs: globs(5)?,
+        exclude_globs: globs(6)?,
+        propagate_deletions: row.get::<_, i32>(7)? != 0,
+        compare_hash: row.get::<_, i32>(8)? != 0,
+        last_run_at: row.get(9)?,
+        created_at: row.get::<_, Option<String>>(10)?.unwrap_or_default(),
+        updated_at: row.get::<_, Option<String>>(11)?.unwrap_or_default(),
+    })
+}
+
+fn fetch_profile(conn: &rusqlite::Connection, id: &str) -> Result<SyncProfile, String> {
+    conn.query_row(
+        &format!("SELECT {} FROM sync_profiles WHERE id=?1", PROFILE_COLUMNS),
+        params![id],
+        row_to_profile,
+    )
+    .map_err(|e| match e {
+        rusqlite::Error::QueryReturnedNoRows => format!("Sync profile not found: {}", id),
+        other => other.to_string(),
+    })
+}
+
+fn load_state(conn: &rusqlite::Connection, profile_id: &str) -> Result<HashMap<String, SyncedFile>, String> {
+    let mut stmt = conn
+        .prepare("SELECT rel_path, size, left_mtime, right_mtime FROM sync_state WHERE profile_id=?1")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![profile_id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                SyncedFile {
+                    size: row.get::<_, i64>(1)? as u64,
+                    left_mtime: row.get(2)?,
+                    right_mtime: row.get(3)?,
+                },
+            ))
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<HashMap<_, _>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(rows)
+}
+
+fn save_state(conn: &rusqlite::Connection, profile_id: &str, entries: &[(String, SyncedFile)]) -> Result<(), String> {
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    tx.execute("DELETE FROM sync_state WHERE profile_id=?1", params![profile_id])
+        .map_err(|e| e.to_string())?;
+    {
+        let mut stmt = tx
+            .prepare(
+                "INSERT INTO sync_state (profile_id, rel_path, size, left_mtime, right_mtime)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+            )
+            .map_err(|e| e.to_string())?;
+        for (rel, file) in entries {
+            stmt.execute(params![profile_id, rel, file.size as i64, file.left_mtime, file.right_mtime])
+                .map_err(|e| e.to_string())?;
+        }
+    }
+    tx.execute(
+        "UPDATE sync_profiles SET last_run_at=?1 WHERE id=?2",
+        params![now_iso(), profile_id],
+    )
+    .map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| e.to_string())
+}
+
+fn validate_input(input: &SyncProfileInput) -> Result<(), String> {
+    if input.name.trim().is_empty() {
+        return Err("Profile name is required".to_string());
+    }
+    let (left, right) = (Path::new(&input.left_path), Path::new(&input.right_path));
+    if !left.is_absolute() || !right.is_absolute() {
+        return Err("Both folders must be absolute paths".to_string());
+    }
+    if left.starts_with(right) || right.starts_with(left) {
+        return Err("Sync folders must not contain each other".to_string());
+    }
+    Filters::new(&input.include_globs, &input.exclude_globs).map(|_| ())
+}
+
+/// Resolve both roots through the path policy. The right folder may not exist
+/// yet (e.g. a fresh mirror target); it is created when the job runs.
+fn resolve_roots(profile: &SyncProfile) -> Result<(PathBuf, PathBuf), String> {
+    let left = check_read(&profile.left_path)?;
+    if !left.is_dir() {
+        return Err(format!("Not a folder: {}", profile.left_path));
+    }
+    if profile.mode == SyncMode::TwoWay {
+        check_write(&profile.left_path)?;
+    }
+    let right = check_write(&profile.right_path)?;
+    if right.exists() && !right.is_dir() {
+        return Err(format!("Not a folder: {}", profile.right_path));
+    }
+    if left.starts_with(&right) || right.starts_with(&left) {
+        return Err("Sync folders must not contain each other".to_string());
+    }
+    Ok((left, right))
+}
+
+fn make_plan(
+    profile: &SyncProfile,
+    previous: &HashMap<String, SyncedFile>,
+) -> Result<(PathBuf, PathBuf, Filters, SyncPlan), String> {
+    let (left, right) = resolve_roots(profile)?;
+    let filters = Filters::new(&profile.include_globs, &profile.exclude_globs)?;
+    let left_tree = scan(&left, &filters)?;
+    let right_tree = scan(&right, &filters)?;
+    let options = SyncOptions {
+        mode: profile.mode,
+        propagate_deletions: profile.propagate_deletions,
+        compare_hash: profile.compare_hash,
+        left_root: &left,
+        right_root: &right,
+    };
+    let plan = build_plan(&left_tree, &right_tree, previous, &options);
+    Ok((left, right, filters, plan))
+}
+
+fn run_sync(
+    app: &AppHandle,
+    job_id: &str,
+    profile: &SyncProfile,
+    previous: HashMap<String, SyncedFile>,
+    confirm_deletions: bool,
+    cancelled: &AtomicBool,
+) -> Result<(), String> {
+    let mut progress = SyncProgress {
+        job_id: job_id.to_string(),
+        profile_id: profile.id.clone(),
+        status: "planning".to_string(),
+        processed: 0,
+        total: 0,
+        bytes_done: 0,
+        bytes_total: 0,
+        current_path: None,
+        conflicts: 0,
+        failures: Vec::new(),
+        error: None,
+    };
+    let _ = app.emit(PROGRESS_EVENT, &progress);
+
+    let result = (|| {
+        let (left, right, filters, plan) = make_plan(profile, &previous)?;
+        if let (Some(warning), false) = (&plan.deletion_warning, confirm_deletions) {
+            return Err(format!("{}. Review the plan and confirm to run anyway.", warning));
+        }
+        if plan.bytes_to_right > 0 {
+            ensure_space(plan.bytes_to_right, &right)?;
+        }
+        if plan.bytes_to_left > 0 {
+            ensure_space(plan.bytes_to_left, &left)?;
+        }
+        std::fs::create_dir_all(&right).map_err(|e| format!("Failed to create {}: {}", right.display(), e))?;
+
+        progress.status = "running".to_string();
+        progress.total = plan.actions.len();
+        progress.bytes_total = plan.bytes_to_left + plan.bytes_to_right;
+        progress.conflicts = plan.conflicts;
+
+        let mut last_emit: Option<Instant> = None;
+        let failures = execute_plan(&plan, &left, &right, cancelled, |index, action| {
+            progress.processed = index;
+            progress.current_path = Some(action.path.clone());
+            if last_emit.map(|t| t.elapsed() >= PROGRESS_INTERVAL).unwrap_or(true) {
+                let _ = app.emit(PROGRESS_EVENT, &progress);
+                last_emit = Some(Instant::now());
+            }
+            progress.bytes_done += action.size;
+        });
+
+        if cancelled.load(Ordering::Relaxed) {
+            return Ok(failures);
+        }
+
+        // Remember what both sides look like now. Failed paths and conflicts
+        // keep their old state so the next run plans them again; a conflict
+        // must never be recorded as resolved.
+        let unresolved: HashSet<&str> = failures
+            .iter()
+            .map(|f| f.path.as_str())
+            .chain(
+                plan.actions
+                    .iter()
+                    .filter(|a| a.kind == SyncActionKind::Conflict)
+                    .map(|a| a.path.as_str()),
+            )
+            .collect();
+        let mut state: Vec<(String, SyncedFile)> =
+            snapshot_state(&scan(&left, &filters)?, &scan(&right, &filters)?, &left, &right)
+                .into_iter()
+                .filter(|(rel, _)| !unresolved.contains(rel.as_str()))
+                .collect();
+        state.extend(
+            previous
+                .iter()
+                .filter(|(rel, _)| unresolved.contains(rel.as_str()))
+                .map(|(rel, file)| (rel.clone(), *file)),
+        );
+
+        let db = app.state::<SyncDb>();
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        save_state(&conn, &profile.id, &state)?;
+        Ok(failures)
+    })();
+
+    progress.current_path = None;
+    match result {
+        Ok(failures) => {
+            progress.processed = progress.total;
+            progress.status =
+                if cancelled.load(Ordering::Relaxed) { "cancelled" } else { "completed" }.to_string();
+            progress.failures = failures;
+            let _ = app.emit(PROGRESS_EVENT, &progress);
+            Ok(())
+        }
+        Err(e) => {
+            progress.status = "failed".to_string();
+            progress.error = Some(e.clone());
+            let _ = app.emit(PROGRESS_EVENT, &progress);
+            Err(e)
+        }
+    }
+}
+
+// ─── Profiles ─────────────────────────────────────────────────────────────────
+
+#[tauri::command]
+pub fn list_sync_profiles(state: State<'_, SyncDb>) -> Result<Vec<SyncProfile>, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare(&format!("SELECT {} FROM sync_profiles ORDER BY name COLLATE NOCASE", PROFILE_COLUMNS))
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map([], row_to_profile)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows)
+}
+
+#[tauri::command]
+pub fn create_sync_profile(state: State<'_, SyncDb>, profile: SyncProfileInput) -> Result<SyncProfile, String> {
+    validate_input(&profile)?;
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let id = new_id();
+    let now = now_iso();
+
+    conn.execute(
+        "INSERT INTO sync_profiles (id, name, left_path, right_path, mode, include_globs, exclude_globs,
+                                    propagate_deletions, compare_hash, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?10)",
+        params![
+            id,
+            profile.name.trim(),
+            profile.left_path,
+            profile.right_path,
+            profile.mode.as_str(),
+            serde_json::to_string(&profile.include_globs).map_err(|e| e.to_string())?,
+            serde_json::to_string(&profile.exclude_globs).map_err(|e| e.to_string())?,
+            profile.propagate_deletions as i32,
+            profile.compare_hash as i32,
+            now,
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+
+    fetch_profile(&conn, &id)
+}
+
+/// Replace a profile's settings. Changing either folder or the mode forgets
+/// the recorded state, so the next two-way run treats differences as new.
+#[tauri::command]
+pub fn update_sync_profile(
+    state: State<'_, SyncDb>,
+    id: String,
+    profile: SyncProfileInput,
+) -> Result<SyncProfile, String> {
+    validate_input(&profile)?;
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let existing = fetch_profile(&conn, &id)?;
+
+    conn.execute(
+        "UPDATE sync_profiles
+         SET name=?1, left_path=?2, right_path=?3, mode=?4, include_globs=?5, exclude_globs=?6,
+             propagate_deletions=?7, compare_hash=?8, updated_at=?9
+         WHERE id=?10",
+        params![
+            profile.name.trim(),
+            profile.left_path,
+            profile.right_path,
+            profile.mode.as_str(),
+            serde_json::to_string(&profile.include_globs).map_err(|e| e.to_string())?,
+            serde_json::to_string(&profile.exclude_globs).map_err(|e| e.to_string())?,
+            profile.propagate_deletions as i32,
+            profile.compare_hash as i32,
+            now_iso(),
+            id,
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+
+    if existing.left_path != profile.left_path
+        || existing.right_path != profile.right_path
+        || existing.mode != profile.mode
+    {
+        conn.execute("DELETE FROM sync_state WHERE profile_id=?1", params![id])
+            .map_err(|e| e.to_string())?;
+    }
+
+    fetch_profile(&conn, &id)
+}
+
+#[tauri::command]
+pub fn delete_sync_profile(state: State<'_, SyncDb>, id: String) -> Result<(), String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM sync_profiles WHERE id=?1", params![id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// ─── Jobs ─────────────────────────────────────────────────────────────────────
+
+/// Dry run: what `start_sync` would do right now, without touching any file.
+#[tauri::command]
+pub async fn plan_sync(state: State<'_, SyncDb>, profile_id: String) -> Result<SyncPlan, String> {
+    let (profile, previous) = {
+        let conn = state.0.lock().map_err(|e| e.to_string())?;
+        (fetch_profile(&conn, &profile_id)?, load_state(&conn, &profile_id)?)
+    };
+
+    tokio::task::spawn_blocking(move || make_plan(&profile, &previous).map(|(_, _, _, plan)| plan))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Run a profile in the background and return the job id. Progress arrives
+/// as `sync-progress` events; conflicts are reported but never overwritten.
+/// A plan with a `deletion_warning` fails unless `confirm_deletions` is set,
+/// and a profile can't be started while it is already running.
+#[tauri::command]
+pub fn start_sync(
+    app: AppHandle,
+    state: State<'_, SyncDb>,
+    jobs: State<'_, SyncJobs>,
+    profile_id: String,
+    confirm_deletions: Option<bool>,
+) -> Result<String, String> {
+    let (profile, previous) = {
+        let conn = state.0.lock().map_err(|e| e.to_string())?;
+        (fetch_profile(&conn, &profile_id)?, load_state(&conn, &profile_id)?)
+    };
+
+    let job_id = new_id();
+    let cancelled = Arc::new(AtomicBool::new(false));
+    {
+        let mut running = jobs.jobs.lock().map_err(|e| e.to_string())?;
+        if running.values().any(|job| job.profile_id == profile.id) {
+            return Err(format!("Sync profile '{}' is already running", profile.name));
+        }
+        running.insert(job_id.clone(), RunningSync { profile_id: profile.id.clone(), cancelled: cancelled.clone() });
+    }
+
+    let jobs = jobs.jobs.clone();
+    let thread_job_id = job_id.clone();
+    std::thread::spawn(move || {
+        if let Err(e) = run_sync(&app, &thread_job_id, &profile, previous, confirm_deletions.unwrap_or(false), &cancelled) {
+            log::warn!("sync {} failed: {}", thread_job_id, e);
+        }
+        if let Ok(mut running) = jobs.lock() {
+            running.remove(&thread_job_id);
+        }
+    });
+
+    Ok(job_id)
+}
+
+/// Stop a running sync after the file currently being copied.
+#[tauri::command]
+pub fn cancel_sync(jobs: State<'_, SyncJobs>, job_id: String) -> Result<(), String> {
+    let running = jobs.jobs.lock().map_err(|e| e.to_string())?;
+    let job = running.get(&job_id).ok_or("Sync job not found")?;
+    job.cancelled.store(true, Ordering::Relaxed);
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use rusqlite::{Connection, Result};
+use std::path::Path;
+use std::sync::Mutex;
+
+/// Managed state wrapping the SQLite connection behind a Mutex.
+pub struct SyncDb(pub Mutex<Connection>);
+
+/// Open (or create) the SQLite database at `path` and run all CREATE TABLE
+/// migrations. Returns the ready-to-use connection.
+pub fn initialize(path: &Path) -> Result<Connection> {
+    let conn = Connection::open(path)?;
+
+    conn.execute_batch(
+        "
+        PRAGMA journal_mode = WAL;
+        PRAGMA foreign_keys = ON;
+
+        CREATE TABLE IF NOT EXISTS sync_profiles (
+            id                  TEXT PRIMARY KEY,
+            name                TEXT NOT NULL,
+            left_path           TEXT NOT NULL,
+            right_path          TEXT NOT NULL,
+            mode                TEXT NOT NULL DEFAULT 'mirror',
+            include_globs       TEXT NOT NULL DEFAULT '[]',
+            exclude_globs       TEXT NOT NULL DEFAULT '[]',
+            propagate_deletions INTEGER DEFAULT 0,
+            compare_hash        INTEGER DEFAULT 0,
+            last_run_at         TEXT,
+            created_at          TEXT,
+            updated_at          TEXT
+        );
+
+        -- What each side looked like after the last successful run, so
+        -- two-way sync can tell edits and deletions apart from new files.
+        CREATE TABLE IF NOT EXISTS sync_state (
+            profile_id  TEXT NOT NULL REFERENCES sync_profiles(id) ON DELETE CASCADE,
+            rel_path    TEXT NOT NULL,
+            size        INTEGER NOT NULL,
+            left_mtime  INTEGER NOT NULL,
+            right_mtime INTEGER NOT NULL,
+            PRIMARY KEY (profile_id, rel_path)
+        );
+        ",
+    )?;
+
+    Ok(conn)
+}
@@ -0,0 +1,339 @@
+// src-tauri/src/thumbnails.rs
+// Persistent thumbnail cache with a prioritised worker pool
+
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::cmp::Ordering as CmpOrdering;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+
+use tauri::State;
+use tokio::sync::oneshot;
+
+use crate::ffmpeg::{create_ffmpeg_command, find_ffmpeg};
+use crate::file_settings::{cache_key, enforce_configured_cache_limit, get_cache_dir, touch_cache_entry};
+use crate::path_policy::check_read;
+
+/// Largest edge a thumbnail may be requested at.
+const MAX_THUMBNAIL_SIZE: u32 = 1024;
+
+/// Run `enforce_cache_limit` after this many newly generated thumbnails.
+const ENFORCE_EVERY: usize = 50;
+
+const IMAGE_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "bmp", "webp", "tif", "tiff", "ico", "tga",
+];
+
+const VIDEO_EXTENSIONS: &[&str] = &[
+    "mp4", "mkv", "webm", "avi", "mov", "wmv", "flv", "m4v", "mpg", "mpeg", "3gp", "ogv", "ts",
+];
+
+type Waiter = oneshot::Sender<Result<String, String>>;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+enum Priority {
+    Background = 0,
+    Visible = 1,
+}
+
+struct Job {
+    key: String,
+    path: String,
+    size: u32,
+    priority: Priority,
+    seq: u64,
+}
+
+// Higher priority first, then first-come first-served within a priority.
+impl Ord for Job {
+    fn cmp(&self, other: &Self) -> CmpOrdering {
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+impl PartialOrd for Job {
+    fn partial_cmp(&self, other: &Self) -> Option<CmpOrdering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Job {
+    fn eq(&self, other: &Self) -> bool {
+        self.seq == other.seq
+    }
+}
+
+impl Eq for Job {}
+
+#[derive(Default)]
+struct Queue {
+    jobs: BinaryHeap<Job>,
+    /// Callers waiting on each cache key, queued or in flight.
+    waiters: HashMap<String, Vec<Waiter>>,
+    next_seq: u64,
+}
+
+struct Shared {
+    queue: Mutex<Queue>,
+    available: Condvar,
+    generated_since_enforce: AtomicUsize,
+}
+
+/// Managed state owning the thumbnail worker threads.
+pub struct ThumbnailService {
+    shared: Arc<Shared>,
+}
+
+impl Default for ThumbnailService {
+    fn default() -> Self {
+        let workers = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(2)
+            .clamp(1, 4);
+        Self::new(workers)
+    }
+}
+
+impl ThumbnailService {
+    pub fn new(workers: usize) -> Self {
+        let shared = Arc::new(Shared {
+            queue: Mutex::new(Queue::default()),
+            available: Condvar::new(),
+            generated_since_enforce: AtomicUsize::new(0),
+        });
+
+        for i in 0..workers {
+            let shared = shared.clone();
+            std::thread::Builder::new()
+                .name(format!("thumbnail-worker-{}", i))
+                .spawn(move || worker_loop(shared))
+                .expect("failed to spawn thumbnail worker");
+        }
+
+        Self { shared }
+    }
+
+    fn enqueue(&self, path: String, size: u32, priority: Priority) -> Result<oneshot::Receiver<Result<String, String>>, String> {
+        let key = thumbnail_key(Path::new(&path), size)?;
+        let (tx, rx) = oneshot::channel();
+
+        let mut queue = self.shared.queue.lock().map_err(|e| e.to_string())?;
+        let already_pending = queue.waiters.contains_key(&key);
+        queue.waiters.entry(key.clone()).or_default().push(tx);
+
+        if already_pending {
+            // Only ever raise the priority of an existing job, never lower it.
+            if priority == Priority::Visible {
+                raise_priority(&mut queue, |job| job.key == key);
+            }
+        } else {
+            let seq = queue.next_seq;
+            queue.next_seq += 1;
+            queue.jobs.push(Job { key, path, size, priority, seq });
+            self.shared.available.notify_one();
+        }
+
+        Ok(rx)
+    }
+}
+
+fn raise_priority(queue: &mut Queue, matches: impl Fn(&Job) -> bool) {
+    let mut jobs = std::mem::take(&mut queue.jobs).into_vec();
+    for job in jobs.iter_mut() {
+        if matches(job) {
+            job.priority = Priority::Visible;
+        }
+    }
+    queue.jobs = BinaryHeap::from(jobs);
+}
+
+fn worker_loop(shared: Arc<Shared>) {
+    loop {
+        let job = {
+            let Ok(mut queue) = shared.queue.lock() else { return };
+            loop {
+                if let Some(job) = queue.jobs.pop() {
+                    break job;
+                }
+                queue = match shared.available.wait(queue) {
+                    Ok(q) => q,
+                    Err(_) => return,
+                };
+            }
+        };
+
+        let result = match cache_path_for_key(&job.key) {
+            Ok(cached) if cached.exists() => Ok(cached),
+            _ => generate_thumbnail(Path::new(&job.path), job.size, &job.key)
+                .inspect(|_| after_generate(&shared)),
+        }
+        .map(|p| p.to_string_lossy().to_string());
+
+        let waiters = shared
+            .queue
+            .lock()
+            .ok()
+            .and_then(|mut q| q.waiters.remove(&job.key))
+            .unwrap_or_default();
+        for waiter in waiters {
+            let _ = waiter.send(result.clone());
+        }
+    }
+}
+
+/// Trim the cache every `ENFORCE_EVERY` new thumbnails so it never grows unbounded.
+fn after_generate(shared: &Shared) {
+    let count = shared.generated_since_enforce.fetch_add(1, Ordering::Relaxed) + 1;
+    if count >= ENFORCE_EVERY {
+        shared.generated_since_enforce.store(0, Ordering::Relaxed);
+        enforce_configured_cache_limit(None);
+    }
+}
+
+fn extension_of(path: &Path) -> String {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase()
+}
+
+pub(crate) fn is_image_path(path: &Path) -> bool {
+    IMAGE_EXTENSIONS.contains(&extension_of(path).as_str())
+}
+
+pub(crate) fn is_video_path(path: &Path) -> bool {
+    VIDEO_EXTENSIONS.contains(&extension_of(path).as_str())
+}
+
+/// Thumbnails are keyed by source path, size and mtime, so edited files get a
+/// fresh thumbnail without any explicit invalidation.
+fn thumbnail_key(path: &Path, size: u32) -> Result<String, String> {
+    cache_key(path, ("thumbnail", size))
+}
+
+fn cache_path_for_key(key: &str) -> Result<PathBuf, String> {
+    Ok(get_cache_dir()?.join(format!("{}.png", key)))
+}
+
+fn generate_thumbnail(path: &Path, size: u32, key: &str) -> Result<PathBuf, String> {
+    let output = cache_path_for_key(key)?;
+    // Write next to the final file and rename so readers never see a partial PNG.
+    let partial = output.with_extension("part.png");
+
+    let result = if is_image_path(path) {
+        generate_image_thumbnail(path, size, &partial)
+    } else if is_video_path(path) {
+        generate_video_thumbnail(path, size, &partial)
+    } else {
+        Err("Unsupported file type for thumbnails".to_string())
+    };
+
+    if let Err(e) = result {
+        let _ = fs::remove_file(&partial);
+        return Err(e);
+    }
+
+    fs::rename(&partial, &output).map_err(|e| format!("Failed to store thumbnail: {}", e))?;
+    Ok(output)
+}
+
+fn generate_image_thumbnail(path: &Path, size: u32, output: &Path) -> Result<(), String> {
+    let img = image::ImageReader::open(path)
+        .map_err(|e| format!("Failed to open image: {}", e))?
+        .with_guessed_format()
+        .map_err(|e| format!("Failed to detect image format: {}", e))?
+        .decode()
+        .map_err(|e| format!("Failed to decode image: {}", e))?;
+
+    img.thumbnail(size, size)
+        .save_with_format(output, image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to write thumbnail: {}", e))
+}
+
+fn generate_video_thumbnail(path: &Path, size: u32, output: &Path) -> Result<(), String> {
+    let ffmpeg_path = find_ffmpeg()?;
+
+    let status = create_ffmpeg_command(&ffmpeg_path)
+        .arg("-i")
+        .arg(path)
+        .args([
+            "-vframes", "1",
+            "-vf", &format!("scale={0}:{0}:force_original_aspect_ratio=decrease", size),
+            "-y",
+        ])
+        .arg(output)
+        .stderr(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .status()
+        .map_err(|e| format!("FFmpeg execution failed: {}", e))?;
+
+    if !status.success() {
+        return Err("FFmpeg failed to extract thumbnail".to_string());
+    }
+
+    Ok(())
+}
+
+/// Return the cached thumbnail for `path`, generating it on the calling thread
+/// if needed. Used by callers that bypass the worker queue.
+pub(crate) fn ensure_thumbnail(path: &Path, size: u32) -> Result<PathBuf, String> {
+    let key = thumbnail_key(path, size)?;
+    let cached = cache_path_for_key(&key)?;
+    if cached.exists() {
+        touch_cache_entry(&cached);
+        return Ok(cached);
+    }
+    generate_thumbnail(path, size, &key)
+}
+
+/// Return the path of a cached thumbnail, queueing generation on the worker pool
+/// when it is missing. Visible items jump ahead of background prefetches.
+#[tauri::command]
+pub async fn get_thumbnail(
+    state: State<'_, ThumbnailService>,
+    path: String,
+    size: u32,
+    visible: Option<bool>,
+) -> Result<String, String> {
+    let size = size.clamp(16, MAX_THUMBNAIL_SIZE);
+    // Jobs keep the path as given so `prioritize_thumbnails` can match it
+    check_read(&path)?;
+
+    // Fast path: serve straight from disk without touching the queue.
+    let key = thumbnail_key(Path::new(&path), size)?;
+    let cached = cache_path_for_key(&key)?;
+    if cached.exists() {
+        touch_cache_entry(&cached);
+        return Ok(cached.to_string_lossy().to_string());
+    }
+
+    let priority = if visible.unwrap_or(true) { Priority::Visible } else { Priority::Background };
+    let rx = state.enqueue(path, size, priority)?;
+
+    rx.await
+        .map_err(|_| "Thumbnail request cancelled".to_string())?
+}
+
+/// Bump queued requests for these paths to visible priority, e.g. after scrolling.
+#[tauri::command]
+pub fn prioritize_thumbnails(state: State<'_, ThumbnailService>, paths: Vec<String>) -> Result<(), String> {
+    let wanted: HashSet<String> = paths.into_iter().collect();
+    let mut queue = state.shared.queue.lock().map_err(|e| e.to_string())?;
+    raise_priority(&mut queue, |job| wanted.contains(&job.path));
+    Ok(())
+}
+
+/// Drop every request that has not started yet, e.g. when leaving a directory.
+#[tauri::command]
+pub fn cancel_pending_thumbnails(state: State<'_, ThumbnailService>) -> Result<(), String> {
+    let mut queue = state.shared.queue.lock().map_err(|e| e.to_string())?;
+    let jobs = std::mem::take(&mut queue.jobs);
+    for job in jobs {
+        // Dropping the senders wakes the waiting commands with a cancellation error.
+        queue.waiters.remove(&job.key);
+    }
+    Ok(())
+}
@@ -0,0 +1,573 @@
+// src-tauri/src/transcode.rs
+// Cancellable FFmpeg transcode jobs with progress events
+
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, State};
+
+use crate::ffmpeg::{create_ffmpeg_command, find_ffmpeg};
+use crate::file_settings::{
+    cache_key, enforce_configured_cache_limit, get_transcode_cache_dir, touch_cache_entry,
+};
+use crate::media_info::probe_duration;
+use crate::path_policy::check_read;
+
+/// Event emitted for every progress update and state change of a job.
+const PROGRESS_EVENT: &str = "transcode-progress";
+
+/// Lines of FFmpeg stderr kept to explain a failed transcode.
+const STDERR_TAIL_LINES: usize = 20;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum TranscodeFormat {
+    /// H.264 + AAC in MP4, plays everywhere
+    H264,
+    /// VP9 + Opus in WebM, smaller files, slower to encode
+    Vp9,
+    /// Audio track only as AAC in M4A
+    AudioOnly,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TranscodePreset {
+    pub format: TranscodeFormat,
+    /// Downscale so the output is at most this many pixels tall
+    pub max_height: Option<u32>,
+}
+
+impl Default for TranscodePreset {
+    fn default() -> Self {
+        Self { format: TranscodeFormat::H264, max_height: None }
+    }
+}
+
+impl TranscodePreset {
+    fn extension(&self) -> &'static str {
+        match self.format {
+            TranscodeFormat::H264 => "mp4",
+            TranscodeFormat::Vp9 => "webm",
+            TranscodeFormat::AudioOnly => "m4a",
+        }
+    }
+
+    /// Scale filter keeping dimensions even (required by H.264 4:2:0) and
+    /// converting to a pixel format browsers can decode.
+    fn video_filter(&self) -> String {
+        match self.max_height {
+            Some(h) => format!("scale=-2:trunc(min({}\\,ih)/2)*2,format=yuv420p", h),
+            None => "scale=trunc(iw/2)*2:trunc(ih/2)*2,format=yuv420p".to_string(),
+        }
+    }
+
+    fn ffmpeg_args(&self) -> Vec<String> {
+        let args: Vec<&str> = match self.format {
+            // Key fixes for ProRes:
+            // - pix_fmt yuv420p: Convert from ProRes 4:2:2/4:4:4 to 4:2:0 (browser compatible)
+            // - colorspace flags: Tag output as bt709 so browsers don't guess
+            TranscodeFormat::H264 => vec![
+                "-c:v", "libx264",
+                "-preset", "fast",
+                "-crf", "18",
+                "-color_primaries", "bt709",
+                "-color_trc", "bt709",
+                "-colorspace", "bt709",
+                "-c:a", "aac",
+                "-b:a", "192k",
+                "-movflags", "+faststart",
+            ],
+            TranscodeFormat::Vp9 => vec![
+                "-c:v", "libvpx-vp9",
+                "-crf", "32",
+                "-b:v", "0",
+                "-deadline", "good",
+                "-cpu-used", "4",
+                "-row-mt", "1",
+                "-c:a", "libopus",
+                "-b:a", "128k",
+            ],
+            TranscodeFormat::AudioOnly => vec![
+                "-vn",
+                "-c:a", "aac",
+                "-b:a", "192k",
+                "-movflags", "+faststart",
+            ],
+        };
+
+        let mut args: Vec<String> = args.into_iter().map(String::from).collect();
+        if self.format != TranscodeFormat::AudioOnly {
+            args.push("-vf".to_string());
+            args.push(self.video_filter());
+        }
+        args
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TranscodeStatus {
+    /// Claimed, FFmpeg not started yet
+    Pending,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct TranscodeProgress {
+    job_id: String,
+    source: String,
+    output_path: String,
+    status: TranscodeStatus,
+    /// 0–100, only meaningful when the source duration is known
+    percent: f64,
+    /// Seconds of output written so far
+    out_time: f64,
+    duration: Option<f64>,
+    /// Encoding speed as a multiple of realtime
+    speed: Option<f64>,
+    eta_seconds: Option<f64>,
+    error: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct TranscodeStarted {
+    /// `None` when a cached output was found and no job was needed
+    job_id: Option<String>,
+    output_path: String,
+    cached: bool,
+}
+
+struct TranscodeJob {
+    /// `None` until FFmpeg has been spawned
+    child: Option<Arc<Mutex<Child>>>,
+    cancelled: Arc<AtomicBool>,
+    progress: TranscodeProgress,
+    /// Ids of callers following this job under their own id; progress is
+    /// emitted under each and any of them can cancel it
+    aliases: Vec<String>,
+}
+
+type OutputClaims = Arc<Mutex<HashMap<PathBuf, String>>>;
+
+pub struct TranscodeState {
+    jobs: Arc<Mutex<HashMap<String, TranscodeJob>>>,
+    /// Cache outputs being written, with the id of the job writing each
+    outputs: OutputClaims,
+}
+
+impl Default for TranscodeState {
+    fn default() -> Self {
+        Self {
+            jobs: Arc::new(Mutex::new(HashMap::new())),
+            outputs: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+/// An output reserved for one job; released when dropped, however the job ends.
+struct OutputClaim {
+    outputs: OutputClaims,
+    path: PathBuf,
+}
+
+impl Drop for OutputClaim {
+    fn drop(&mut self) {
+        if let Ok(mut outputs) = self.outputs.lock() {
+            outputs.remove(&self.path);
+        }
+    }
+}
+
+enum Claim {
+    New(OutputClaim),
+    /// Another job is already writing the output
+    Running(String),
+}
+
+/// Reserve `output` for `job_id` so the same source and preset are never
+/// transcoded twice at once.
+fn claim_output(outputs: &OutputClaims, output: &Path, job_id: &str) -> Result<Claim, String> {
+    let mut claimed = outputs.lock().map_err(|e| e.to_string())?;
+    if let Some(existing) = claimed.get(output) {
+        return Ok(Claim::Running(existing.clone()));
+    }
+    claimed.insert(output.to_path_buf(), job_id.to_string());
+    Ok(Claim::New(OutputClaim { outputs: outputs.clone(), path: output.to_path_buf() }))
+}
+
+fn output_path_for(source: &Path, preset: &TranscodePreset) -> Result<PathBuf, String> {
+    let key = cache_key(source, ("transcode", preset))?;
+    Ok(get_transcode_cache_dir()?.join(format!("{}.{}", key, preset.extension())))
+}
+
+fn partial_path_for(output_path: &Path, preset: &TranscodePreset) -> PathBuf {
+    output_path.with_extension(format!("part.{}", preset.extension()))
+}
+
+/// Parse `speed=1.53x` from the progress stream.
+fn parse_speed(value: &str) -> Option<f64> {
+    value.trim().trim_end_matches('x').parse().ok().filter(|s: &f64| *s > 0.0)
+}
+
+fn emit_progress(app: &AppHandle, jobs: &Mutex<HashMap<String, TranscodeJob>>, progress: &TranscodeProgress) {
+    let mut aliases = Vec::new();
+    if let Ok(mut jobs) = jobs.lock() {
+        if let Some(job) = jobs.get_mut(&progress.job_id) {
+            job.progress = progress.clone();
+            aliases = job.aliases.clone();
+        }
+    }
+    let _ = app.emit(PROGRESS_EVENT, progress.clone());
+    for alias in aliases {
+        let _ = app.emit(PROGRESS_EVENT, TranscodeProgress { job_id: alias, ..progress.clone() });
+    }
+}
+
+/// Add a job as pending as soon as its output is claimed, so it can be
+/// listed and cancelled before FFmpeg starts. Returns its cancel flag.
+fn register_job(
+    app: &AppHandle,
+    jobs: &Mutex<HashMap<String, TranscodeJob>>,
+    job_id: &str,
+    source: &str,
+    output_path: &Path,
+) -> Result<Arc<AtomicBool>, String> {
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let progress = TranscodeProgress {
+        job_id: job_id.to_string(),
+        source: source.to_string(),
+        output_path: output_path.to_string_lossy().to_string(),
+        status: TranscodeStatus::Pending,
+        percent: 0.0,
+        out_time: 0.0,
+        duration: None,
+        speed: None,
+        eta_seconds: None,
+        error: None,
+    };
+    jobs.lock().map_err(|e| e.to_string())?.insert(
+        job_id.to_string(),
+        TranscodeJob { child: None, cancelled: cancelled.clone(), progress: progress.clone(), aliases: Vec::new() },
+    );
+    emit_progress(app, jobs, &progress);
+    Ok(cancelled)
+}
+
+/// Follow the running job `job_id` under `alias` as well.
+fn alias_job(jobs: &Mutex<HashMap<String, TranscodeJob>>, job_id: &str, alias: &str) -> Result<(), String> {
+    let mut jobs = jobs.lock().map_err(|e| e.to_string())?;
+    if let Some(job) = jobs.get_mut(job_id) {
+        job.aliases.push(alias.to_string());
+    }
+    Ok(())
+}
+
+/// Run a registered transcode to completion on the current thread, emitting
+/// progress events as FFmpeg reports them, and remove the job when it ends.
+/// Returns the finished output path.
+fn run_transcode(
+    app: &AppHandle,
+    jobs: &Arc<Mutex<HashMap<String, TranscodeJob>>>,
+    job_id: &str,
+    source: &str,
+    output_path: &Path,
+    preset: TranscodePreset,
+    cancelled: &AtomicBool,
+) -> Result<String, String> {
+    let mut progress = TranscodeProgress {
+        job_id: job_id.to_string(),
+        source: source.to_string(),
+        output_path: output_path.to_string_lossy().to_string(),
+        status: TranscodeStatus::Running,
+        percent: 0.0,
+        out_time: 0.0,
+        duration: None,
+        speed: None,
+        eta_seconds: None,
+        error: None,
+    };
+    let result = transcode(app, jobs, Path::new(source), output_path, preset, cancelled, &mut progress);
+
+    match &result {
+        Ok(()) => {
+            progress.status = TranscodeStatus::Completed;
+            progress.percent = 100.0;
+            progress.eta_seconds = Some(0.0);
+            enforce_configured_cache_limit(Some(output_path));
+        }
+        Err(e) => {
+            // Never leave a half-written file behind in the cache.
+            let _ = fs::remove_file(partial_path_for(output_path, &preset));
+            if cancelled.load(Ordering::Relaxed) {
+                progress.status = TranscodeStatus::Cancelled;
+            } else {
+                progress.status = TranscodeStatus::Failed;
+                progress.error = Some(e.clone());
+            }
+        }
+    }
+
+    emit_progress(app, jobs, &progress);
+    if let Ok(mut jobs) = jobs.lock() {
+        jobs.remove(job_id);
+    }
+
+    result.map(|_| output_path.to_string_lossy().to_string())
+}
+
+/// Spawn FFmpeg for a registered job and wait for it, keeping `progress`
+/// up to date.
+fn transcode(
+    app: &AppHandle,
+    jobs: &Mutex<HashMap<String, TranscodeJob>>,
+    source_path: &Path,
+    output_path: &Path,
+    preset: TranscodePreset,
+    cancelled: &AtomicBool,
+    progress: &mut TranscodeProgress,
+) -> Result<(), String> {
+    let partial_path = &partial_path_for(output_path, &preset);
+    let ffmpeg_path = find_ffmpeg()?;
+    let duration = probe_duration(source_path);
+    progress.duration = duration;
+    if cancelled.load(Ordering::Relaxed) {
+        return Err("Transcode cancelled".to_string());
+    }
+
+    let mut child = create_ffmpeg_command(&ffmpeg_path)
+        .args(["-nostats", "-progress", "pipe:1", "-i"])
+        .arg(source_path)
+        .args(preset.ffmpeg_args())
+        .arg("-y")
+        .arg(partial_path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("FFmpeg execution failed: {}", e))?;
+
+    let stdout = child.stdout.take().ok_or("Failed to capture FFmpeg output")?;
+    let stderr = child.stderr.take().ok_or("Failed to capture FFmpeg output")?;
+
+    let child = Arc::new(Mutex::new(child));
+    {
+        // Under the jobs lock, so a cancel either sees the child or has
+        // already set the flag checked here
+        let mut jobs = jobs.lock().map_err(|e| e.to_string())?;
+        if let Some(job) = jobs.get_mut(&progress.job_id) {
+            job.child = Some(child.clone());
+        }
+        if cancelled.load(Ordering::Relaxed) {
+            let _ = child.lock().map_err(|e| e.to_string())?.kill();
+        }
+    }
+    emit_progress(app, jobs, progress);
+
+    // Drain stderr on its own thread so FFmpeg never blocks on a full pipe.
+    let stderr_thread = std::thread::spawn(move || {
+        let mut tail = VecDeque::with_capacity(STDERR_TAIL_LINES);
+        for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+            if tail.len() == STDERR_TAIL_LINES {
+                tail.pop_front();
+            }
+            tail.push_back(line);
+        }
+        tail.into_iter().collect::<Vec<_>>().join("\n")
+    });
+
+    let started = Instant::now();
+    for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+        let Some((key, value)) = line.split_once('=') else { continue };
+        match key {
+            // Despite the name, out_time_ms is also in microseconds.
+            "out_time_us" | "out_time_ms" => {
+                if let Ok(us) = value.trim().parse::<i64>() {
+                    progress.out_time = us.max(0) as f64 / 1_000_000.0;
+                }
+            }
+            "speed" => progress.speed = parse_speed(value),
+            // Each block of key=value pairs ends with a `progress=` line.
+            "progress" => {
+                if let Some(total) = duration.filter(|d| *d > 0.0) {
+                    progress.percent = (progress.out_time / total * 100.0).clamp(0.0, 100.0);
+                    let remaining = (total - progress.out_time).max(0.0);
+                    progress.eta_seconds = match progress.speed {
+                        Some(speed) => Some(remaining / speed),
+                        None if progress.out_time > 0.0 => {
+                            let elapsed = started.elapsed().as_secs_f64();
+                            Some(elapsed * remaining / progress.out_time)
+                        }
+                        None => None,
+                    };
+                }
+                emit_progress(app, jobs, progress);
+            }
+            _ => {}
+        }
+    }
+
+    // Poll rather than block in wait() so cancel_transcode can still lock the child.
+    let status = loop {
+        match child.lock().map_err(|e| e.to_string())?.try_wait() {
+            Ok(Some(status)) => break Ok(status),
+            Ok(None) => {}
+            Err(e) => break Err(e),
+        }
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    };
+    let stderr_tail = stderr_thread.join().unwrap_or_default();
+
+    if cancelled.load(Ordering::Relaxed) {
+        return Err("Transcode cancelled".to_string());
+    }
+    match status {
+        Ok(s) if s.success() => fs::rename(partial_path, output_path)
+            .map_err(|e| format!("Failed to store transcoded video: {}", e)),
+        Ok(_) => Err(format!("FFmpeg transcoding failed: {}", stderr_tail)),
+        Err(e) => Err(format!("FFmpeg execution failed: {}", e)),
+    }
+}
+
+/// Start a transcode in the background. Progress arrives as `transcode-progress`
+/// events carrying the returned job id. If the same source is already being
+/// transcoded with the same preset, that job's id is returned instead.
+#[tauri::command]
+pub async fn start_transcode(
+    app: AppHandle,
+    state: State<'_, TranscodeState>,
+    path: String,
+    preset: TranscodePreset,
+) -> Result<TranscodeStarted, String> {
+    check_read(&path)?;
+    let output_path = output_path_for(Path::new(&path), &preset)?;
+    if output_path.exists() {
+        touch_cache_entry(&output_path);
+        return Ok(TranscodeStarted {
+            job_id: None,
+            output_path: output_path.to_string_lossy().to_string(),
+            cached: true,
+        });
+    }
+
+    let job_id = uuid::Uuid::new_v4().to_string();
+    let claim = match claim_output(&state.outputs, &output_path, &job_id)? {
+        Claim::New(claim) => claim,
+        Claim::Running(existing) => {
+            return Ok(TranscodeStarted {
+                job_id: Some(existing),
+                output_path: output_path.to_string_lossy().to_string(),
+                cached: false,
+            });
+        }
+    };
+
+    let jobs = state.jobs.clone();
+    let cancelled = register_job(&app, &jobs, &job_id, &path, &output_path)?;
+    let thread_job_id = job_id.clone();
+    let thread_output = output_path.clone();
+    std::thread::spawn(move || {
+        if let Err(e) = run_transcode(&app, &jobs, &thread_job_id, &path, &thread_output, preset, &cancelled) {
+            log::warn!("transcode {} ended: {}", thread_job_id, e);
+        }
+        drop(claim);
+    });
+
+    Ok(TranscodeStarted {
+        job_id: Some(job_id),
+        output_path: output_path.to_string_lossy().to_string(),
+        cached: false,
+    })
+}
+
+/// Stop a pending or running transcode, by its id or an alias; its partial
+/// output is deleted.
+#[tauri::command]
+pub fn cancel_transcode(state: State<'_, TranscodeState>, job_id: String) -> Result<(), String> {
+    let jobs = state.jobs.lock().map_err(|e| e.to_string())?;
+    let job = jobs
+        .get(&job_id)
+        .or_else(|| jobs.values().find(|job| job.aliases.contains(&job_id)))
+        .ok_or("Transcode job not found")?;
+
+    job.cancelled.store(true, Ordering::Relaxed);
+    match &job.child {
+        Some(child) => child
+            .lock()
+            .map_err(|e| e.to_string())?
+            .kill()
+            .map_err(|e| format!("Failed to stop FFmpeg: {}", e)),
+        // The flag stops it before FFmpeg is spawned
+        None => Ok(()),
+    }
+}
+
+#[tauri::command]
+pub fn list_transcode_jobs(state: State<'_, TranscodeState>) -> Result<Vec<TranscodeProgress>, String> {
+    let jobs = state.jobs.lock().map_err(|e| e.to_string())?;
+    Ok(jobs.values().map(|job| job.progress.clone()).collect())
+}
+
+/// Return a path the webview can play, transcoding to H.264 when needed.
+///
+/// Runs as a regular transcode job, so callers can pass `job_id` to follow
+/// `transcode-progress` events and cancel it with `cancel_transcode`. When a
+/// job is already writing the same output, this waits for that job instead,
+/// and `job_id` becomes an alias for it: its events are repeated under
+/// `job_id`, and cancelling `job_id` cancels it.
+#[tauri::command]
+pub async fn get_playable_video(
+    app: AppHandle,
+    state: State<'_, TranscodeState>,
+    path: String,
+    job_id: Option<String>,
+) -> Result<String, String> {
+    check_read(&path)?;
+    let path_lower = path.to_lowercase();
+
+    // Common web-compatible formats - return as-is
+    if path_lower.ends_with(".mp4")
+        || path_lower.ends_with(".webm")
+        || path_lower.ends_with(".ogg") {
+        return Ok(path);
+    }
+
+    let preset = TranscodePreset::default();
+    let output_path = output_path_for(Path::new(&path), &preset)?;
+    if output_path.exists() {
+        touch_cache_entry(&output_path);
+        return Ok(output_path.to_string_lossy().to_string());
+    }
+
+    let jobs = state.jobs.clone();
+    let outputs = state.outputs.clone();
+    let job_id = job_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    tokio::task::spawn_blocking(move || match claim_output(&outputs, &output_path, &job_id)? {
+        Claim::New(_claim) => {
+            let cancelled = register_job(&app, &jobs, &job_id, &path, &output_path)?;
+            run_transcode(&app, &jobs, &job_id, &path, &output_path, preset, &cancelled)
+        }
+        Claim::Running(existing) => {
+            alias_job(&jobs, &existing, &job_id)?;
+            while outputs.lock().map_err(|e| e.to_string())?.contains_key(&output_path) {
+                std::thread::sleep(std::time::Duration::from_millis(200));
+            }
+            if output_path.exists() {
+                Ok(output_path.to_string_lossy().to_string())
+            } else {
+                Err("Transcode failed or was cancelled".to_string())
+            }
+        }
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
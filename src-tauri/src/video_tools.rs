@@ -0,0 +1,315 @@
+// src-tauri/src/video_tools.rs
+// Producing new files from videos: trimming, frame export, sprites and GIFs
+
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::ffmpeg::{create_ffmpeg_command, find_ffmpeg};
+use crate::file_settings::{cache_key, enforce_configured_cache_limit, get_preview_cache_dir, touch_cache_entry};
+use crate::files::get_unique_path;
+use crate::media_info::probe_duration;
+use crate::path_policy::{check_read, check_write};
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ContactSheet {
+    path: String,
+    columns: u32,
+    rows: u32,
+    frame_count: u32,
+    tile_width: u32,
+    tile_height: u32,
+    /// Seconds of video between consecutive tiles
+    interval: f64,
+}
+
+/// Run FFmpeg with the given arguments, returning the tail of stderr on failure.
+fn run_ffmpeg(args: Vec<OsString>) -> Result<(), String> {
+    let ffmpeg_path = find_ffmpeg()?;
+
+    let output = create_ffmpeg_command(&ffmpeg_path)
+        .args(&args)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::piped())
+        .output()
+        .map_err(|e| format!("FFmpeg execution failed: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let tail: Vec<&str> = stderr.lines().rev().take(5).collect();
+        let message: Vec<&str> = tail.into_iter().rev().collect();
+        return Err(format!("FFmpeg failed: {}", message.join("\n")));
+    }
+
+    Ok(())
+}
+
+/// Run FFmpeg into a sibling `*.part.<ext>` file and move it over `output`
+/// only on success, so a failed run never truncates or removes an existing
+/// file. `args` are everything but the output path.
+fn render_to(output: &Path, mut args: Vec<OsString>) -> Result<(), String> {
+    let extension = output.extension().and_then(|e| e.to_str()).unwrap_or_default();
+    let partial = output.with_extension(format!("part.{}", extension));
+    args.extend(["-y".into(), partial.clone().into()]);
+
+    let result = run_ffmpeg(args)
+        .and_then(|()| fs::rename(&partial, output).map_err(|e| format!("Failed to write {}: {}", output.display(), e)));
+    if result.is_err() {
+        let _ = fs::remove_file(&partial);
+    }
+    result
+}
+
+fn seconds_arg(seconds: f64) -> OsString {
+    format!("{:.3}", seconds.max(0.0)).into()
+}
+
+/// Output path next to the source, e.g. `clip_trim_12.5-20.mp4`, made unique.
+fn default_output(source: &Path, suffix: &str, extension: &str) -> PathBuf {
+    let stem = source.file_stem().and_then(|s| s.to_str()).unwrap_or("video");
+    let parent = source.parent().unwrap_or(Path::new("."));
+    get_unique_path(parent.join(format!("{}_{}.{}", stem, suffix, extension)))
+}
+
+/// Destination for a new file; an explicit `output_path` must be in an
+/// existing folder.
+fn resolve_output(source: &Path, output_path: Option<String>, suffix: &str, extension: &str) -> Result<PathBuf, String> {
+    let output = match output_path {
+        Some(p) => check_write(&p)?,
+        None => check_write(&default_output(source, suffix, extension).to_string_lossy())?,
+    };
+    if output == source {
+        return Err("Output path must differ from the source file".to_string());
+    }
+    Ok(output)
+}
+
+fn validate_range(start: f64, end: f64) -> Result<(), String> {
+    if !start.is_finite() || !end.is_finite() || start < 0.0 || end <= start {
+        return Err("Invalid time range".to_string());
+    }
+    Ok(())
+}
+
+/// Encoder arguments for re-encoding into a container, or `None` when the
+/// container isn't one we encode to.
+fn reencode_args(extension: &str) -> Option<Vec<OsString>> {
+    let args: &[&str] = match extension.to_ascii_lowercase().as_str() {
+        "mp4" | "m4v" | "mov" | "mkv" => &[
+            "-c:v", "libx264", "-preset", "fast", "-crf", "18", "-pix_fmt", "yuv420p",
+            "-c:a", "aac", "-b:a", "192k",
+        ],
+        "webm" => &[
+            "-c:v", "libvpx-vp9", "-crf", "32", "-b:v", "0", "-row-mt", "1",
+            "-c:a", "libopus", "-b:a", "128k",
+        ],
+        _ => return None,
+    };
+    Some(args.iter().map(OsString::from).collect())
+}
+
+/// Cut the clip between `start` and `end` seconds.
+///
+/// Streams are copied without re-encoding unless `precise` is set; a copy cut
+/// snaps to the nearest keyframe. If the copy fails (e.g. a codec the target
+/// container can't hold) it falls back to re-encoding with codecs that suit
+/// the container; a default output in a container we don't encode to becomes
+/// an `.mp4` next to the source.
+#[tauri::command]
+pub async fn trim_video(
+    path: String,
+    start: f64,
+    end: f64,
+    output_path: Option<String>,
+    precise: Option<bool>,
+) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || {
+        validate_range(start, end)?;
+        let source = &check_read(&path)?;
+        let extension = source.extension().and_then(|e| e.to_str()).unwrap_or("mp4").to_string();
+        let suffix = format!("trim_{}-{}", start, end);
+        let explicit_output = output_path.is_some();
+        let output = resolve_output(source, output_path, &suffix, &extension)?;
+
+        // Video (without cover art) and audio only; subtitle and data streams
+        // often can't go into the target container. Both are optional.
+        let input_args: Vec<OsString> = vec![
+            "-ss".into(), seconds_arg(start),
+            "-to".into(), seconds_arg(end),
+            "-i".into(), source.into(),
+            "-map".into(), "0:V?".into(),
+            "-map".into(), "0:a?".into(),
+        ];
+
+        let copy_result = if precise.unwrap_or(false) {
+            Err("re-encode requested".to_string())
+        } else {
+            let mut args = input_args.clone();
+            args.extend(["-c".into(), "copy".into(), "-avoid_negative_ts".into(), "make_zero".into()]);
+            render_to(&output, args)
+        };
+
+        if copy_result.is_ok() {
+            return Ok(output.to_string_lossy().to_string());
+        }
+
+        let output_extension = output.extension().and_then(|e| e.to_str()).unwrap_or_default().to_string();
+        let (output, codec_args) = match reencode_args(&output_extension) {
+            Some(codec_args) => (output, codec_args),
+            None if !explicit_output => (resolve_output(source, None, &suffix, "mp4")?, reencode_args("mp4").unwrap_or_default()),
+            None => return Err(format!("Cannot re-encode to .{}; use .mp4, .mkv, .mov or .webm", output_extension)),
+        };
+
+        let mut args = input_args;
+        args.extend(codec_args);
+        render_to(&output, args)?;
+
+        Ok(output.to_string_lossy().to_string())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Save the frame at `timestamp` seconds as a full-resolution PNG.
+#[tauri::command]
+pub async fn export_frame(path: String, timestamp: f64, output_path: Option<String>) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || {
+        let source = &check_read(&path)?;
+        let suffix = format!("frame_{:.2}", timestamp.max(0.0));
+        let output = resolve_output(source, output_path, &suffix, "png")?;
+
+        render_to(&output, vec![
+            // Seeking before -i is fast and frame-accurate when decoding
+            "-ss".into(), seconds_arg(timestamp),
+            "-i".into(), source.into(),
+            "-frames:v".into(), "1".into(),
+        ])?;
+
+        Ok(output.to_string_lossy().to_string())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Build a sprite of `count` evenly spaced frames laid out in a grid, used for
+/// scrubbing previews. Sprites are cached alongside the hover previews.
+#[tauri::command]
+pub async fn generate_contact_sheet(
+    path: String,
+    count: u32,
+    columns: Option<u32>,
+    tile_width: Option<u32>,
+) -> Result<ContactSheet, String> {
+    tokio::task::spawn_blocking(move || {
+        let source = &check_read(&path)?;
+        let count = count.clamp(1, 400);
+        let columns = columns.unwrap_or(10).clamp(1, count);
+        let rows = count.div_ceil(columns);
+        let tile_width = tile_width.unwrap_or(160).clamp(16, 1920);
+
+        let duration = probe_duration(source)
+            .filter(|d| *d > 0.0)
+            .ok_or("Could not determine video duration")?;
+        let interval = duration / count as f64;
+
+        let key = cache_key(source, ("sprite", count, columns, tile_width))?;
+        let output = get_preview_cache_dir()?.join(format!("{}.jpg", key));
+
+        if output.exists() {
+            touch_cache_entry(&output);
+        } else {
+            let partial = output.with_extension("part.jpg");
+            // Sample one frame per interval, offset by half an interval so the
+            // first tile isn't the (often black) very first frame.
+            let filter = format!(
+                "fps=1/{interval:.6},scale={tile_width}:-2,tile={columns}x{rows}",
+            );
+            let result = run_ffmpeg(vec![
+                "-ss".into(), seconds_arg(interval / 2.0),
+                "-i".into(), source.into(),
+                "-vf".into(), filter.into(),
+                "-frames:v".into(), "1".into(),
+                "-q:v".into(), "4".into(),
+                "-y".into(), partial.clone().into(),
+            ]);
+            if let Err(e) = result {
+                let _ = fs::remove_file(&partial);
+                return Err(e);
+            }
+            fs::rename(&partial, &output).map_err(|e| format!("Failed to store contact sheet: {}", e))?;
+            enforce_configured_cache_limit(Some(&output));
+        }
+
+        let (_, sheet_height) = image::image_dimensions(&output)
+            .map_err(|e| format!("Failed to read contact sheet: {}", e))?;
+
+        Ok(ContactSheet {
+            path: output.to_string_lossy().to_string(),
+            columns,
+            rows,
+            frame_count: count,
+            tile_width,
+            tile_height: sheet_height / rows,
+            interval,
+        })
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Convert a segment to an animated GIF (two-pass palette) or animated WebP.
+#[tauri::command]
+pub async fn create_animation(
+    path: String,
+    start: f64,
+    end: f64,
+    width: Option<u32>,
+    fps: Option<u32>,
+    format: String,
+    output_path: Option<String>,
+) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || {
+        validate_range(start, end)?;
+        let source = &check_read(&path)?;
+        let width = width.unwrap_or(480).clamp(16, 1920);
+        let fps = fps.unwrap_or(15).clamp(1, 50);
+        let scale = format!("fps={},scale={}:-1:flags=lanczos", fps, width);
+
+        let (extension, codec_args): (&str, Vec<OsString>) = match format.as_str() {
+            // A palette generated from the clip itself keeps GIF colours faithful;
+            // diff_mode only re-dithers the parts of each frame that changed.
+            "gif" => ("gif", vec![
+                "-vf".into(),
+                format!("{},split[s0][s1];[s0]palettegen=stats_mode=diff[p];[s1][p]paletteuse=dither=bayer:bayer_scale=5:diff_mode=rectangle", scale).into(),
+            ]),
+            "webp" => ("webp", vec![
+                "-vf".into(), scale.into(),
+                "-c:v".into(), "libwebp".into(),
+                "-lossless".into(), "0".into(),
+                "-q:v".into(), "70".into(),
+                "-compression_level".into(), "6".into(),
+            ]),
+            other => return Err(format!("Unsupported animation format: {}", other)),
+        };
+
+        let suffix = format!("{}-{}", start, end);
+        let output = resolve_output(source, output_path, &suffix, extension)?;
+
+        let mut args: Vec<OsString> = vec![
+            "-ss".into(), seconds_arg(start),
+            "-to".into(), seconds_arg(end),
+            "-i".into(), source.into(),
+            "-an".into(),
+        ];
+        args.extend(codec_args);
+        args.extend(["-loop".into(), "0".into()]);
+        render_to(&output, args)?;
+
+        Ok(output.to_string_lossy().to_string())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
@@ -0,0 +1,310 @@
+// src-tauri/src/volumes.rs
+// Mounted volume enumeration and mount/unmount notifications on Linux
+
+use std::collections::HashMap;
+use std::fs;
+use std::os::unix::io::AsRawFd;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter};
+
+use crate::disk_space::query_space;
+use crate::files::DriveInfo;
+
+const MOUNTINFO_PATH: &str = "/proc/self/mountinfo";
+
+/// Kernel and runtime filesystems that never hold user files.
+const PSEUDO_FILESYSTEMS: &[&str] = &[
+    "autofs", "binfmt_misc", "bpf", "cgroup", "cgroup2", "configfs", "debugfs",
+    "devpts", "devtmpfs", "efivarfs", "fusectl", "hugetlbfs", "mqueue", "nsfs",
+    "overlay", "proc", "pstore", "ramfs", "rpc_pipefs", "securityfs", "squashfs",
+    "sysfs", "tmpfs", "tracefs", "fuse.gvfsd-fuse", "fuse.portal", "fuse.lxcfs",
+];
+
+const NETWORK_FILESYSTEMS: &[&str] = &[
+    "nfs", "nfs4", "cifs", "smb3", "smbfs", "9p", "afs", "ceph", "glusterfs",
+    "davfs", "fuse.sshfs", "sshfs", "fuse.rclone", "fuse.s3fs",
+];
+
+/// Mount point prefixes reserved for the system. `/run/media` is where udisks
+/// mounts removable drives, so it is let through explicitly.
+const HIDDEN_MOUNT_PREFIXES: &[&str] = &["/proc", "/sys", "/dev", "/run", "/snap", "/boot", "/var/lib"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct MountEntry {
+    mount_point: String,
+    source: String,
+    fs_type: String,
+}
+
+/// Decode the octal escapes (`\040` for space etc.) used in mountinfo fields.
+fn unescape_octal(field: &str) -> String {
+    let bytes = field.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'\\' && i + 4 <= bytes.len() && bytes[i + 1..i + 4].iter().all(|b| (b'0'..=b'7').contains(b)) {
+            let value = (bytes[i + 1] - b'0') as u32 * 64 + (bytes[i + 2] - b'0') as u32 * 8 + (bytes[i + 3] - b'0') as u32;
+            if let Ok(value) = u8::try_from(value) {
+                out.push(value);
+                i += 4;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+/// Decode the `\xHH` escapes udev uses in `/dev/disk/by-label` names.
+fn unescape_hex(name: &str) -> String {
+    let bytes = name.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'\\' && i + 4 <= bytes.len() && bytes[i + 1] == b'x' {
+            let digits = std::str::from_utf8(&bytes[i + 2..i + 4]).unwrap_or("");
+            if let Ok(value) = u8::from_str_radix(digits, 16) {
+                out.push(value);
+                i += 4;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+/// Parse mountinfo lines of the form
+/// `36 35 98:0 /mnt1 /mnt/parent rw,noatime master:1 - ext3 /dev/root rw`.
+fn parse_mountinfo(contents: &str) -> Vec<MountEntry> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let (left, right) = line.split_once(" - ")?;
+            let mount_point = left.split(' ').nth(4)?;
+            let mut right = right.split(' ');
+            let fs_type = right.next()?;
+            let source = right.next().unwrap_or("");
+            Some(MountEntry {
+                mount_point: unescape_octal(mount_point),
+                source: unescape_octal(source),
+                fs_type: fs_type.to_string(),
+            })
+        })
+        .collect()
+}
+
+fn read_mounts() -> Result<Vec<MountEntry>, String> {
+    let contents = fs::read_to_string(MOUNTINFO_PATH)
+        .map_err(|e| format!("Failed to read mount table: {}", e))?;
+    Ok(parse_mountinfo(&contents))
+}
+
+/// Filesystem type of the innermost mount containing `path`.
+pub(crate) fn filesystem_type_for(path: &Path) -> Option<String> {
+    let path = fs::canonicalize(path).ok()?;
+    read_mounts()
+        .ok()?
+        .into_iter()
+        .filter(|m| path.starts_with(&m.mount_point))
+        // Later entries shadow earlier mounts on the same point
+        .max_by_key(|m| m.mount_point.len())
+        .map(|m| m.fs_type)
+}
+
+fn is_user_visible(entry: &MountEntry) -> bool {
+    if PSEUDO_FILESYSTEMS.contains(&entry.fs_type.as_str()) {
+        return false;
+    }
+    let point = entry.mount_point.as_str();
+    if point == "/" || point.starts_with("/run/media/") {
+        return true;
+    }
+    !HIDDEN_MOUNT_PREFIXES
+        .iter()
+        .any(|prefix| point == *prefix || point.starts_with(&format!("{}/", prefix)))
+}
+
+/// Map resolved device nodes (e.g. `/dev/sdb1`) to their filesystem labels.
+fn device_labels() -> HashMap<PathBuf, String> {
+    let mut labels = HashMap::new();
+    if let Ok(entries) = fs::read_dir("/dev/disk/by-label") {
+        for entry in entries.flatten() {
+            if let Ok(device) = fs::canonicalize(entry.path()) {
+                labels.insert(device, unescape_hex(&entry.file_name().to_string_lossy()));
+            }
+        }
+    }
+    labels
+}
+
+/// Sysfs directory of the whole disk backing a block device, so partitions
+/// like `sdb1` resolve to `sdb`.
+fn sysfs_disk(device: &Path) -> Option<PathBuf> {
+    let name = device.file_name()?;
+    let node = fs::canonicalize(Path::new("/sys/class/block").join(name)).ok()?;
+    if node.join("partition").exists() {
+        node.parent().map(Path::to_path_buf)
+    } else {
+        Some(node)
+    }
+}
+
+fn classify(entry: &MountEntry, device: Option<&Path>) -> String {
+    if NETWORK_FILESYSTEMS.contains(&entry.fs_type.as_str()) {
+        return "network".to_string();
+    }
+    if entry.fs_type == "iso9660" || entry.fs_type == "udf" {
+        return "cdrom".to_string();
+    }
+    if let Some(disk) = device.and_then(sysfs_disk) {
+        let removable = fs::read_to_string(disk.join("removable"))
+            .map(|s| s.trim() == "1")
+            .unwrap_or(false);
+        // USB hard drives often report removable=0, so check the bus as well
+        if removable || disk.to_string_lossy().contains("/usb") {
+            return "removable".to_string();
+        }
+    }
+    if entry.mount_point.starts_with("/media/") || entry.mount_point.starts_with("/run/media/") {
+        return "removable".to_string();
+    }
+    "fixed".to_string()
+}
+
+fn to_drive_info(entry: &MountEntry, labels: &HashMap<PathBuf, String>) -> DriveInfo {
+    let device = if entry.source.starts_with("/dev/") {
+        fs::canonicalize(&entry.source).ok()
+    } else {
+        None
+    };
+
+    let label = device
+        .as_ref()
+        .and_then(|d| labels.get(d).cloned())
+        .or_else(|| {
+            if entry.mount_point == "/" {
+                Some("Root".to_string())
+            } else {
+                Path::new(&entry.mount_point)
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+            }
+        });
+
+    let space = query_space(Path::new(&entry.mount_point)).ok();
+
+    DriveInfo {
+        letter: entry.mount_point.clone(),
+        path: entry.mount_point.clone(),
+        label,
+        drive_type: classify(entry, device.as_deref()),
+        filesystem: Some(entry.fs_type.clone()),
+        device: Some(entry.source.clone()).filter(|s| !s.is_empty() && s != "none"),
+        total_space: space.map(|(total, _, _)| total),
+        free_space: space.map(|(_, _, available)| available),
+    }
+}
+
+fn visible_mounts() -> Result<Vec<MountEntry>, String> {
+    let mut mounts: Vec<MountEntry> = Vec::new();
+    for entry in read_mounts()?.into_iter().filter(is_user_visible) {
+        // A later mount over the same point shadows the earlier one
+        mounts.retain(|m| m.mount_point != entry.mount_point);
+        mounts.push(entry);
+    }
+    mounts.sort_by(|a, b| {
+        (a.mount_point != "/", &a.mount_point).cmp(&(b.mount_point != "/", &b.mount_point))
+    });
+    Ok(mounts)
+}
+
+/// Mount points of the user-visible volumes, root first.
+pub(crate) fn mount_points() -> Vec<PathBuf> {
+    visible_mounts()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|m| PathBuf::from(m.mount_point))
+        .collect()
+}
+
+/// User-visible mounted volumes, root first.
+pub(crate) fn list_volumes() -> Result<Vec<DriveInfo>, String> {
+    let labels = device_labels();
+    Ok(visible_mounts()?
+        .iter()
+        .map(|entry| to_drive_info(entry, &labels))
+        .collect())
+}
+
+/// Block until the kernel signals a mount table change. The mountinfo file
+/// raises POLLPRI whenever a mount is added or removed in our namespace.
+fn wait_for_mount_change(file: &fs::File) -> bool {
+    let mut fds = libc::pollfd {
+        fd: file.as_raw_fd(),
+        events: libc::POLLPRI,
+        revents: 0,
+    };
+    let result = unsafe { libc::poll(&mut fds, 1, -1) };
+    result > 0 && fds.revents & (libc::POLLPRI | libc::POLLERR) != 0
+}
+
+/// Watch the mount table and emit `volume-mounted` / `volume-unmounted` with
+/// the affected volume, followed by `volumes-changed` with the full list.
+pub fn start_mount_watcher(app: AppHandle) {
+    thread::spawn(move || {
+        let file = match fs::File::open(MOUNTINFO_PATH) {
+            Ok(f) => f,
+            Err(e) => {
+                log::warn!("Mount watcher disabled: {}", e);
+                return;
+            }
+        };
+        let mut known = visible_mounts().unwrap_or_default();
+
+        loop {
+            if !wait_for_mount_change(&file) {
+                thread::sleep(Duration::from_secs(2));
+            }
+            // udisks may mount and then immediately remount with final options
+            thread::sleep(Duration::from_millis(200));
+
+            let current = match visible_mounts() {
+                Ok(m) => m,
+                Err(e) => {
+                    log::warn!("Mount watcher: {}", e);
+                    continue;
+                }
+            };
+            if current == known {
+                continue;
+            }
+
+            let labels = device_labels();
+            for entry in current.iter().filter(|m| !known.contains(m)) {
+                let _ = app.emit("volume-mounted", to_drive_info(entry, &labels));
+            }
+            for entry in known.iter().filter(|m| !current.contains(m)) {
+                // The mount is gone, so there is no space to report
+                let _ = app.emit("volume-unmounted", DriveInfo {
+                    letter: entry.mount_point.clone(),
+                    path: entry.mount_point.clone(),
+                    label: Path::new(&entry.mount_point).file_name().map(|n| n.to_string_lossy().to_string()),
+                    drive_type: classify(entry, None),
+                    filesystem: Some(entry.fs_type.clone()),
+                    device: Some(entry.source.clone()),
+                    total_space: None,
+                    free_space: None,
+                });
+            }
+            let volumes: Vec<DriveInfo> = current.iter().map(|m| to_drive_info(m, &labels)).collect();
+            let _ = app.emit("volumes-changed", volumes);
+            known = current;
+        }
+    });
+}
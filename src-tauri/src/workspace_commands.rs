@@ -0,0 +1,526 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, State};
+use uuid::Uuid;
+
+use crate::file_index::{FileIndex, FileMatch};
+use crate::path_policy::check_read;
+use crate::workspace_db::WorkspaceDb;
+
+/// Event emitted when a workspace's file index is built or changes.
+const INDEX_EVENT: &str = "workspace-index";
+
+/// Filesystem events are applied at once but announced at most this often.
+const INDEX_EVENT_DEBOUNCE: Duration = Duration::from_millis(500);
+
+const DEFAULT_FIND_LIMIT: usize = 50;
+
+// ─── Public data structures ───────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Workspace {
+    pub id: String,
+    pub name: String,
+    pub roots: Vec<String>,
+    pub created_at: String,
+    pub updated_at: String,
+    pub last_opened_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WorkspaceInput {
+    pub name: String,
+    pub roots: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceTab {
+    pub path: String,
+    /// 1-based
+    pub cursor_line: i64,
+    /// 1-based
+    pub cursor_column: i64,
+    pub scroll_top: f64,
+    pub is_active: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkspaceSession {
+    pub workspace: Workspace,
+    pub tabs: Vec<WorkspaceTab>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexStatus {
+    pub workspace_id: String,
+    /// The initial walk is still running
+    pub indexing: bool,
+    pub file_count: usize,
+    /// The workspace has more files than the index holds
+    pub truncated: bool,
+}
+
+struct OpenWorkspace {
+    /// `None` until the initial walk finishes
+    index: Arc<Mutex<Option<FileIndex>>>,
+    _watchers: Vec<RecommendedWatcher>,
+}
+
+/// File indexes of the open workspaces by id.
+pub struct WorkspaceIndexes {
+    open: Arc<Mutex<HashMap<String, OpenWorkspace>>>,
+}
+
+impl Default for WorkspaceIndexes {
+    fn default() -> Self {
+        Self {
+            open: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+// ─── Private helpers ──────────────────────────────────────────────────────────
+
+fn new_id() -> String {
+    Uuid::new_v4().to_string()
+}
+
+fn now_iso() -> String {
+    chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string()
+}
+
+fn row_to_workspace(row: &rusqlite::Row) -> rusqlite::Result<Workspace> {
+    Ok(Workspace {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        roots: Vec::new(),
+        created_at: row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+        updated_at: row.get::<_, Option<String>>(3)?.unwrap_or_default(),
+        last_opened_at: row.get(4)?,
+    })
+}
+
+fn load_roots(conn: &rusqlite::Connection, workspace_id: &str) -> Result<Vec<String>, String> {
+    let mut stmt = conn
+        .prepare("SELECT path FROM workspace_roots WHERE workspace_id=?1 ORDER BY sort_order ASC")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![workspace_id], |row| row.get(0))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<String>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(rows)
+}
+
+fn fetch_workspace(conn: &rusqlite::Connection, id: &str) -> Result<Workspace, String> {
+    let mut workspace = conn
+        .query_row(
+            "SELECT id, name, created_at, updated_at, last_opened_at FROM workspaces WHERE id=?1",
+            params![id],
+            row_to_workspace,
+        )
+        .map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => format!("Workspace not found: {}", id),
+            other => other.to_string(),
+        })?;
+    workspace.roots = load_roots(conn, id)?;
+    Ok(workspace)
+}
+
+fn save_roots(conn: &rusqlite::Connection, workspace_id: &str, roots: &[String]) -> Result<(), String> {
+    conn.execute("DELETE FROM workspace_roots WHERE workspace_id=?1", params![workspace_id])
+        .map_err(|e| e.to_string())?;
+    for (i, root) in roots.iter().enumerate() {
+        conn.execute(
+            "INSERT OR IGNORE INTO workspace_roots (workspace_id, path, sort_order) VALUES (?1, ?2, ?3)",
+            params![workspace_id, root, i as i32],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+fn validate_input(input: &WorkspaceInput) -> Result<(), String> {
+    if input.name.trim().is_empty() {
+        return Err("Workspace name is required".to_string());
+    }
+    if input.roots.is_empty() {
+        return Err("A workspace needs at least one folder".to_string());
+    }
+    for root in &input.roots {
+        if !check_read(root)?.is_dir() {
+            return Err(format!("Not a folder: {}", root));
+        }
+    }
+    Ok(())
+}
+
+fn emit_status(app: &AppHandle, workspace_id: &str, index: &Option<FileIndex>) {
+    let _ = app.emit(
+        INDEX_EVENT,
+        IndexStatus {
+            workspace_id: workspace_id.to_string(),
+            indexing: index.is_none(),
+            file_count: index.as_ref().map(|i| i.len()).unwrap_or(0),
+            truncated: index.as_ref().map(|i| i.truncated).unwrap_or(false),
+        },
+    );
+}
+
+fn start_watcher(
+    app: &AppHandle,
+    workspace_id: &str,
+    index: &Arc<Mutex<Option<FileIndex>>>,
+    queued: &Arc<Mutex<HashSet<PathBuf>>>,
+    root: &Path,
+) -> Option<RecommendedWatcher> {
+    let app = app.clone();
+    let workspace_id = workspace_id.to_string();
+    let index = index.clone();
+    let queued = queued.clone();
+    let pending = Arc::new(AtomicBool::new(false));
+
+    let handler = move |event: notify::Result<notify::Event>| {
+        let Ok(event) = event else { return };
+        {
+            let Ok(mut guard) = index.lock() else { return };
+            let Some(files) = guard.as_mut() else {
+                // Still walking: replay these once the index is built
+                if let Ok(mut queued) = queued.lock() {
+                    queued.extend(event.paths);
+                }
+                return;
+            };
+            let mut changed = false;
+            for path in &event.paths {
+                changed |= files.refresh_path(path);
+            }
+            if !changed {
+                return;
+            }
+        }
+        if !pending.swap(true, Ordering::AcqRel) {
+            let app = app.clone();
+            let pending = pending.clone();
+            let index = index.clone();
+            let workspace_id = workspace_id.clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(INDEX_EVENT_DEBOUNCE);
+                pending.store(false, Ordering::Release);
+                if let Ok(guard) = index.lock() {
+                    emit_status(&app, &workspace_id, &guard);
+                }
+            });
+        }
+    };
+
+    let mut watcher = match notify::recommended_watcher(handler) {
+        Ok(w) => w,
+        Err(e) => {
+            log::warn!("workspace: failed to create watcher: {}", e);
+            return None;
+        }
+    };
+    if let Err(e) = watcher.watch(root, RecursiveMode::Recursive) {
+        log::warn!("workspace: cannot watch {}: {}", root.display(), e);
+        return None;
+    }
+    Some(watcher)
+}
+
+/// (Re)build the index of an open workspace in the background. Watcher
+/// events that arrive during the initial walk are queued and applied to the
+/// built index.
+fn start_indexing(app: &AppHandle, indexes: &WorkspaceIndexes, workspace: &Workspace) -> Result<(), String> {
+    let roots: Vec<PathBuf> = workspace
+        .roots
+        .iter()
+        .filter_map(|root| check_read(root).ok())
+        .filter(|root| root.is_dir())
+        .collect();
+
+    let index = Arc::new(Mutex::new(None));
+    let queued = Arc::new(Mutex::new(HashSet::new()));
+    let watchers = roots
+        .iter()
+        .filter_map(|root| start_watcher(app, &workspace.id, &index, &queued, root))
+        .collect();
+    let replaced = indexes.open.lock().map_err(|e| e.to_string())?.insert(
+        workspace.id.clone(),
+        OpenWorkspace {
+            index: index.clone(),
+            _watchers: watchers,
+        },
+    );
+    // Stopping the old watchers can block on the OS, so drop them outside the lock
+    drop(replaced);
+    emit_status(app, &workspace.id, &None);
+
+    let app = app.clone();
+    let workspace_id = workspace.id.clone();
+    std::thread::spawn(move || {
+        let mut built = FileIndex::build(&roots);
+        log::info!("workspace {}: indexed {} files", workspace_id, built.len());
+        // Holding the index lock keeps watchers from queueing behind the replay
+        if let Ok(mut guard) = index.lock() {
+            if let Ok(mut queued) = queued.lock() {
+                for path in queued.drain() {
+                    built.refresh_path(&path);
+                }
+            }
+            *guard = Some(built);
+            emit_status(&app, &workspace_id, &guard);
+        }
+    });
+    Ok(())
+}
+
+// ─── Workspaces ───────────────────────────────────────────────────────────────
+
+#[tauri::command]
+pub fn list_workspaces(state: State<'_, WorkspaceDb>) -> Result<Vec<Workspace>, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, name, created_at, updated_at, last_opened_at
+             FROM   workspaces
+             ORDER  BY last_opened_at IS NULL, last_opened_at DESC, name COLLATE NOCASE",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let mut rows = stmt
+        .query_map([], row_to_workspace)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    for workspace in &mut rows {
+        workspace.roots = load_roots(&conn, &workspace.id)?;
+    }
+
+    Ok(rows)
+}
+
+#[tauri::command]
+pub fn create_workspace(state: State<'_, WorkspaceDb>, workspace: WorkspaceInput) -> Result<Workspace, String> {
+    validate_input(&workspace)?;
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let id = new_id();
+    let now = now_iso();
+
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    tx.execute(
+        "INSERT INTO workspaces (id, name, created_at, updated_at) VALUES (?1, ?2, ?3, ?3)",
+        params![id, workspace.name.trim(), now],
+    )
+    .map_err(|e| e.to_string())?;
+    save_roots(&tx, &id, &workspace.roots)?;
+    tx.commit().map_err(|e| e.to_string())?;
+
+    fetch_workspace(&conn, &id)
+}
+
+/// Rename a workspace or change its folders. An open workspace is reindexed
+/// when its folders change.
+#[tauri::command]
+pub fn update_workspace(
+    app: AppHandle,
+    state: State<'_, WorkspaceDb>,
+    indexes: State<'_, WorkspaceIndexes>,
+    id: String,
+    workspace: WorkspaceInput,
+) -> Result<Workspace, String> {
+    validate_input(&workspace)?;
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let existing = fetch_workspace(&conn, &id)?;
+
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    tx.execute(
+        "UPDATE workspaces SET name=?1, updated_at=?2 WHERE id=?3",
+        params![workspace.name.trim(), now_iso(), id],
+    )
+    .map_err(|e| e.to_string())?;
+    save_roots(&tx, &id, &workspace.roots)?;
+    tx.commit().map_err(|e| e.to_string())?;
+
+    let updated = fetch_workspace(&conn, &id)?;
+    let is_open = indexes.open.lock().map_err(|e| e.to_string())?.contains_key(&id);
+    if is_open && existing.roots != updated.roots {
+        start_indexing(&app, &indexes, &updated)?;
+    }
+    Ok(updated)
+}
+
+#[tauri::command]
+pub fn delete_workspace(
+    state: State<'_, WorkspaceDb>,
+    indexes: State<'_, WorkspaceIndexes>,
+    id: String,
+) -> Result<(), String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM workspaces WHERE id=?1", params![id])
+        .map_err(|e| e.to_string())?;
+    let closed = indexes.open.lock().map_err(|e| e.to_string())?.remove(&id);
+    drop(closed);
+    Ok(())
+}
+
+/// Open a workspace: start indexing its folders (progress arrives as
+/// `workspace-index` events) and return it with its saved tabs.
+#[tauri::command]
+pub fn open_workspace(
+    app: AppHandle,
+    state: State<'_, WorkspaceDb>,
+    indexes: State<'_, WorkspaceIndexes>,
+    id: String,
+) -> Result<WorkspaceSession, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    conn.execute(
+        "UPDATE workspaces SET last_opened_at=?1 WHERE id=?2",
+        params![now_iso(), id],
+    )
+    .map_err(|e| e.to_string())?;
+    let workspace = fetch_workspace(&conn, &id)?;
+    let tabs = load_tabs(&conn, &id)?;
+    drop(conn);
+
+    let is_open = indexes.open.lock().map_err(|e| e.to_string())?.contains_key(&id);
+    if !is_open {
+        start_indexing(&app, &indexes, &workspace)?;
+    }
+    Ok(WorkspaceSession { workspace, tabs })
+}
+
+/// Drop a workspace's index and stop watching its folders.
+#[tauri::command]
+pub fn close_workspace(indexes: State<'_, WorkspaceIndexes>, id: String) -> Result<(), String> {
+    // Stopping watchers can block on the OS, so drop them outside the lock
+    let closed = indexes.open.lock().map_err(|e| e.to_string())?.remove(&id);
+    drop(closed);
+    Ok(())
+}
+
+// ─── File index ───────────────────────────────────────────────────────────────
+
+#[tauri::command]
+pub fn get_workspace_index_status(
+    indexes: State<'_, WorkspaceIndexes>,
+    id: String,
+) -> Result<IndexStatus, String> {
+    let index = indexes
+        .open
+        .lock()
+        .map_err(|e| e.to_string())?
+        .get(&id)
+        .map(|open| open.index.clone())
+        .ok_or_else(|| format!("Workspace is not open: {}", id))?;
+    let guard = index.lock().map_err(|e| e.to_string())?;
+    Ok(IndexStatus {
+        workspace_id: id,
+        indexing: guard.is_none(),
+        file_count: guard.as_ref().map(|i| i.len()).unwrap_or(0),
+        truncated: guard.as_ref().map(|i| i.truncated).unwrap_or(false),
+    })
+}
+
+/// Fuzzy file lookup across an open workspace's folders. Returns nothing
+/// while the index is still being built.
+#[tauri::command]
+pub async fn find_workspace_files(
+    indexes: State<'_, WorkspaceIndexes>,
+    id: String,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<FileMatch>, String> {
+    let index = indexes
+        .open
+        .lock()
+        .map_err(|e| e.to_string())?
+        .get(&id)
+        .map(|open| open.index.clone())
+        .ok_or_else(|| format!("Workspace is not open: {}", id))?;
+
+    tokio::task::spawn_blocking(move || {
+        let guard = index.lock().map_err(|e| e.to_string())?;
+        Ok(guard
+            .as_ref()
+            .map(|files| files.search(&query, limit.unwrap_or(DEFAULT_FIND_LIMIT)))
+            .unwrap_or_default())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+// ─── Tabs ─────────────────────────────────────────────────────────────────────
+
+fn load_tabs(conn: &rusqlite::Connection, workspace_id: &str) -> Result<Vec<WorkspaceTab>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT path, cursor_line, cursor_column, scroll_top, is_active
+             FROM   workspace_tabs
+             WHERE  workspace_id=?1
+             ORDER  BY sort_order ASC",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![workspace_id], |row| {
+            Ok(WorkspaceTab {
+                path: row.get(0)?,
+                cursor_line: row.get(1)?,
+                cursor_column: row.get(2)?,
+                scroll_top: row.get(3)?,
+                is_active: row.get::<_, i32>(4)? != 0,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(rows)
+}
+
+#[tauri::command]
+pub fn get_workspace_tabs(state: State<'_, WorkspaceDb>, id: String) -> Result<Vec<WorkspaceTab>, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    load_tabs(&conn, &id)
+}
+
+/// Replace the saved tabs of a workspace, in display order.
+#[tauri::command]
+pub fn save_workspace_tabs(
+    state: State<'_, WorkspaceDb>,
+    id: String,
+    tabs: Vec<WorkspaceTab>,
+) -> Result<(), String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    tx.execute("DELETE FROM workspace_tabs WHERE workspace_id=?1", params![id])
+        .map_err(|e| e.to_string())?;
+    {
+        let mut stmt = tx
+            .prepare(
+                "INSERT OR REPLACE INTO workspace_tabs
+                     (workspace_id, path, sort_order, cursor_line, cursor_column, scroll_top, is_active)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            )
+            .map_err(|e| e.to_string())?;
+        for (i, tab) in tabs.iter().enumerate() {
+            stmt.execute(params![
+                id,
+                tab.path,
+                i as i32,
+                tab.cursor_line.max(1),
+                tab.cursor_column.max(1),
+                tab.scroll_top,
+                tab.is_active as i32
+            ])
+            .map_err(|e| e.to_string())?;
+        }
+    }
+    tx.commit().map_err(|e| e.to_string())
+}
@@ -0,0 +1,47 @@
+use rusqlite::{Connection, Result};
+use std::path::Path;
+use std::sync::Mutex;
+
+/// Managed state wrapping the SQLite connection behind a Mutex.
+pub struct WorkspaceDb(pub Mutex<Connection>);
+
+/// Open (or create) the SQLite database at `path` and run all CREATE TABLE
+/// migrations. Returns the ready-to-use connection.
+pub fn initialize(path: &Path) -> Result<Connection> {
+    let conn = Connection::open(path)?;
+
+    conn.execute_batch(
+        "
+        PRAGMA journal_mode = WAL;
+        PRAGMA foreign_keys = ON;
+
+        CREATE TABLE IF NOT EXISTS workspaces (
+            id             TEXT PRIMARY KEY,
+            name           TEXT NOT NULL,
+            created_at     TEXT,
+            updated_at     TEXT,
+            last_opened_at TEXT
+        );
+
+        CREATE TABLE IF NOT EXISTS workspace_roots (
+            workspace_id TEXT NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+            path         TEXT NOT NULL,
+            sort_order   INTEGER DEFAULT 0,
+            PRIMARY KEY (workspace_id, path)
+        );
+
+        CREATE TABLE IF NOT EXISTS workspace_tabs (
+            workspace_id  TEXT NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+            path          TEXT NOT NULL,
+            sort_order    INTEGER DEFAULT 0,
+            cursor_line   INTEGER DEFAULT 1,
+            cursor_column INTEGER DEFAULT 1,
+            scroll_top    REAL DEFAULT 0,
+            is_active     INTEGER DEFAULT 0,
+            PRIMARY KEY (workspace_id, path)
+        );
+        ",
+    )?;
+
+    Ok(conn)
+}
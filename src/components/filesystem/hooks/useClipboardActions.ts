@@ -4,6 +4,7 @@ import { clipboard } from '$lib/stores/clipboardStore';
 import { directoryCache } from '$lib/stores/directoryCacheStore';
 import { selectedFiles } from './useFileSelection';
 import { joinPath } from './fileUtils';
+import { errorMessage } from '$lib/utils/errors';
 
 export function copySelectedFiles(currentPath: string | undefined) {
   const selected = get(selectedFiles);
@@ -81,7 +82,7 @@ export async function pasteFiles(
     }
   } catch (err) {
     console.error('Paste error:', err);
-    alert('Paste failed: ' + err);
+    alert('Paste failed: ' + errorMessage(err));
   } finally {
     isPasting = false;
   }
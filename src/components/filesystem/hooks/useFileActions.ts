@@ -5,11 +5,20 @@ import { currentView } from '$lib/stores/viewStore';
 import { isImageFile, isVideoFile, joinPath } from './fileUtils';
 import { openMediaInNewWindow } from '$lib/utils/openMediaWindow';
 import { directoryCache } from '$lib/stores/directoryCacheStore';
+import { recentFiles } from '$lib/stores/locationStore';
+import { errorMessage } from '$lib/utils/errors';
+import { textToEditorHtml } from '$lib/utils/editorContent';
+
+function recordOpen(path: string) {
+  recentFiles.add(path).catch(err => console.error('Failed to record recent file:', err));
+}
 
 export function openFile(file: any) {
   if (isImageFile(file.name)) {
+    recordOpen(file.path);
     openMediaInNewWindow(file.path, file.name, 'image');
   } else if (isVideoFile(file.name)) {
+    recordOpen(file.path);
     openMediaInNewWindow(file.path, file.name, 'video');
   } else {
     openInEditor(file);
@@ -34,15 +43,16 @@ export async function openInEditor(file: any) {
       id: crypto.randomUUID(),
       name: file.name,
       path: file.path,
-      content: `<p>${content.replace(/\n/g, '</p><p>')}</p>`,
+      content: textToEditorHtml(content),
       isDirty: false
     };
 
     editorTabs.update(tabs => [...tabs, newTab]);
     activeEditorTabId.set(newTab.id);
     currentView.set('editor');
+    recordOpen(file.path);
   } catch (err) {
-    alert(`Failed to open file: ${err}`);
+    alert(`Failed to open file: ${errorMessage(err)}`);
   }
 }
 
@@ -69,7 +79,7 @@ export async function deleteFiles(
     directoryCache.invalidate(currentPath);
     reloadFn(currentPath, null, true);
   } catch (err) {
-    alert('Error deleting: ' + err);
+    alert('Error deleting: ' + errorMessage(err));
   }
 }
 
@@ -109,7 +119,7 @@ export async function createItem(
     directoryCache.invalidate(currentPath);
     await reloadFn(currentPath, finalName, true);
   } catch (err) {
-    alert(`Error creating ${type}: ${err}`);
+    alert(`Error creating ${type}: ${errorMessage(err)}`);
   }
 }
 
@@ -128,6 +138,6 @@ export async function renameFile(
     directoryCache.invalidate(currentPath);
     await reloadFn(currentPath, newName, true);
   } catch (err) {
-    alert('Rename failed: ' + err);
+    alert('Rename failed: ' + errorMessage(err));
   }
 }
\ No newline at end of file
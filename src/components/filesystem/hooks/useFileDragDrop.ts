@@ -1,6 +1,7 @@
 import { writable, get } from 'svelte/store';
 import { invoke } from '@tauri-apps/api/core';
 import { joinPath } from './fileUtils';
+import { errorMessage } from '$lib/utils/errors';
 
 export interface DragState {
   draggedFile: string | null;
@@ -161,7 +162,7 @@ function createFileDragDrop() {
         onComplete();
       } catch (err) {
         console.error("❌ Move error:", err);
-        alert("Move failed: " + err);
+        alert("Move failed: " + errorMessage(err));
       }
     },
 
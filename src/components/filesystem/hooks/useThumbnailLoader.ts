@@ -1,11 +1,12 @@
 /**
  * Thumbnail loading queue system
- * Handles lazy loading of image/video thumbnails with concurrency limits
+ * Handles lazy loading of image/video thumbnails with concurrency limits;
+ * items on screen (see `observeThumbnail`) are loaded and generated first
  * OPTIMIZED: Added global cache that persists across directory changes
  */
 
 import { writable, get, derived } from 'svelte/store';
-import { invoke } from '@tauri-apps/api/core';
+import { invoke, convertFileSrc } from '@tauri-apps/api/core';
 import { isImageFile, isVideoFile, getImageMimeType } from './fileUtils';
 import { settings } from '$lib/stores/settingsStore';
 
@@ -23,6 +24,9 @@ interface QueueItem {
 const globalThumbnailCache = new Map<string, string>();
 const MAX_GLOBAL_CACHE_SIZE = 500;
 
+// Edge length requested from the backend thumbnail cache (covers HiDPI grid icons)
+const THUMBNAIL_RENDER_SIZE = 256;
+
 function createThumbnailLoader() {
   const { subscribe, set, update } = writable<ThumbnailState>({
     thumbnails: new Map<string, string>(),
@@ -32,6 +36,30 @@ function createThumbnailLoader() {
   let thumbnailQueue: QueueItem[] = [];
   let activeLoads = 0;
 
+  // Paths whose item is on screen, reported by the intersection observer
+  const visiblePaths = new Set<string>();
+  // Paths with a `get_thumbnail` request waiting on the backend
+  const requested = new Set<string>();
+  let observer: IntersectionObserver | null = null;
+
+  function handleIntersect(entries: IntersectionObserverEntry[]) {
+    const raised: string[] = [];
+    for (const entry of entries) {
+      const path = (entry.target as HTMLElement).dataset.thumbnailPath;
+      if (!path) continue;
+      if (entry.isIntersecting) {
+        visiblePaths.add(path);
+        if (requested.has(path)) raised.push(path);
+      } else {
+        visiblePaths.delete(path);
+      }
+    }
+    // Requests already queued in the backend move ahead of background work
+    if (raised.length > 0) {
+      invoke('prioritize_thumbnails', { paths: raised }).catch(() => {});
+    }
+  }
+
   function getMaxConcurrent(): number {
     const currentSettings = get(settings);
     return currentSettings.fileMaxConcurrentThumbnails || 5;
@@ -46,7 +74,8 @@ function createThumbnailLoader() {
     const maxConcurrent = getMaxConcurrent();
     
     while (thumbnailQueue.length > 0 && activeLoads < maxConcurrent) {
-      const item = thumbnailQueue.shift();
+      const visible = thumbnailQueue.findIndex(item => visiblePaths.has(item.path));
+      const [item] = thumbnailQueue.splice(Math.max(visible, 0), 1);
       if (item) {
         activeLoads++;
         loadThumbnail(item.path, item.name);
@@ -54,6 +83,11 @@ function createThumbnailLoader() {
     }
   }
 
+  async function loadImageDataUrl(filePath: string, fileName: string): Promise<string> {
+    const base64 = await invoke<string>('read_file_base64', { path: filePath });
+    return `data:${getImageMimeType(fileName)};base64,${base64}`;
+  }
+
   async function loadThumbnail(filePath: string, fileName: string) {
     const state = get({ subscribe });
     
@@ -82,23 +116,32 @@ function createThumbnailLoader() {
     }));
 
     try {
-      let base64: string;
-      let mimeType: string;
+      let thumbUrl: string;
 
-      if (isVideoFile(fileName)) {
-        // Extract video thumbnail - Rust function only takes path
-        base64 = await invoke<string>('extract_video_thumbnail', { path: filePath });
-        mimeType = 'image/png';
+      if (fileName.toLowerCase().endsWith('.svg')) {
+        // Vector images are small and scale cleanly - load directly
+        thumbUrl = await loadImageDataUrl(filePath, fileName);
       } else {
-        // Load image directly
-        base64 = await invoke<string>('read_file_base64', { path: filePath });
-        mimeType = getImageMimeType(fileName);
+        try {
+          // Served from the persistent on-disk cache, generated by the Rust worker pool
+          requested.add(filePath);
+          const thumbPath = await invoke<string>('get_thumbnail', {
+            path: filePath,
+            size: THUMBNAIL_RENDER_SIZE,
+            visible: visiblePaths.has(filePath)
+          });
+          thumbUrl = convertFileSrc(thumbPath);
+        } catch (err) {
+          // Formats the backend cannot decode still render in the webview
+          if (isVideoFile(fileName) || String(err).includes('cancelled')) throw err;
+          thumbUrl = await loadImageDataUrl(filePath, fileName);
+        } finally {
+          requested.delete(filePath);
+        }
       }
 
-      const dataUrl = `data:${mimeType};base64,${base64}`;
-
       // OPTIMIZATION: Add to global cache
-      globalThumbnailCache.set(filePath, dataUrl);
+      globalThumbnailCache.set(filePath, thumbUrl);
       
       // Limit global cache size
       if (globalThumbnailCache.size > MAX_GLOBAL_CACHE_SIZE) {
@@ -109,7 +152,7 @@ function createThumbnailLoader() {
       // Add to current view thumbnails
       update(state => {
         const newThumbnails = new Map(state.thumbnails);
-        newThumbnails.set(fileName, dataUrl);
+        newThumbnails.set(fileName, thumbUrl);
         
         const newLoadingSet = new Set(state.loadingSet);
         newLoadingSet.delete(fileName);
@@ -138,6 +181,8 @@ function createThumbnailLoader() {
 
     queueThumbnails: (files: any[]) => {
       thumbnailQueue = [];
+      // Drop backend work for the directory we are leaving
+      invoke('cancel_pending_thumbnails').catch(() => {});
       
       // OPTIMIZATION: Restore from global cache, queue the rest
       const restoredThumbnails = new Map<string, string>();
@@ -166,6 +211,7 @@ function createThumbnailLoader() {
 
     clearThumbnails: () => {
       thumbnailQueue = [];
+      invoke('cancel_pending_thumbnails').catch(() => {});
       activeLoads = 0;
       set({
         thumbnails: new Map<string, string>(),
@@ -179,6 +225,26 @@ function createThumbnailLoader() {
 
     getThumbnailSize,
 
+    /** Svelte action: track whether the item showing `path` is on screen. */
+    observe: (node: HTMLElement, path: string) => {
+      observer ??= new IntersectionObserver(handleIntersect, { rootMargin: '200px' });
+      node.dataset.thumbnailPath = path;
+      observer.observe(node);
+      return {
+        update(next: string) {
+          visiblePaths.delete(node.dataset.thumbnailPath ?? '');
+          node.dataset.thumbnailPath = next;
+          // Re-observing reports the current intersection for the new path
+          observer?.unobserve(node);
+          observer?.observe(node);
+        },
+        destroy() {
+          visiblePaths.delete(node.dataset.thumbnailPath ?? '');
+          observer?.unobserve(node);
+        }
+      };
+    },
+
     getGlobalCacheSize: (): number => {
       return globalThumbnailCache.size;
     }
@@ -186,6 +252,7 @@ function createThumbnailLoader() {
 }
 
 export const thumbnailLoader = createThumbnailLoader();
+export const observeThumbnail = thumbnailLoader.observe;
 
 // Derived store for backward compatibility
 export const thumbnails = derived(thumbnailLoader, $state => $state.thumbnails);
\ No newline at end of file
@@ -0,0 +1,70 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+
+// ─── TypeScript interfaces ────────────────────────────────────────────────────
+
+export type HashAlgorithm = 'sha256' | 'sha1' | 'md5' | 'blake3';
+
+export interface FileHash {
+  path: string;
+  algorithm: HashAlgorithm;
+  /** Lowercase hex digest */
+  hash: string;
+  size: number;
+}
+
+export interface HashProgress {
+  job_id: string;
+  path: string;
+  bytes_done: number;
+  bytes_total: number;
+  files_done: number;
+  files_total: number;
+}
+
+export type ChecksumStatus = 'ok' | 'mismatch' | 'missing' | 'error';
+
+export interface ChecksumEntry {
+  /** File name as written in the checksum file */
+  name: string;
+  path: string;
+  algorithm: HashAlgorithm;
+  expected: string;
+  actual: string | null;
+  status: ChecksumStatus;
+  error: string | null;
+}
+
+export interface ChecksumReport {
+  checksum_file: string;
+  entries: ChecksumEntry[];
+  ok: number;
+  mismatched: number;
+  missing: number;
+  errors: number;
+  skipped_lines: number;
+}
+
+// ─── Commands ─────────────────────────────────────────────────────────────────
+
+/** Pass a `jobId` to receive `hash-progress` events for large files. */
+export function hashFile(path: string, algorithm: HashAlgorithm = 'sha256', jobId?: string): Promise<FileHash> {
+  return invoke<FileHash>('hash_file', { path, algorithm, jobId: jobId ?? null });
+}
+
+/** Verify every entry of a `.sha256sum`-style file; algorithm is detected when omitted. */
+export function verifyChecksums(
+  checksumFile: string,
+  algorithm?: HashAlgorithm,
+  jobId?: string
+): Promise<ChecksumReport> {
+  return invoke<ChecksumReport>('verify_checksums', {
+    checksumFile,
+    algorithm: algorithm ?? null,
+    jobId: jobId ?? null,
+  });
+}
+
+export function onHashProgress(handler: (progress: HashProgress) => void): Promise<UnlistenFn> {
+  return listen<HashProgress>('hash-progress', event => handler(event.payload));
+}
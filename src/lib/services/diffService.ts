@@ -0,0 +1,85 @@
+import { invoke } from '@tauri-apps/api/core';
+
+// ─── TypeScript interfaces ────────────────────────────────────────────────────
+
+export type LineChange = 'unchanged' | 'added' | 'removed';
+
+export interface InlineSegment {
+  text: string;
+  /** True for the words that differ from the paired line */
+  changed: boolean;
+}
+
+export interface DiffLine {
+  change: LineChange;
+  old_line: number | null;
+  new_line: number | null;
+  segments: InlineSegment[];
+}
+
+export interface DiffHunk {
+  old_start: number;
+  old_lines: number;
+  new_start: number;
+  new_lines: number;
+  lines: DiffLine[];
+}
+
+export interface FileDiff {
+  left: string;
+  right: string;
+  identical: boolean;
+  /** Binary or oversized files carry no hunks */
+  binary: boolean;
+  hunks: DiffHunk[];
+  added: number;
+  removed: number;
+}
+
+export type EntryStatus = 'only_left' | 'only_right' | 'changed';
+
+/** Symlinks are compared by target, never followed. */
+export type EntryKind = 'file' | 'dir' | 'symlink';
+
+export interface DirEntryDiff {
+  path: string;
+  status: EntryStatus;
+  is_dir: boolean;
+  /** null on the side the entry is missing from */
+  left_kind: EntryKind | null;
+  right_kind: EntryKind | null;
+  left_size: number | null;
+  right_size: number | null;
+}
+
+/** An entry that couldn't be compared, e.g. an unreadable file or folder. */
+export interface DirEntryError {
+  path: string;
+  message: string;
+}
+
+export interface DirectoryDiff {
+  left: string;
+  right: string;
+  only_left: DirEntryDiff[];
+  only_right: DirEntryDiff[];
+  changed: DirEntryDiff[];
+  identical_count: number;
+  errors: DirEntryError[];
+}
+
+export type CompareMode = 'name' | 'size' | 'hash';
+
+// ─── Commands ─────────────────────────────────────────────────────────────────
+
+export function diffFiles(left: string, right: string, context = 3): Promise<FileDiff> {
+  return invoke<FileDiff>('diff_files', { left, right, context });
+}
+
+export function diffDirectories(
+  left: string,
+  right: string,
+  compare: CompareMode = 'hash'
+): Promise<DirectoryDiff> {
+  return invoke<DirectoryDiff>('diff_directories', { left, right, compare });
+}
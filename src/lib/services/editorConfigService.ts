@@ -0,0 +1,29 @@
+import { invoke } from '@tauri-apps/api/core';
+
+// ─── TypeScript interfaces ────────────────────────────────────────────────────
+
+/** Effective `.editorconfig` settings for a file; null where no rule applies. */
+export interface EditorConfigSettings {
+  indent_style: 'tab' | 'space' | null;
+  indent_size: number | null;
+  tab_width: number | null;
+  end_of_line: 'lf' | 'crlf' | 'cr' | null;
+  charset: 'utf-8' | 'utf-8-bom' | 'utf-16le' | 'utf-16be' | 'latin1' | null;
+  trim_trailing_whitespace: boolean | null;
+  insert_final_newline: boolean | null;
+  max_line_length: number | null;
+  /** Contributing .editorconfig files, outermost first */
+  sources: string[];
+}
+
+// ─── Commands ─────────────────────────────────────────────────────────────────
+
+export function resolveEditorConfig(path: string): Promise<EditorConfigSettings> {
+  return invoke<EditorConfigSettings>('resolve_editorconfig', { path });
+}
+
+/** Text inserted by the Tab key, from the file's settings or the editor default. */
+export function indentUnit(config: EditorConfigSettings | null, defaultSize: number): string {
+  if (config?.indent_style === 'tab') return '\t';
+  return ' '.repeat(config?.indent_size ?? defaultSize);
+}
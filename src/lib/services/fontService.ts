@@ -0,0 +1,59 @@
+import { invoke } from '@tauri-apps/api/core';
+
+// ─── TypeScript interfaces ────────────────────────────────────────────────────
+
+/** One installed face; collections have a face per index. */
+export interface FontInfo {
+  family: string;
+  style: string;
+  /** 100 (thin) to 900 (black); 400 is regular */
+  weight: number;
+  italic: boolean;
+  monospace: boolean;
+  path: string;
+  index: number;
+}
+
+// ─── Commands ─────────────────────────────────────────────────────────────────
+
+/** Installed faces sorted by family; the scan is cached until `refresh`. */
+export function getSystemFonts(options: { monospaceOnly?: boolean; refresh?: boolean } = {}): Promise<FontInfo[]> {
+  return invoke<FontInfo[]>('get_system_fonts', {
+    monospaceOnly: options.monospaceOnly ?? false,
+    refresh: options.refresh ?? false,
+  });
+}
+
+/** Distinct family names, in the order the faces are sorted. */
+export function fontFamilies(faces: FontInfo[]): string[] {
+  return [...new Set(faces.map(f => f.family))];
+}
+
+/** A CSS font-family value: a picked family name with a fallback, or a full stack as is. */
+export function cssFontStack(value: string, fallback: string): string {
+  if (!value.trim()) return fallback;
+  if (value.includes(',') || /['"]/.test(value)) return value;
+  return `'${value}', ${fallback}`;
+}
+
+/** The installed face that best matches a family, weight and slant. */
+export function resolveFont(family: string, weight = 400, italic = false): Promise<FontInfo> {
+  return invoke<FontInfo>('resolve_font', { family, weight, italic });
+}
+
+/** Sample text rendered natively in `family`, as a PNG data URL. */
+export async function renderFontPreview(
+  family: string,
+  text: string,
+  options: { size?: number; weight?: number; italic?: boolean; color?: string } = {},
+): Promise<string> {
+  const png = await invoke<string>('render_font_preview', {
+    family,
+    text,
+    size: options.size ?? null,
+    weight: options.weight ?? null,
+    italic: options.italic ?? null,
+    color: options.color ?? null,
+  });
+  return `data:image/png;base64,${png}`;
+}
@@ -0,0 +1,48 @@
+import { invoke } from '@tauri-apps/api/core';
+
+// ─── TypeScript interfaces ────────────────────────────────────────────────────
+
+/** Formatter settings for one language, keyed by file extension in settings. */
+export interface FormatterConfig {
+  /** Formatter id to use instead of the default, or 'none' to never format */
+  formatter?: string;
+  /** Path to the formatter binary, when it is not on PATH */
+  command?: string;
+  extra_args?: string[];
+  timeout_ms?: number;
+}
+
+export interface FormatResult {
+  /** null when no formatter applies to the file */
+  formatter: string | null;
+  content: string;
+  changed: boolean;
+}
+
+export interface FormatterInfo {
+  id: string;
+  extensions: string[];
+  /** null when the formatter is not installed */
+  path: string | null;
+}
+
+// ─── Commands ─────────────────────────────────────────────────────────────────
+
+/** Format buffer content for the file at `path`; the file itself is not touched. */
+export function formatDocument(path: string, content: string, config?: FormatterConfig): Promise<FormatResult> {
+  return invoke<FormatResult>('format_document', { path, content, config: config ?? null });
+}
+
+export function listFormatters(): Promise<FormatterInfo[]> {
+  return invoke<FormatterInfo[]>('list_formatters');
+}
+
+/** The configuration in `configs` for the extension of `path`, if any. */
+export function formatterConfigFor(
+  path: string,
+  configs: Record<string, FormatterConfig>,
+): FormatterConfig | undefined {
+  const name = path.split(/[\\/]/).pop() ?? '';
+  const dot = name.lastIndexOf('.');
+  return dot > 0 ? configs[name.slice(dot + 1).toLowerCase()] : undefined;
+}
@@ -0,0 +1,120 @@
+import { invoke } from '@tauri-apps/api/core';
+
+// ─── TypeScript interfaces ────────────────────────────────────────────────────
+
+export type GitLineKind = 'context' | 'added' | 'removed' | 'no_newline';
+
+export interface GitDiffLine {
+  kind: GitLineKind;
+  content: string;
+  old_line: number | null;
+  new_line: number | null;
+}
+
+export interface GitHunk {
+  /** `@@ -a,b +c,d @@` line; identifies the hunk for staging */
+  header: string;
+  old_start: number;
+  old_lines: number;
+  new_start: number;
+  new_lines: number;
+  lines: GitDiffLine[];
+}
+
+export interface GitFileDiff {
+  path: string;
+  binary: boolean;
+  hunks: GitHunk[];
+}
+
+/** `index`: work tree vs index, `staged`: index vs HEAD, `head`: work tree vs HEAD */
+export type GitDiffBase = 'index' | 'staged' | 'head';
+
+export interface GitCommit {
+  hash: string;
+  short_hash: string;
+  author_name: string;
+  author_email: string;
+  date: string;
+  subject: string;
+}
+
+export interface GitLogPage {
+  commits: GitCommit[];
+  has_more: boolean;
+}
+
+export interface GitBranch {
+  name: string;
+  is_remote: boolean;
+  is_current: boolean;
+  commit: string;
+  upstream: string | null;
+  updated_at: string;
+}
+
+export interface GitStash {
+  index: number;
+  message: string;
+  date: string;
+}
+
+// ─── Staging ──────────────────────────────────────────────────────────────────
+
+export function stagePaths(repo: string, paths: string[]): Promise<void> {
+  return invoke('git_stage', { repo, paths });
+}
+
+export function unstagePaths(repo: string, paths: string[]): Promise<void> {
+  return invoke('git_unstage', { repo, paths });
+}
+
+export function stageHunk(path: string, hunkHeader: string): Promise<void> {
+  return invoke('git_stage_hunk', { path, hunkHeader });
+}
+
+export function unstageHunk(path: string, hunkHeader: string): Promise<void> {
+  return invoke('git_unstage_hunk', { path, hunkHeader });
+}
+
+// ─── Commits and history ──────────────────────────────────────────────────────
+
+export function commit(repo: string, message: string, amend = false): Promise<GitCommit> {
+  return invoke<GitCommit>('git_commit', { repo, message, amend });
+}
+
+export function getLog(repo: string, skip = 0, limit = 50, file?: string): Promise<GitLogPage> {
+  return invoke<GitLogPage>('git_log', { repo, skip, limit, file: file ?? null });
+}
+
+export function diffFile(path: string, against: GitDiffBase = 'index'): Promise<GitFileDiff> {
+  return invoke<GitFileDiff>('git_diff_file', { path, against });
+}
+
+// ─── Branches ─────────────────────────────────────────────────────────────────
+
+export function listBranches(repo: string): Promise<GitBranch[]> {
+  return invoke<GitBranch[]>('git_branches', { repo });
+}
+
+export function createBranch(repo: string, name: string, startPoint?: string, checkout = true): Promise<void> {
+  return invoke('git_create_branch', { repo, name, startPoint: startPoint ?? null, checkout });
+}
+
+export function checkoutBranch(repo: string, name: string): Promise<void> {
+  return invoke('git_checkout_branch', { repo, name });
+}
+
+// ─── Stashes ──────────────────────────────────────────────────────────────────
+
+export function listStashes(repo: string): Promise<GitStash[]> {
+  return invoke<GitStash[]>('git_stash_list', { repo });
+}
+
+export function stashPush(repo: string, message?: string, includeUntracked = false): Promise<void> {
+  return invoke('git_stash_push', { repo, message: message ?? null, includeUntracked });
+}
+
+export function stashPop(repo: string, index = 0): Promise<void> {
+  return invoke('git_stash_pop', { repo, index });
+}
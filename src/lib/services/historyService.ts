@@ -0,0 +1,51 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { DiffHunk } from './diffService';
+
+// ─── TypeScript interfaces ────────────────────────────────────────────────────
+
+export interface HistoryVersion {
+  id: number;
+  path: string;
+  hash: string;
+  size: number;
+  created_at: string;
+}
+
+export interface HistoryVersionContent {
+  version: HistoryVersion;
+  content: string;
+}
+
+/** A stored version (old side) compared with the current file (new side). */
+export interface HistoryDiff {
+  version: HistoryVersion;
+  file_missing: boolean;
+  identical: boolean;
+  hunks: DiffHunk[];
+  added: number;
+  removed: number;
+}
+
+// ─── Commands ─────────────────────────────────────────────────────────────────
+
+/** Versions of a file, newest first. */
+export function listFileHistory(path: string): Promise<HistoryVersion[]> {
+  return invoke<HistoryVersion[]>('list_file_history', { path });
+}
+
+export function getHistoryVersion(id: number): Promise<HistoryVersionContent> {
+  return invoke<HistoryVersionContent>('get_history_version', { id });
+}
+
+export function diffHistoryVersion(id: number, context = 3): Promise<HistoryDiff> {
+  return invoke<HistoryDiff>('diff_history_version', { id, context });
+}
+
+/** Write a version back to its file; the replaced content stays in history. */
+export function restoreHistoryVersion(id: number): Promise<HistoryVersion> {
+  return invoke<HistoryVersion>('restore_history_version', { id });
+}
+
+export function clearFileHistory(path: string): Promise<void> {
+  return invoke('clear_file_history', { path });
+}
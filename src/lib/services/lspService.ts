@@ -0,0 +1,363 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+
+// ─── TypeScript interfaces ────────────────────────────────────────────────────
+
+export type LspServerState = 'running' | 'restarted' | 'stopped' | 'failed';
+
+export interface LspServerInfo {
+  id: string;
+  command: string;
+  root: string;
+  languages: string[];
+  state: LspServerState;
+}
+
+export interface AvailableServer {
+  command: string;
+  languages: string[];
+  /** null when the server is not installed */
+  path: string | null;
+}
+
+export interface LspStatus {
+  server_id: string;
+  state: LspServerState;
+  message: string | null;
+}
+
+interface LspMessageEvent {
+  server_id: string;
+  message: JsonRpcMessage;
+}
+
+export interface JsonRpcMessage {
+  jsonrpc: '2.0';
+  id?: number | string | null;
+  method?: string;
+  params?: unknown;
+  result?: unknown;
+  error?: { code: number; message: string; data?: unknown };
+}
+
+/** Zero-based line and UTF-16 character offset. */
+export interface LspPosition {
+  line: number;
+  character: number;
+}
+
+export interface LspRange {
+  start: LspPosition;
+  end: LspPosition;
+}
+
+export interface LspLocation {
+  uri: string;
+  range: LspRange;
+}
+
+export interface CompletionItem {
+  label: string;
+  detail?: string;
+  insertText?: string;
+  filterText?: string;
+  sortText?: string;
+  /** `range` for a plain edit, `insert`/`replace` for an InsertReplaceEdit */
+  textEdit?: { newText: string; range?: LspRange; insert?: LspRange; replace?: LspRange };
+}
+
+// ─── Commands ─────────────────────────────────────────────────────────────────
+
+export function availableServers(): Promise<AvailableServer[]> {
+  return invoke<AvailableServer[]>('lsp_available_servers');
+}
+
+/** Start (or reuse) the server for the workspace containing `path`. */
+export function startServer(language: string, path: string): Promise<LspServerInfo> {
+  return invoke<LspServerInfo>('lsp_start', { language, path });
+}
+
+export function sendMessage(serverId: string, message: JsonRpcMessage): Promise<void> {
+  return invoke('lsp_send', { serverId, message });
+}
+
+export function stopServer(serverId: string): Promise<void> {
+  return invoke('lsp_stop', { serverId });
+}
+
+export function listServers(): Promise<LspServerInfo[]> {
+  return invoke<LspServerInfo[]>('lsp_list_servers');
+}
+
+// ─── Client ───────────────────────────────────────────────────────────────────
+
+type NotificationHandler = (params: any) => void;
+
+export function pathToUri(path: string): string {
+  const normalized = path.replace(/\\/g, '/');
+  return encodeURI(normalized.startsWith('/') ? `file://${normalized}` : `file:///${normalized}`);
+}
+
+/** Compare URIs as servers may re-encode them or change drive letter case. */
+export function sameUri(a: string, b: string): boolean {
+  const normalize = (uri: string) =>
+    decodeURIComponent(uri).replace(/^file:\/\/\/([A-Za-z]):/, (_, drive) => `file:///${drive.toLowerCase()}:`);
+  return normalize(a) === normalize(b);
+}
+
+export function uriToPath(uri: string): string {
+  const path = decodeURIComponent(uri.replace(/^file:\/\//, ''));
+  // file:///C:/dir -> C:/dir
+  return /^\/[A-Za-z]:/.test(path) ? path.slice(1) : path;
+}
+
+/** Items of a `textDocument/completion` result, sorted as the server asks. */
+export function completionItems(result: any): CompletionItem[] {
+  const items: CompletionItem[] = Array.isArray(result) ? result : result?.items ?? [];
+  return [...items].sort((a, b) => (a.sortText ?? a.label).localeCompare(b.sortText ?? b.label));
+}
+
+/** Plain text of a `textDocument/hover` result; code fences are dropped. */
+export function hoverText(result: any): string {
+  const part = (content: any): string =>
+    typeof content === 'string' ? content : content?.value ?? '';
+  const contents = result?.contents;
+  const text = Array.isArray(contents) ? contents.map(part).join('\n\n') : part(contents);
+  return text
+    .split('\n')
+    .filter(line => !line.startsWith('```'))
+    .join('\n')
+    .trim();
+}
+
+/** Targets of a `textDocument/definition` result (Location, Location[] or LocationLink[]). */
+export function definitionLocations(result: any): LspLocation[] {
+  const list: any[] = Array.isArray(result) ? result : result ? [result] : [];
+  return list.map(item =>
+    'targetUri' in item
+      ? { uri: item.targetUri, range: item.targetSelectionRange ?? item.targetRange }
+      : { uri: item.uri, range: item.range }
+  );
+}
+
+const clients = new Map<string, Promise<LspClient>>();
+
+/** JSON-RPC error code for requests the client doesn't implement. */
+const METHOD_NOT_FOUND = -32601;
+
+/**
+ * A server with no open documents is stopped after this long; the delay
+ * keeps it running while the editor switches between files of one workspace.
+ */
+const IDLE_STOP_MS = 30_000;
+
+/** Language id of a file for the servers, or null when none handles it. */
+export function languageForPath(path: string): string | null {
+  const ext = path.split('.').pop()?.toLowerCase() ?? '';
+  const map: Record<string, string> = {
+    rs: 'rust',
+    ts: 'typescript',
+    mts: 'typescript',
+    cts: 'typescript',
+    tsx: 'typescriptreact',
+    js: 'javascript',
+    mjs: 'javascript',
+    cjs: 'javascript',
+    jsx: 'javascriptreact',
+    py: 'python',
+  };
+  return map[ext] ?? null;
+}
+
+/**
+ * JSON-RPC client for one language server. Matches responses to requests,
+ * dispatches notifications, and repeats the initialize handshake when the
+ * backend restarts a crashed server (documents must then be reopened, see
+ * `onRestart`). Documents go through `openDocument` and `closeDocument`,
+ * and the server is stopped once none has been open for a while.
+ */
+export class LspClient {
+  private nextId = 1;
+  private pending = new Map<number, { resolve: (v: any) => void; reject: (e: Error) => void }>();
+  private handlers = new Map<string, NotificationHandler[]>();
+  private unlisten: UnlistenFn[] = [];
+  private restartHandlers: (() => void)[] = [];
+  /** Open count per document URI; several editors may show the same file */
+  private documents = new Map<string, number>();
+  private idleTimer: ReturnType<typeof setTimeout> | undefined;
+  capabilities: Record<string, any> = {};
+
+  private constructor(readonly server: LspServerInfo) {}
+
+  /** Client for the workspace containing `path`, shared by all its files. */
+  static async forPath(language: string, path: string): Promise<LspClient> {
+    const server = await startServer(language, path);
+    let client = clients.get(server.id);
+    if (!client) {
+      client = LspClient.connect(server);
+      clients.set(server.id, client);
+      client.catch(() => clients.delete(server.id));
+    }
+    return client;
+  }
+
+  private static async connect(server: LspServerInfo): Promise<LspClient> {
+    const client = new LspClient(server);
+    client.unlisten.push(
+      await listen<LspMessageEvent>('lsp-message', event => {
+        if (event.payload.server_id === client.server.id) client.dispatch(event.payload.message);
+      }),
+      await listen<LspStatus>('lsp-status', event => {
+        if (event.payload.server_id === client.server.id) client.onStatus(event.payload);
+      }),
+    );
+    await client.initialize();
+    // Stopped again if the caller never opens a document
+    client.scheduleIdleStop();
+    return client;
+  }
+
+  request<T = any>(method: string, params?: unknown): Promise<T> {
+    const id = this.nextId++;
+    return new Promise<T>((resolve, reject) => {
+      this.pending.set(id, { resolve, reject });
+      sendMessage(this.server.id, { jsonrpc: '2.0', id, method, params }).catch(err => {
+        this.pending.delete(id);
+        reject(err instanceof Error ? err : new Error(String(err)));
+      });
+    });
+  }
+
+  notify(method: string, params?: unknown): Promise<void> {
+    return sendMessage(this.server.id, { jsonrpc: '2.0', method, params });
+  }
+
+  /** Handle a server notification, e.g. `textDocument/publishDiagnostics`. */
+  on(method: string, handler: NotificationHandler): () => void {
+    const list = this.handlers.get(method) ?? [];
+    list.push(handler);
+    this.handlers.set(method, list);
+    return () => this.handlers.set(method, (this.handlers.get(method) ?? []).filter(h => h !== handler));
+  }
+
+  /** Send `didOpen`; the server stays running while any document is open. */
+  openDocument(document: { uri: string; languageId: string; version: number; text: string }): Promise<void> {
+    clearTimeout(this.idleTimer);
+    this.documents.set(document.uri, (this.documents.get(document.uri) ?? 0) + 1);
+    return this.notify('textDocument/didOpen', { textDocument: document });
+  }
+
+  /** Send `didClose`; closing the last document schedules the server's stop. */
+  closeDocument(uri: string): Promise<void> {
+    const count = (this.documents.get(uri) ?? 1) - 1;
+    if (count > 0) this.documents.set(uri, count);
+    else this.documents.delete(uri);
+    if (this.documents.size === 0) this.scheduleIdleStop();
+    return this.notify('textDocument/didClose', { textDocument: { uri } });
+  }
+
+  private scheduleIdleStop() {
+    clearTimeout(this.idleTimer);
+    this.idleTimer = setTimeout(() => {
+      if (this.documents.size > 0) return;
+      this.dispose().catch(err => console.warn('Failed to stop language server:', err));
+    }, IDLE_STOP_MS);
+  }
+
+  /** Called after a crashed server was restarted and initialized again. */
+  onRestart(handler: () => void): () => void {
+    this.restartHandlers.push(handler);
+    return () => (this.restartHandlers = this.restartHandlers.filter(h => h !== handler));
+  }
+
+  async dispose(): Promise<void> {
+    clearTimeout(this.idleTimer);
+    clients.delete(this.server.id);
+    this.unlisten.forEach(fn => fn());
+    try {
+      await this.request('shutdown');
+    } catch {
+      // Stopping kills the server anyway
+    }
+    await stopServer(this.server.id);
+    this.rejectPending('Language server stopped');
+  }
+
+  private async initialize(): Promise<void> {
+    const result = await this.request<{ capabilities: Record<string, any> }>('initialize', {
+      processId: null,
+      rootUri: pathToUri(this.server.root),
+      workspaceFolders: [{ uri: pathToUri(this.server.root), name: this.server.root.split(/[\\/]/).pop() }],
+      capabilities: {
+        textDocument: {
+          synchronization: { didSave: true },
+          completion: { completionItem: { snippetSupport: false, documentationFormat: ['markdown', 'plaintext'] } },
+          hover: { contentFormat: ['markdown', 'plaintext'] },
+          definition: { linkSupport: false },
+          publishDiagnostics: { relatedInformation: false },
+        },
+        workspace: { workspaceFolders: true },
+      },
+    });
+    this.capabilities = result.capabilities ?? {};
+    await this.notify('initialized', {});
+  }
+
+  private dispatch(message: JsonRpcMessage) {
+    if (message.method === undefined && typeof message.id === 'number') {
+      const waiter = this.pending.get(message.id);
+      if (!waiter) return;
+      this.pending.delete(message.id);
+      if (message.error) waiter.reject(new Error(message.error.message));
+      else waiter.resolve(message.result);
+    } else if (message.method && message.id !== undefined) {
+      sendMessage(this.server.id, this.answer(message)).catch(() => {});
+    } else if (message.method) {
+      this.handlers.get(message.method)?.forEach(h => h(message.params));
+    }
+  }
+
+  /** Reply to a server-to-client request; we have no settings and register nothing. */
+  private answer(request: JsonRpcMessage): JsonRpcMessage {
+    const id = request.id ?? null;
+    switch (request.method) {
+      case 'workspace/configuration': {
+        // One entry per requested item; null means "use your defaults"
+        const items = (request.params as { items?: unknown[] } | undefined)?.items ?? [];
+        return { jsonrpc: '2.0', id, result: items.map(() => null) };
+      }
+      case 'client/registerCapability':
+      case 'client/unregisterCapability':
+      case 'window/workDoneProgress/create':
+        return { jsonrpc: '2.0', id, result: null };
+      default:
+        return {
+          jsonrpc: '2.0',
+          id,
+          error: { code: METHOD_NOT_FOUND, message: `Unhandled method ${request.method}` },
+        };
+    }
+  }
+
+  private async onStatus(status: LspStatus) {
+    this.server.state = status.state;
+    if (status.state === 'restarted') {
+      this.rejectPending('Language server restarted');
+      try {
+        await this.initialize();
+        // The new process has nothing open; restart handlers reopen documents
+        this.documents.clear();
+        this.restartHandlers.forEach(h => h());
+      } catch (err) {
+        console.error('Failed to reinitialize language server:', err);
+      }
+    } else if (status.state === 'failed' || status.state === 'stopped') {
+      this.rejectPending(status.message ?? 'Language server stopped');
+    }
+  }
+
+  private rejectPending(reason: string) {
+    this.pending.forEach(waiter => waiter.reject(new Error(reason)));
+    this.pending.clear();
+  }
+}
@@ -0,0 +1,54 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+
+// ─── TypeScript interfaces ────────────────────────────────────────────────────
+
+export interface BufferSnapshotInput {
+  buffer_id: string;
+  name: string;
+  path: string | null;
+  content: string;
+}
+
+export interface BufferSnapshot extends BufferSnapshotInput {
+  /** Unix seconds */
+  saved_at: number;
+}
+
+export interface RecoverableBuffer {
+  buffer_id: string;
+  name: string;
+  path: string | null;
+  saved_at: number;
+  size: number;
+  /** The file on disk changed after the snapshot was taken */
+  file_changed: boolean;
+}
+
+// ─── Commands ─────────────────────────────────────────────────────────────────
+
+export function saveBufferSnapshot(snapshot: BufferSnapshotInput): Promise<void> {
+  return invoke('save_buffer_snapshot', { snapshot });
+}
+
+export function discardBufferSnapshot(bufferId: string): Promise<void> {
+  return invoke('discard_buffer_snapshot', { bufferId });
+}
+
+export function listRecoverableBuffers(): Promise<RecoverableBuffer[]> {
+  return invoke<RecoverableBuffer[]>('list_recoverable_buffers');
+}
+
+export function getBufferSnapshot(bufferId: string): Promise<BufferSnapshot> {
+  return invoke<BufferSnapshot>('get_buffer_snapshot', { bufferId });
+}
+
+/** Tell the backend the app may quit now. */
+export function hotExitReady(): Promise<void> {
+  return invoke('hot_exit_ready');
+}
+
+/** The app is about to quit; snapshot dirty buffers, then call hotExitReady. */
+export function onHotExit(handler: () => void): Promise<UnlistenFn> {
+  return listen('hot-exit', () => handler());
+}
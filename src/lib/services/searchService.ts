@@ -0,0 +1,79 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+
+// ─── TypeScript interfaces ────────────────────────────────────────────────────
+
+export interface SearchOptions {
+  query: string;
+  is_regex?: boolean;
+  case_sensitive?: boolean;
+  whole_word?: boolean;
+  include_globs?: string[];
+  exclude_globs?: string[];
+  /** Skip files matched by .gitignore (default true) */
+  respect_gitignore?: boolean;
+  include_hidden?: boolean;
+  /** Default 10000 */
+  max_results?: number;
+}
+
+export interface SearchMatch {
+  /** 1-based */
+  line: number;
+  /** 1-based, in JavaScript string units */
+  column: number;
+  length: number;
+  /** Byte offset in the file; pass back to replaceInFiles */
+  offset: number;
+  match_text: string;
+  line_text: string;
+}
+
+export interface FileMatches {
+  search_id: string;
+  path: string;
+  matches: SearchMatch[];
+}
+
+export interface SearchSummary {
+  search_id: string;
+  files_searched: number;
+  files_matched: number;
+  match_count: number;
+  truncated: boolean;
+  cancelled: boolean;
+}
+
+export interface ReplaceTarget {
+  path: string;
+  offsets: number[];
+}
+
+export interface ReplaceResult {
+  files: { path: string; replacements: number }[];
+  total: number;
+}
+
+// ─── Commands ─────────────────────────────────────────────────────────────────
+
+/** Resolves when the search ends; matches arrive through onSearchResults. */
+export function findInFiles(root: string, options: SearchOptions, searchId: string): Promise<SearchSummary> {
+  return invoke<SearchSummary>('find_in_files', { root, options, searchId });
+}
+
+export function cancelSearch(searchId: string): Promise<void> {
+  return invoke('cancel_search', { searchId });
+}
+
+/** Pass the same options used for the search. Nothing is written if any file changed. */
+export function replaceInFiles(
+  options: SearchOptions,
+  replacement: string,
+  targets: ReplaceTarget[],
+): Promise<ReplaceResult> {
+  return invoke<ReplaceResult>('replace_in_files', { options, replacement, targets });
+}
+
+export function onSearchResults(handler: (results: FileMatches) => void): Promise<UnlistenFn> {
+  return listen<FileMatches>('search-results', event => handler(event.payload));
+}
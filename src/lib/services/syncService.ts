@@ -0,0 +1,118 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+
+// ─── TypeScript interfaces ────────────────────────────────────────────────────
+
+export type SyncMode = 'mirror' | 'two_way';
+
+export interface SyncProfile {
+  id: string;
+  name: string;
+  /** Source folder for mirrors */
+  left_path: string;
+  right_path: string;
+  mode: SyncMode;
+  include_globs: string[];
+  exclude_globs: string[];
+  propagate_deletions: boolean;
+  /** Compare contents when size matches but timestamps differ */
+  compare_hash: boolean;
+  last_run_at: string | null;
+  created_at: string;
+  updated_at: string;
+}
+
+export type SyncProfileInput = Pick<
+  SyncProfile,
+  | 'name'
+  | 'left_path'
+  | 'right_path'
+  | 'mode'
+  | 'include_globs'
+  | 'exclude_globs'
+  | 'propagate_deletions'
+  | 'compare_hash'
+>;
+
+export type SyncActionKind = 'copy_to_right' | 'copy_to_left' | 'delete_left' | 'delete_right' | 'conflict';
+
+export interface SyncAction {
+  kind: SyncActionKind;
+  /** Relative to both folders, `/` separated */
+  path: string;
+  reason: string;
+  size: number;
+}
+
+export interface SyncPlan {
+  actions: SyncAction[];
+  bytes_to_left: number;
+  bytes_to_right: number;
+  conflicts: number;
+  unchanged: number;
+  /** Set when the deletions look like an emptied or missing folder; starting needs `confirmDeletions` */
+  deletion_warning: string | null;
+}
+
+export interface SyncFailure {
+  path: string;
+  message: string;
+}
+
+export type SyncStatus = 'planning' | 'running' | 'completed' | 'failed' | 'cancelled';
+
+export interface SyncProgress {
+  job_id: string;
+  profile_id: string;
+  status: SyncStatus;
+  processed: number;
+  total: number;
+  bytes_done: number;
+  bytes_total: number;
+  current_path: string | null;
+  conflicts: number;
+  failures: SyncFailure[];
+  error: string | null;
+}
+
+// ─── Profiles ─────────────────────────────────────────────────────────────────
+
+export function listSyncProfiles(): Promise<SyncProfile[]> {
+  return invoke<SyncProfile[]>('list_sync_profiles');
+}
+
+export function createSyncProfile(profile: SyncProfileInput): Promise<SyncProfile> {
+  return invoke<SyncProfile>('create_sync_profile', { profile });
+}
+
+export function updateSyncProfile(id: string, profile: SyncProfileInput): Promise<SyncProfile> {
+  return invoke<SyncProfile>('update_sync_profile', { id, profile });
+}
+
+export function deleteSyncProfile(id: string): Promise<void> {
+  return invoke('delete_sync_profile', { id });
+}
+
+// ─── Jobs ─────────────────────────────────────────────────────────────────────
+
+/** Dry run: the actions a sync would take right now. */
+export function planSync(profileId: string): Promise<SyncPlan> {
+  return invoke<SyncPlan>('plan_sync', { profileId });
+}
+
+/**
+ * Start a sync in the background; returns the job id. Fails while the
+ * profile is already running, or when the plan has a `deletion_warning`
+ * that isn't confirmed.
+ */
+export function startSync(profileId: string, confirmDeletions = false): Promise<string> {
+  return invoke<string>('start_sync', { profileId, confirmDeletions });
+}
+
+export function cancelSync(jobId: string): Promise<void> {
+  return invoke('cancel_sync', { jobId });
+}
+
+export function onSyncProgress(handler: (progress: SyncProgress) => void): Promise<UnlistenFn> {
+  return listen<SyncProgress>('sync-progress', event => handler(event.payload));
+}
@@ -0,0 +1,93 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+
+// ─── TypeScript interfaces ────────────────────────────────────────────────────
+
+export interface Workspace {
+  id: string;
+  name: string;
+  roots: string[];
+  created_at: string;
+  updated_at: string;
+  last_opened_at: string | null;
+}
+
+export type WorkspaceInput = Pick<Workspace, 'name' | 'roots'>;
+
+export interface WorkspaceTab {
+  path: string;
+  /** 1-based */
+  cursor_line: number;
+  /** 1-based */
+  cursor_column: number;
+  scroll_top: number;
+  is_active: boolean;
+}
+
+export interface WorkspaceSession {
+  workspace: Workspace;
+  tabs: WorkspaceTab[];
+}
+
+export interface IndexStatus {
+  workspace_id: string;
+  /** The initial walk is still running */
+  indexing: boolean;
+  file_count: number;
+  truncated: boolean;
+}
+
+export interface FileMatch {
+  path: string;
+  rel_path: string;
+  root: string;
+  score: number;
+  /** Char positions in rel_path matched by the query */
+  positions: number[];
+}
+
+// ─── Commands ─────────────────────────────────────────────────────────────────
+
+export function listWorkspaces(): Promise<Workspace[]> {
+  return invoke<Workspace[]>('list_workspaces');
+}
+
+export function createWorkspace(workspace: WorkspaceInput): Promise<Workspace> {
+  return invoke<Workspace>('create_workspace', { workspace });
+}
+
+export function updateWorkspace(id: string, workspace: WorkspaceInput): Promise<Workspace> {
+  return invoke<Workspace>('update_workspace', { id, workspace });
+}
+
+export function deleteWorkspace(id: string): Promise<void> {
+  return invoke('delete_workspace', { id });
+}
+
+export function openWorkspace(id: string): Promise<WorkspaceSession> {
+  return invoke<WorkspaceSession>('open_workspace', { id });
+}
+
+export function closeWorkspace(id: string): Promise<void> {
+  return invoke('close_workspace', { id });
+}
+
+export function getIndexStatus(id: string): Promise<IndexStatus> {
+  return invoke<IndexStatus>('get_workspace_index_status', { id });
+}
+
+export function findFiles(id: string, query: string, limit = 50): Promise<FileMatch[]> {
+  return invoke<FileMatch[]>('find_workspace_files', { id, query, limit });
+}
+
+export function getTabs(id: string): Promise<WorkspaceTab[]> {
+  return invoke<WorkspaceTab[]>('get_workspace_tabs', { id });
+}
+
+export function saveTabs(id: string, tabs: WorkspaceTab[]): Promise<void> {
+  return invoke('save_workspace_tabs', { id, tabs });
+}
+
+export function onIndexStatus(handler: (status: IndexStatus) => void): Promise<UnlistenFn> {
+  return listen<IndexStatus>('workspace-index', event => handler(event.payload));
+}
@@ -1,4 +1,6 @@
 import { writable, derived, get } from 'svelte/store';
+import { getLastLocation, recentFolders } from './locationStore';
+import { settings } from './settingsStore';
 
 interface FileTab {
     id: string;
@@ -14,22 +16,31 @@ interface FileTabState {
     activeId: string;
 }
 
+let lastPath = '';
+
 function saveLastPath(path: string) {
-    localStorage.setItem('lastFilePath', path);
+    lastPath = path;
+    recentFolders.add(path).catch(err => console.error('Failed to record folder visit:', err));
 }
 
-function loadLastPath(): string {
-    return localStorage.getItem('lastFilePath') || 'C:\\';
+/** Where new tabs open: the configured start path, else the last visited folder. */
+async function resolveStartPath(): Promise<string> {
+    const s = get(settings);
+    if (!s.fileRememberLastPath && s.fileDefaultStartPath) {
+        return s.fileDefaultStartPath;
+    }
+    return getLastLocation();
 }
 
 function createFileTabStore() {
-    const initialPath = loadLastPath();
+    // The start path comes from the backend, so the first tab is filled in
+    // once it resolves; consumers skip tabs with an empty path.
     const initialTab: FileTab = {
         id: crypto.randomUUID(),
-        path: initialPath,
-        name: initialPath.split(/[\\/]/).filter(p => p).pop() || 'Drive',
-        history: [initialPath],
-        historyIndex: 0,
+        path: '',
+        name: 'Loading',
+        history: [],
+        historyIndex: -1,
         refreshCounter: 0
     };
 
@@ -38,11 +49,27 @@ function createFileTabStore() {
         activeId: initialTab.id
     });
 
+    resolveStartPath()
+        .then(startPath => {
+            lastPath = startPath;
+            update(state => ({
+                ...state,
+                tabs: state.tabs.map(tab => tab.path ? tab : {
+                    ...tab,
+                    path: startPath,
+                    name: startPath.split(/[\\/]/).filter(p => p).pop() || 'Drive',
+                    history: [startPath],
+                    historyIndex: 0
+                })
+            }));
+        })
+        .catch(err => console.error('Failed to resolve start path:', err));
+
     return {
         subscribe,
 
         addTab: (path?: string) => update(state => {
-            const newPath = path || loadLastPath();
+            const newPath = path || lastPath;
             const newTab: FileTab = {
                 id: crypto.randomUUID(),
                 path: newPath,
@@ -0,0 +1,101 @@
+import { writable, get } from 'svelte/store';
+import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+
+// ─── TypeScript interfaces ────────────────────────────────────────────────────
+
+export type GitFileState =
+  | 'ignored'
+  | 'untracked'
+  | 'modified'
+  | 'added'
+  | 'deleted'
+  | 'renamed'
+  | 'copied'
+  | 'type_changed'
+  | 'conflicted';
+
+export interface RepoInfo {
+  root: string;
+  /** null when HEAD is detached */
+  branch: string | null;
+  head: string | null;
+  upstream: string | null;
+  ahead: number;
+  behind: number;
+}
+
+export interface GitFileStatus {
+  path: string;
+  rel_path: string;
+  state: GitFileState;
+  staged: boolean;
+  unstaged: boolean;
+  orig_path: string | null;
+}
+
+export interface RepoStatus {
+  repo: RepoInfo;
+  files: GitFileStatus[];
+}
+
+export interface EntryGitStatus {
+  path: string;
+  state: GitFileState;
+  staged: boolean;
+  unstaged: boolean;
+}
+
+interface DirectoryGitStatus {
+  repo: RepoInfo | null;
+  entries: EntryGitStatus[];
+}
+
+export interface GitDirectoryState {
+  path: string | null;
+  repo: RepoInfo | null;
+  /** Keyed by absolute path of each child with a status */
+  entries: Map<string, EntryGitStatus>;
+}
+
+// ─── Stores ───────────────────────────────────────────────────────────────────
+
+/** Git status of the folder shown in the file manager. */
+export const gitDirectory = writable<GitDirectoryState>({ path: null, repo: null, entries: new Map() });
+
+// ─── Functions ────────────────────────────────────────────────────────────────
+
+export async function loadGitDirectoryStatus(path: string): Promise<void> {
+  try {
+    const result = await invoke<DirectoryGitStatus>('get_git_directory_status', { path });
+    gitDirectory.set({
+      path,
+      repo: result.repo,
+      entries: new Map(result.entries.map(e => [e.path, e])),
+    });
+  } catch (err) {
+    console.error('Failed to load git status:', err);
+    gitDirectory.set({ path, repo: null, entries: new Map() });
+  }
+}
+
+export function getRepoStatus(path: string): Promise<RepoStatus | null> {
+  return invoke<RepoStatus | null>('get_git_repo_status', { path });
+}
+
+export async function refreshGitStatus(path: string): Promise<void> {
+  await invoke('refresh_git_status', { path });
+  if (get(gitDirectory).path === path) {
+    await loadGitDirectoryStatus(path);
+  }
+}
+
+/** Reload the shown folder's status whenever its repository changes on disk. */
+export function listenGitStatusChanges(): Promise<UnlistenFn> {
+  return listen<string>('git-status-changed', event => {
+    const { path, repo } = get(gitDirectory);
+    if (path && repo && repo.root === event.payload) {
+      loadGitDirectoryStatus(path);
+    }
+  });
+}
@@ -0,0 +1,175 @@
+import { writable } from 'svelte/store';
+import { invoke } from '@tauri-apps/api/core';
+
+// ─── TypeScript interfaces ────────────────────────────────────────────────────
+
+export interface Bookmark {
+  id: string;
+  path: string;
+  name: string;
+  sort_order: number;
+  created_at: string;
+}
+
+export interface RecentLocation {
+  path: string;
+  name: string;
+  is_dir: boolean;
+  access_count: number;
+  first_accessed: string;
+  last_accessed: string;
+  /** Frecency: access count weighted by recency */
+  score: number;
+}
+
+export interface UserDirectory {
+  kind: string;
+  name: string;
+  path: string;
+}
+
+const RECENT_LIMIT = 20;
+
+// ─── Stores ───────────────────────────────────────────────────────────────────
+
+export const bookmarks = writable<Bookmark[]>([]);
+export const userDirectories = writable<UserDirectory[]>([]);
+
+function createRecentStore(kind: 'file' | 'folder') {
+  const { subscribe, set } = writable<RecentLocation[]>([]);
+
+  async function refresh() {
+    try {
+      set(await invoke<RecentLocation[]>('get_recent_locations', { kind, limit: RECENT_LIMIT }));
+    } catch (err) {
+      console.error(`Failed to load recent ${kind}s:`, err);
+    }
+  }
+
+  return {
+    subscribe,
+    refresh,
+
+    add: async (path: string) => {
+      await invoke('record_location_access', { path, isDir: kind === 'folder' });
+      await refresh();
+    },
+
+    remove: async (path: string) => {
+      await invoke('remove_recent_location', { path });
+      await refresh();
+    },
+
+    clear: async () => {
+      await invoke('clear_recent_locations', { kind });
+      set([]);
+    }
+  };
+}
+
+export const recentFiles = createRecentStore('file');
+export const recentFolders = createRecentStore('folder');
+
+// ─── Bookmark functions ───────────────────────────────────────────────────────
+
+export async function loadBookmarks(): Promise<Bookmark[]> {
+  const result = await invoke<Bookmark[]>('get_bookmarks');
+  bookmarks.set(result);
+  return result;
+}
+
+export async function addBookmark(path: string, name?: string): Promise<Bookmark> {
+  const bookmark = await invoke<Bookmark>('add_bookmark', { path, name: name ?? null });
+  bookmarks.update(prev => (prev.some(b => b.id === bookmark.id) ? prev : [...prev, bookmark]));
+  return bookmark;
+}
+
+export async function renameBookmark(id: string, name: string): Promise<Bookmark> {
+  const bookmark = await invoke<Bookmark>('rename_bookmark', { id, name });
+  bookmarks.update(prev => prev.map(b => (b.id === id ? bookmark : b)));
+  return bookmark;
+}
+
+export async function removeBookmark(id: string): Promise<void> {
+  await invoke('remove_bookmark', { id });
+  bookmarks.update(prev => prev.filter(b => b.id !== id));
+}
+
+export async function reorderBookmarks(ids: string[]): Promise<void> {
+  await invoke('reorder_bookmarks', { ids });
+  await loadBookmarks();
+}
+
+// ─── Locations ────────────────────────────────────────────────────────────────
+
+export async function loadUserDirectories(): Promise<UserDirectory[]> {
+  const result = await invoke<UserDirectory[]>('get_user_directories');
+  userDirectories.set(result);
+  return result;
+}
+
+export function getLocationSuggestions(
+  query: string,
+  kind?: 'file' | 'folder',
+  limit = 10
+): Promise<RecentLocation[]> {
+  return invoke<RecentLocation[]>('get_location_suggestions', { query, kind: kind ?? null, limit });
+}
+
+/**
+ * Last visited folder that still exists, or the home directory. Waits for
+ * the localStorage import so an upgraded install starts where it left off.
+ */
+export async function getLastLocation(): Promise<string> {
+  await migrated();
+  return invoke<string>('get_last_location');
+}
+
+/**
+ * One-time import of the history that used to live in localStorage, so
+ * upgrading doesn't lose the recent files list or the last visited folder.
+ */
+async function migrateLocalStorage() {
+  if (typeof localStorage === 'undefined') return;
+  const stored = localStorage.getItem('recent-files');
+  if (stored) {
+    try {
+      const files: { path: string }[] = JSON.parse(stored);
+      // Oldest first so the most recent ends up with the latest timestamp
+      for (const file of [...files].reverse()) {
+        await invoke('record_location_access', { path: file.path, isDir: false });
+      }
+    } catch (err) {
+      console.error('Failed to migrate recent files:', err);
+    }
+  }
+  // The file browser's last folder; recorded last so it becomes the last location
+  const lastFolder = localStorage.getItem('lastFilePath');
+  if (lastFolder) {
+    try {
+      await invoke('record_location_access', { path: lastFolder, isDir: true });
+    } catch (err) {
+      console.error('Failed to migrate last folder:', err);
+    }
+  }
+  localStorage.removeItem('recent-files');
+  localStorage.removeItem('lastFilePath');
+}
+
+let migration: Promise<void> | undefined;
+
+/** The localStorage import, started by whichever caller needs it first. */
+function migrated(): Promise<void> {
+  migration ??= migrateLocalStorage();
+  return migration;
+}
+
+export async function initLocations(): Promise<void> {
+  await migrated();
+  await Promise.all([
+    recentFiles.refresh(),
+    recentFolders.refresh(),
+    loadBookmarks().catch(err => console.error('Failed to load bookmarks:', err)),
+    loadUserDirectories().catch(err => console.error('Failed to load user directories:', err)),
+  ]);
+}
@@ -0,0 +1,125 @@
+import { writable, get } from 'svelte/store';
+import type { UnlistenFn } from '@tauri-apps/api/event';
+import { editorTabs, activeEditorTabId, type EditorTab } from './editorStore';
+import {
+  saveBufferSnapshot,
+  discardBufferSnapshot,
+  listRecoverableBuffers,
+  getBufferSnapshot,
+  hotExitReady,
+  onHotExit,
+  type RecoverableBuffer,
+} from '$lib/services/recoveryService';
+
+// ─── Stores ───────────────────────────────────────────────────────────────────
+
+/** Unsaved buffers left behind by the previous session. */
+export const recoverableBuffers = writable<RecoverableBuffer[]>([]);
+
+// ─── Internal state ───────────────────────────────────────────────────────────
+
+const SNAPSHOT_DELAY_MS = 2000;
+
+/** Content of the last snapshot written for each tab id. */
+const snapshotted = new Map<string, string>();
+
+let snapshotTimer: ReturnType<typeof setTimeout> | undefined;
+let unsubscribeTabs: (() => void) | null = null;
+let unlistenExit: UnlistenFn | null = null;
+let flushing: Promise<void> = Promise.resolve();
+
+async function writeSnapshots(): Promise<void> {
+  const tabs = get(editorTabs);
+
+  for (const tab of tabs) {
+    if (!tab.isDirty || snapshotted.get(tab.id) === tab.content) continue;
+    try {
+      await saveBufferSnapshot({ buffer_id: tab.id, name: tab.name, path: tab.path, content: tab.content });
+      snapshotted.set(tab.id, tab.content);
+    } catch (err) {
+      console.error('Failed to snapshot buffer:', err);
+    }
+  }
+
+  // Saved or closed tabs no longer need their snapshot
+  const dirty = new Set(tabs.filter(t => t.isDirty).map(t => t.id));
+  for (const id of [...snapshotted.keys()]) {
+    if (dirty.has(id)) continue;
+    snapshotted.delete(id);
+    await discardBufferSnapshot(id).catch(err => console.error('Failed to discard snapshot:', err));
+  }
+}
+
+/** Run snapshot writes one at a time so a slow write never races a newer one. */
+function flush(): Promise<void> {
+  flushing = flushing.then(writeSnapshots);
+  return flushing;
+}
+
+// ─── Functions ────────────────────────────────────────────────────────────────
+
+/** Load leftovers from the last session and start snapshotting dirty tabs. */
+export async function startBufferRecovery(): Promise<void> {
+  recoverableBuffers.set(await listRecoverableBuffers().catch(() => []));
+
+  unsubscribeTabs = editorTabs.subscribe(() => {
+    clearTimeout(snapshotTimer);
+    snapshotTimer = setTimeout(flush, SNAPSHOT_DELAY_MS);
+  });
+
+  unlistenExit = await onHotExit(async () => {
+    clearTimeout(snapshotTimer);
+    try {
+      await flush();
+    } finally {
+      await hotExitReady();
+    }
+  });
+}
+
+export function stopBufferRecovery() {
+  clearTimeout(snapshotTimer);
+  unsubscribeTabs?.();
+  unlistenExit?.();
+  unsubscribeTabs = null;
+  unlistenExit = null;
+}
+
+/** Reopen recovered buffers as dirty tabs (all of them when `ids` is omitted). */
+export async function restoreBuffers(ids?: string[]): Promise<void> {
+  const wanted = get(recoverableBuffers).filter(b => !ids || ids.includes(b.buffer_id));
+  const restored: EditorTab[] = [];
+
+  for (const buffer of wanted) {
+    try {
+      const snapshot = await getBufferSnapshot(buffer.buffer_id);
+      // Keep the id, so further edits update the same snapshot
+      snapshotted.set(snapshot.buffer_id, snapshot.content);
+      restored.push({
+        id: snapshot.buffer_id,
+        name: snapshot.name,
+        path: snapshot.path,
+        content: snapshot.content,
+        isDirty: true,
+      });
+    } catch (err) {
+      console.error('Failed to restore buffer:', err);
+    }
+  }
+
+  if (restored.length > 0) {
+    // Blank untitled tabs are replaced rather than kept alongside
+    editorTabs.update(tabs => [...tabs.filter(t => t.path !== null || t.isDirty || t.content), ...restored]);
+    activeEditorTabId.set(restored[0].id);
+  }
+  recoverableBuffers.update(list => list.filter(b => !wanted.includes(b)));
+}
+
+/** Throw away recovered buffers (all of them when `ids` is omitted). */
+export async function discardRecoveredBuffers(ids?: string[]): Promise<void> {
+  const unwanted = get(recoverableBuffers).filter(b => !ids || ids.includes(b.buffer_id));
+  for (const buffer of unwanted) {
+    await discardBufferSnapshot(buffer.buffer_id).catch(err => console.error('Failed to discard snapshot:', err));
+  }
+  recoverableBuffers.update(list => list.filter(b => !unwanted.includes(b)));
+}
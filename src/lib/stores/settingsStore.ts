@@ -1,5 +1,6 @@
 import { writable } from 'svelte/store';
 import type { ViewMode, SortField, SortDirection } from './viewModeStore';
+import type { FormatterConfig } from '$lib/services/formatService';
 
 export interface NavItem {
   id: string;
@@ -15,6 +16,7 @@ interface Settings {
 
   // Terminal Settings
   termFontSize: number;
+  termFontFamily: string;
   termCursorStyle: 'block' | 'underline' | 'bar';
   termShellPath: string;
   
@@ -27,10 +29,16 @@ interface Settings {
   editorShowMinimap: boolean;
   editorAutoSave: boolean;
   editorAutoSaveInterval: number;
+  editorAutoSaveOnFocusLoss: boolean;
+  editorFormatOnSave: boolean;
+  /** Formatter overrides keyed by file extension (e.g. 'ts', 'py') */
+  editorFormatters: Record<string, FormatterConfig>;
   
   // File Manager Settings
   fileShowHidden: boolean;
   fileConfirmDelete: boolean;
+  /** Legacy: allowed folders now live in the backend; migrated once at startup, then cleared */
+  fileAllowedRoots: string;
   fileDefaultView: ViewMode;
   fileDefaultSortField: SortField;
   fileDefaultSortDirection: SortDirection;
@@ -90,6 +98,7 @@ const defaultSettings: Settings = {
 
   // Terminal
   termFontSize: 14,
+  termFontFamily: 'Fira Code',
   termCursorStyle: 'block',
   termShellPath: 'C:\\Windows\\System32\\WindowsPowerShell\\v1.0\\powershell.exe',
   
@@ -102,10 +111,14 @@ const defaultSettings: Settings = {
   editorShowMinimap: true,
   editorAutoSave: true,
   editorAutoSaveInterval: 3,
+  editorAutoSaveOnFocusLoss: false,
+  editorFormatOnSave: false,
+  editorFormatters: {},
   
   // File Manager
   fileShowHidden: false,
   fileConfirmDelete: true,
+  fileAllowedRoots: '',
   fileDefaultView: 'grid',
   fileDefaultSortField: 'name',
   fileDefaultSortDirection: 'asc',
@@ -115,9 +128,9 @@ const defaultSettings: Settings = {
   fileGridIconSize: 48,
   fileShowFolderSize: false,
   fileFolderSizeThreshold: 1000,
-  fileDefaultStartPath: 'C:\\',
+  fileDefaultStartPath: '',
   fileRememberLastPath: true,
-  fileLastPath: 'C:\\',
+  fileLastPath: '',
   
   // Thumbnails & Previews
   fileThumbnailSize: 48,
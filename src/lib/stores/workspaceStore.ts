@@ -0,0 +1,141 @@
+import { writable, get } from 'svelte/store';
+import { invoke } from '@tauri-apps/api/core';
+import type { UnlistenFn } from '@tauri-apps/api/event';
+import { editorTabs, activeEditorTabId, type EditorTab } from './editorStore';
+import { textToEditorHtml } from '$lib/utils/editorContent';
+import {
+  openWorkspace,
+  closeWorkspace,
+  getIndexStatus,
+  saveTabs,
+  onIndexStatus,
+  type Workspace,
+  type IndexStatus,
+  type WorkspaceTab,
+} from '$lib/services/workspaceService';
+
+// ─── Stores ───────────────────────────────────────────────────────────────────
+
+/** Workspace the editor is working in, if any. */
+export const currentWorkspace = writable<Workspace | null>(null);
+
+/** File index state of the current workspace. */
+export const workspaceIndex = writable<IndexStatus | null>(null);
+
+// ─── Internal state ───────────────────────────────────────────────────────────
+
+/** Last cursor position per editor tab id; not reactive, only persisted. */
+const cursors = new Map<string, { line: number; column: number }>();
+
+let unlistenIndex: UnlistenFn | null = null;
+let unsubscribers: (() => void)[] = [];
+let saveTimer: ReturnType<typeof setTimeout> | undefined;
+
+const SAVE_DELAY_MS = 1000;
+
+function fileName(path: string): string {
+  return path.split(/[\\/]/).pop() || 'Untitled';
+}
+
+function currentTabs(): WorkspaceTab[] {
+  const activeId = get(activeEditorTabId);
+  return get(editorTabs)
+    .filter((tab): tab is EditorTab & { path: string } => tab.path !== null)
+    .map(tab => ({
+      path: tab.path,
+      cursor_line: cursors.get(tab.id)?.line ?? 1,
+      cursor_column: cursors.get(tab.id)?.column ?? 1,
+      scroll_top: 0,
+      is_active: tab.id === activeId,
+    }));
+}
+
+function scheduleSave() {
+  const workspace = get(currentWorkspace);
+  if (!workspace) return;
+  clearTimeout(saveTimer);
+  saveTimer = setTimeout(() => {
+    saveTabs(workspace.id, currentTabs()).catch(err => console.error('Failed to save workspace tabs:', err));
+  }, SAVE_DELAY_MS);
+}
+
+// ─── Functions ────────────────────────────────────────────────────────────────
+
+export function recordCursor(tabId: string, line: number, column: number) {
+  cursors.set(tabId, { line, column });
+  scheduleSave();
+}
+
+export function savedCursor(tabId: string): { line: number; column: number } | undefined {
+  return cursors.get(tabId);
+}
+
+/** Open a workspace, restoring its tabs into the editor next to any unsaved ones. */
+export async function enterWorkspace(id: string): Promise<void> {
+  await leaveWorkspace();
+  const session = await openWorkspace(id);
+
+  const restored: EditorTab[] = [];
+  let activeId: string | null = null;
+  for (const saved of session.tabs) {
+    let content: string;
+    try {
+      content = await invoke<string>('read_file', { path: saved.path });
+    } catch {
+      continue; // Deleted or moved since the workspace was last open
+    }
+    const tab: EditorTab = {
+      id: crypto.randomUUID(),
+      name: fileName(saved.path),
+      path: saved.path,
+      content: textToEditorHtml(content),
+      isDirty: false,
+    };
+    cursors.set(tab.id, { line: saved.cursor_line, column: saved.cursor_column });
+    if (saved.is_active) activeId = tab.id;
+    restored.push(tab);
+  }
+  if (restored.length > 0) {
+    // Unsaved tabs stay open, and win over a restored copy of the same file
+    const dirty = get(editorTabs).filter(tab => tab.isDirty);
+    const dirtyPaths = new Set(dirty.map(tab => tab.path).filter(Boolean));
+    const tabs = [...dirty, ...restored.filter(tab => !dirtyPaths.has(tab.path))];
+    editorTabs.set(tabs);
+    activeEditorTabId.set(tabs.find(tab => tab.id === activeId)?.id ?? tabs[0].id);
+  }
+
+  currentWorkspace.set(session.workspace);
+  unlistenIndex = await onIndexStatus(status => {
+    if (status.workspace_id === id) workspaceIndex.set(status);
+  });
+  workspaceIndex.set(await getIndexStatus(id).catch(() => null));
+
+  // Subscribing calls back immediately; skip that to avoid a pointless save
+  let ready = false;
+  unsubscribers = [
+    editorTabs.subscribe(() => ready && scheduleSave()),
+    activeEditorTabId.subscribe(() => ready && scheduleSave()),
+  ];
+  ready = true;
+}
+
+/** Save the current workspace's tabs and stop indexing it. */
+export async function leaveWorkspace(): Promise<void> {
+  const workspace = get(currentWorkspace);
+  unsubscribers.forEach(fn => fn());
+  unsubscribers = [];
+  unlistenIndex?.();
+  unlistenIndex = null;
+  clearTimeout(saveTimer);
+  if (!workspace) return;
+
+  try {
+    await saveTabs(workspace.id, currentTabs());
+  } catch (err) {
+    console.error('Failed to save workspace tabs:', err);
+  }
+  await closeWorkspace(workspace.id).catch(() => {});
+  cursors.clear();
+  currentWorkspace.set(null);
+  workspaceIndex.set(null);
+}
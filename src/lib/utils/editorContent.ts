@@ -0,0 +1,5 @@
+/** Editor HTML for plain file text: one paragraph per line, markup escaped. */
+export function textToEditorHtml(text: string): string {
+  const escaped = text.replace(/&/g, '&amp;').replace(/</g, '&lt;').replace(/>/g, '&gt;');
+  return `<p>${escaped.replace(/\n/g, '</p><p>')}</p>`;
+}
@@ -0,0 +1,20 @@
+/**
+ * Error returned by the file commands. Policy denials carry a `kind` so
+ * callers can react to them specifically; every kind has a `message`.
+ */
+export interface FsError {
+  kind: 'invalid_path' | 'not_found' | 'outside_allowed_roots' | 'protected_path' | 'io';
+  message: string;
+  path?: string;
+}
+
+export function isFsError(err: unknown): err is FsError {
+  return typeof err === 'object' && err !== null && 'kind' in err && 'message' in err;
+}
+
+/** Human-readable text for an error thrown by `invoke`. */
+export function errorMessage(err: unknown): string {
+  if (isFsError(err)) return err.message;
+  if (err instanceof Error) return err.message;
+  return String(err);
+}